@@ -133,6 +133,7 @@ require('../target/wasm32-unknown-unknown/release/cb_browser_ui').then(cbRustBro
                                     <Utils.RenderContext.Provider value={layers}>
 
                                         <Households.Shapes state={this.state} setState={this.boundSetState} />
+                                        <Debug.Shapes state={this.state} setState={this.boundSetState} />
 
                                         <Planning.ShapesAndLayers state={this.state} setState={this.boundSetState} />
 
@@ -1,6 +1,6 @@
 import React from 'react';
 import { Button, InputNumber } from 'antd';
-import { fmtId } from '../browser_utils/Utils';
+import { fmtId, Interactive3DShape } from '../browser_utils/Utils';
 
 export const initialState = {
     show: false,
@@ -12,11 +12,32 @@ export const initialState = {
     spawnCarsSettings: {
         triesPerLane: 50
     },
+    checkRoutesSettings: {
+        nPairs: 200
+    },
+    roadworksSettings: {
+        radius: 20,
+        startsInMinutes: 0,
+        lastsMinutes: 60
+    },
+    // null, "close" or "reopen": what clicking on the map does to the lanes there
+    roadworksMode: null,
+    detectorSettings: {
+        radius: 10
+    },
+    // null, "add" or "remove": what clicking on the map does to detectors there
+    detectorsMode: null,
+    // fetched from the simulation when the debug window opens
+    driverDistributions: null,
     logLastEntry: 0,
     logTextStart: 0,
     logFirstEntry: 0,
     logEntries: [],
-    logText: []
+    logText: [],
+    routing: null,
+    trips: null,
+    failedTrips: null,
+    detectors: null
 }
 
 export const settingsSpec = {
@@ -30,7 +51,13 @@ export function Windows(props) {
 
     if (state.debug.show) {
         if (!refreshInterval) {
-            refreshInterval = setInterval(() => cbRustBrowser.get_newest_log_messages(), 300);
+            cbRustBrowser.get_driver_distributions();
+            refreshInterval = setInterval(() => {
+                cbRustBrowser.get_newest_log_messages();
+                cbRustBrowser.get_routing_diagnostics();
+                cbRustBrowser.get_failed_trips();
+                cbRustBrowser.get_detector_measurements();
+            }, 300);
         }
     } else {
         if (refreshInterval) {
@@ -102,6 +129,18 @@ export function Windows(props) {
                                 state.debug.spawnCarsSettings.triesPerLane
                             )}>Spawn cars</Button>
                 </div>
+                <div key="routeChecking">
+                    Route pairs
+                <InputNumber
+                        value={state.debug.checkRoutesSettings.nPairs}
+                        onChange={(nPairs) => setState(oldState => update(oldState, {
+                            debug: { checkRoutesSettings: { nPairs: { $set: nPairs } } }
+                        }))}
+                        min={1} /> <Button
+                            onClick={() => cbRustBrowser.check_routes(
+                                state.debug.checkRoutesSettings.nPairs
+                            )}>Check routes</Button>
+                </div>
                 <div key="rendering">
                     <Button
                         onClick={() => setState(
@@ -109,6 +148,127 @@ export function Windows(props) {
                         )}>{state.rendering.enabled ? "Disable rendering" : "Enable rendering"}</Button>
                 </div>
             </details>
+            <details>
+                <summary>Roadworks</summary>
+                <div>
+                    Radius
+                <InputNumber
+                        value={state.debug.roadworksSettings.radius}
+                        onChange={(radius) => setState(oldState => update(oldState, {
+                            debug: { roadworksSettings: { radius: { $set: radius } } }
+                        }))}
+                        step={5.0}
+                        min={1} />
+                    Starts in (min)
+                <InputNumber
+                        value={state.debug.roadworksSettings.startsInMinutes}
+                        onChange={(startsInMinutes) => setState(oldState => update(oldState, {
+                            debug: { roadworksSettings: { startsInMinutes: { $set: startsInMinutes } } }
+                        }))}
+                        min={0} />
+                    Lasts (min, 0 = until reopened)
+                <InputNumber
+                        value={state.debug.roadworksSettings.lastsMinutes}
+                        onChange={(lastsMinutes) => setState(oldState => update(oldState, {
+                            debug: { roadworksSettings: { lastsMinutes: { $set: lastsMinutes } } }
+                        }))}
+                        min={0} />
+                </div>
+                <div>
+                    <Button
+                        type={state.debug.roadworksMode == "close" ? "primary" : "default"}
+                        onClick={() => setState(oldState => update(oldState, {
+                            debug: {
+                                roadworksMode: { $apply: m => m == "close" ? null : "close" },
+                                detectorsMode: { $set: null }
+                            }
+                        }))}>Close lanes by clicking</Button>
+                    <Button
+                        type={state.debug.roadworksMode == "reopen" ? "primary" : "default"}
+                        onClick={() => setState(oldState => update(oldState, {
+                            debug: {
+                                roadworksMode: { $apply: m => m == "reopen" ? null : "reopen" },
+                                detectorsMode: { $set: null }
+                            }
+                        }))}>Reopen lanes by clicking</Button>
+                </div>
+            </details>
+            <details>
+                <summary>Loop Detectors</summary>
+                <div>
+                    Radius
+                <InputNumber
+                        value={state.debug.detectorSettings.radius}
+                        onChange={(radius) => setState(oldState => update(oldState, {
+                            debug: { detectorSettings: { radius: { $set: radius } } }
+                        }))}
+                        step={5.0}
+                        min={1} />
+                </div>
+                <div>
+                    <Button
+                        type={state.debug.detectorsMode == "add" ? "primary" : "default"}
+                        onClick={() => setState(oldState => update(oldState, {
+                            debug: {
+                                detectorsMode: { $apply: m => m == "add" ? null : "add" },
+                                roadworksMode: { $set: null }
+                            }
+                        }))}>Place detectors by clicking</Button>
+                    <Button
+                        type={state.debug.detectorsMode == "remove" ? "primary" : "default"}
+                        onClick={() => setState(oldState => update(oldState, {
+                            debug: {
+                                detectorsMode: { $apply: m => m == "remove" ? null : "remove" },
+                                roadworksMode: { $set: null }
+                            }
+                        }))}>Remove detectors by clicking</Button>
+                </div>
+                <div>
+                    <Button onClick={() => cbRustBrowser.export_detector_measurements()}>Export CSV</Button>
+                    <Button onClick={() => cbRustBrowser.reset_detector_measurements()}>Reset</Button>
+                </div>
+                {state.debug.detectors && state.debug.detectors.length > 0
+                    ? <div className="scrollableLog">{state.debug.detectors.map((measurement, i) =>
+                        <div key={i}>
+                            {fmtId(measurement.lane)} @ {measurement.position.toFixed(1)}m:
+                            {" "}{measurement.flow.toFixed(0)} veh/h,
+                            {" "}{measurement.mean_speed === null ? "-" : (measurement.mean_speed * 3.6).toFixed(0) + " km/h"},
+                            {" "}{(measurement.occupancy * 100).toFixed(0)}% occupied
+                        </div>
+                    )}</div>
+                    : <div>(no detector measurements yet)</div>
+                }
+            </details>
+            <details>
+                <summary>Driver Behaviour</summary>
+                {state.debug.driverDistributions && <div>
+                    {[
+                        ["desired_speed_factor", "Desired speed factor"],
+                        ["time_headway_factor", "Time headway factor"],
+                        ["reaction_delay", "Reaction delay (s)"]
+                    ].map(([parameter, label]) =>
+                        <div key={parameter}>
+                            {label}
+                            {["mean", "std_dev", "min", "max"].map(stat => [
+                                " " + stat + " ",
+                                <InputNumber
+                                    key={stat}
+                                    value={state.debug.driverDistributions[parameter][stat]}
+                                    onChange={(value) => setState(oldState => update(oldState, {
+                                        debug: { driverDistributions: { [parameter]: { [stat]: { $set: value } } } }
+                                    }))}
+                                    step={0.1}
+                                    min={0} />
+                            ])}
+                        </div>
+                    )}
+                    <div>
+                        <Button
+                            onClick={() => cbRustBrowser.set_driver_distributions(state.debug.driverDistributions)}
+                        >Apply to new trips</Button>
+                    </div>
+                </div>}
+            </details>
             <details>
                 <summary>Networking</summary>
                 <div>{Object.keys(state.system.networkingTurns).map(machine =>
@@ -121,6 +281,67 @@ export function Windows(props) {
                     <div>{message}: {state.system.messageStats[message]}</div>
                 )}</div>
             </details>
+            <details>
+                <summary>Routing</summary>
+                {state.debug.routing
+                    ? <div>
+                        <div>{state.debug.routing.converged ? "Converged" : "Converging..."}</div>
+                        <div>Stable links: {state.debug.routing.n_stable} / {state.debug.routing.n_links}</div>
+                        <div>Landmarks: {state.debug.routing.n_landmarks}</div>
+                        <div>Links without landmark: {state.debug.routing.n_without_landmark}</div>
+                    </div>
+                    : <div>(no routing information yet)</div>
+                }
+            </details>
+            <details>
+                <summary>Trips</summary>
+                <div>
+                    <Button onClick={() => cbRustBrowser.get_trip_statistics()}>Refresh</Button>
+                    <Button onClick={() => cbRustBrowser.export_trip_statistics()}>Export CSV</Button>
+                    <Button onClick={() => cbRustBrowser.reset_trip_statistics()}>Reset</Button>
+                </div>
+                {state.debug.trips
+                    ? <div>
+                        <div>Trips: {state.debug.trips.summary.n_trips} ({state.debug.trips.summary.n_successful} successful, {state.debug.trips.summary.n_failed} failed)</div>
+                        <div>Mean duration: {(state.debug.trips.summary.mean_duration / 60).toFixed(1)} min</div>
+                        <div>Mean distance: {state.debug.trips.summary.mean_distance.toFixed(0)} m</div>
+                        <div>Without known location: {state.debug.trips.summary.n_unlocated}</div>
+                        <h3>Durations</h3>
+                        <div>{state.debug.trips.durationHistogram.map((count, i) =>
+                            <div key={i}>{i * 5}{i === state.debug.trips.durationHistogram.length - 1 ? "+" : "-" + (i + 1) * 5} min: {count}</div>
+                        )}</div>
+                        <h3>Busiest zone pairs</h3>
+                        <div className="scrollableLog">{state.debug.trips.odMatrix.slice(0, 20).map((entry, i) =>
+                            <div key={i}>({entry.origin.join(", ")}) → ({entry.destination.join(", ")}): {entry.n_trips} trips, {entry.n_failed} failed, {(entry.mean_duration / 60).toFixed(1)} min</div>
+                        )}</div>
+                    </div>
+                    : <div>(no trip statistics yet)</div>
+                }
+            </details>
+            <details>
+                <summary>Failed Trips</summary>
+                {state.debug.failedTrips
+                    ? <div>
+                        <div>
+                            <Button
+                                onClick={() => cbRustBrowser.set_failed_trip_reporting(!state.debug.failedTrips.enabled)}
+                            >{state.debug.failedTrips.enabled ? "Stop reporting" : "Start reporting"}</Button>
+                            <Button onClick={() => cbRustBrowser.clear_failed_trips()}>Clear</Button>
+                        </div>
+                        <div>Failed trips: {state.debug.failedTrips.nFailures}</div>
+                        <div className="scrollableLog">{state.debug.failedTrips.groups.map((group, i) =>
+                            <div key={i}>
+                                {group.n_failures}x {group.fate} at {fmtId(group.location)}
+                                {" "}(latest: {fmtId(group.latest.rough_source)} → {fmtId(group.latest.rough_destination)}
+                                {group.latest.source && ", from " + fmtId(group.latest.source.location.link) + " @ " + group.latest.source.offset.toFixed(1)}
+                                {group.latest.destination && ", to " + fmtId(group.latest.destination.location.link) + " @ " + group.latest.destination.offset.toFixed(1)}
+                                {group.latest.last_lane && ", last on " + fmtId(group.latest.last_lane)})
+                            </div>
+                        )}</div>
+                    </div>
+                    : <div>(no failed trip information yet)</div>
+                }
+            </details>
             <details>
                 <summary>Simulation Log</summary>
                 <div className="scrollableLog">{state.debug.logEntries.map((entry, i) => {
@@ -135,6 +356,60 @@ export function Windows(props) {
         connectionIssue && <div className="window connection">{connectionIssue}</div>];
 }
 
+export function Shapes(props) {
+    const { state } = props;
+    const roadworksMode = state.debug.roadworksMode;
+    const detectorsMode = state.debug.detectorsMode;
+
+    if (roadworksMode) {
+        return <Interactive3DShape
+            id="roadworksCanvas"
+            key="roadworksCanvas"
+            shape={{
+                type: "everywhere",
+            }}
+            zIndex={3}
+            cursorHover="crosshair"
+            cursorActive="pointer"
+            onEvent={e => {
+                if (e.drag && e.drag.end) {
+                    const position = [e.drag.end[0], e.drag.end[1]];
+                    const settings = state.debug.roadworksSettings;
+                    if (roadworksMode == "close") {
+                        cbRustBrowser.close_lanes(
+                            position, settings.radius, settings.startsInMinutes, settings.lastsMinutes
+                        );
+                    } else {
+                        cbRustBrowser.reopen_lanes(position, settings.radius);
+                    }
+                }
+            }} />
+    } else if (detectorsMode) {
+        return <Interactive3DShape
+            id="detectorsCanvas"
+            key="detectorsCanvas"
+            shape={{
+                type: "everywhere",
+            }}
+            zIndex={3}
+            cursorHover="crosshair"
+            cursorActive="pointer"
+            onEvent={e => {
+                if (e.drag && e.drag.end) {
+                    const position = [e.drag.end[0], e.drag.end[1]];
+                    const radius = state.debug.detectorSettings.radius;
+                    if (detectorsMode == "add") {
+                        cbRustBrowser.add_detectors(position, radius);
+                    } else {
+                        cbRustBrowser.remove_detectors(position, radius);
+                    }
+                }
+            }} />
+    } else {
+        return null;
+    }
+}
+
 export function bindInputs(state, setState) {
     const inputActions = {
         "toggleDebugView": () => setState(oldState => update(oldState, {
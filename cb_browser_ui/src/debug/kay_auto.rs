@@ -72,6 +72,331 @@ impl Into<LogRecipientID> for LogUIID {
     }
 }
 
+impl Actor for RoutingUI {
+    type ID = RoutingUIID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct RoutingUIID {
+    _raw_id: RawID
+}
+
+impl Copy for RoutingUIID {}
+impl Clone for RoutingUIID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for RoutingUIID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RoutingUIID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for RoutingUIID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for RoutingUIID {
+    fn eq(&self, other: &RoutingUIID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for RoutingUIID {}
+
+impl TypedID for RoutingUIID {
+    type Target = RoutingUI;
+
+    fn from_raw(id: RawID) -> Self {
+        RoutingUIID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl RoutingUIID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = RoutingUIID::from_raw(world.allocate_instance_id::<RoutingUI>());
+        let swarm = world.local_broadcast::<RoutingUI>();
+        world.send(swarm, MSG_RoutingUI_spawn(id, ));
+        id
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingUI_spawn(pub RoutingUIID, );
+
+impl Into<RoutingDiagnosticsListenerID> for RoutingUIID {
+    fn into(self) -> RoutingDiagnosticsListenerID {
+        RoutingDiagnosticsListenerID::from_raw(self.as_raw())
+    }
+}
+
+impl Actor for TripStatisticsUI {
+    type ID = TripStatisticsUIID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TripStatisticsUIID {
+    _raw_id: RawID
+}
+
+impl Copy for TripStatisticsUIID {}
+impl Clone for TripStatisticsUIID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TripStatisticsUIID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TripStatisticsUIID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TripStatisticsUIID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TripStatisticsUIID {
+    fn eq(&self, other: &TripStatisticsUIID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TripStatisticsUIID {}
+
+impl TypedID for TripStatisticsUIID {
+    type Target = TripStatisticsUI;
+
+    fn from_raw(id: RawID) -> Self {
+        TripStatisticsUIID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TripStatisticsUIID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = TripStatisticsUIID::from_raw(world.allocate_instance_id::<TripStatisticsUI>());
+        let swarm = world.local_broadcast::<TripStatisticsUI>();
+        world.send(swarm, MSG_TripStatisticsUI_spawn(id, ));
+        id
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatisticsUI_spawn(pub TripStatisticsUIID, );
+
+impl Into<TripStatisticsListenerID> for TripStatisticsUIID {
+    fn into(self) -> TripStatisticsListenerID {
+        TripStatisticsListenerID::from_raw(self.as_raw())
+    }
+}
+
+impl Actor for FailedTripsUI {
+    type ID = FailedTripsUIID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct FailedTripsUIID {
+    _raw_id: RawID
+}
+
+impl Copy for FailedTripsUIID {}
+impl Clone for FailedTripsUIID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for FailedTripsUIID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "FailedTripsUIID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for FailedTripsUIID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for FailedTripsUIID {
+    fn eq(&self, other: &FailedTripsUIID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for FailedTripsUIID {}
+
+impl TypedID for FailedTripsUIID {
+    type Target = FailedTripsUI;
+
+    fn from_raw(id: RawID) -> Self {
+        FailedTripsUIID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl FailedTripsUIID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = FailedTripsUIID::from_raw(world.allocate_instance_id::<FailedTripsUI>());
+        let swarm = world.local_broadcast::<FailedTripsUI>();
+        world.send(swarm, MSG_FailedTripsUI_spawn(id, ));
+        id
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripsUI_spawn(pub FailedTripsUIID, );
+
+impl Into<FailedTripListenerID> for FailedTripsUIID {
+    fn into(self) -> FailedTripListenerID {
+        FailedTripListenerID::from_raw(self.as_raw())
+    }
+}
+
+impl Actor for DetectorsUI {
+    type ID = DetectorsUIID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct DetectorsUIID {
+    _raw_id: RawID
+}
+
+impl Copy for DetectorsUIID {}
+impl Clone for DetectorsUIID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for DetectorsUIID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "DetectorsUIID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for DetectorsUIID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for DetectorsUIID {
+    fn eq(&self, other: &DetectorsUIID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for DetectorsUIID {}
+
+impl TypedID for DetectorsUIID {
+    type Target = DetectorsUI;
+
+    fn from_raw(id: RawID) -> Self {
+        DetectorsUIID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl DetectorsUIID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = DetectorsUIID::from_raw(world.allocate_instance_id::<DetectorsUI>());
+        let swarm = world.local_broadcast::<DetectorsUI>();
+        world.send(swarm, MSG_DetectorsUI_spawn(id, ));
+        id
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DetectorsUI_spawn(pub DetectorsUIID, );
+
+impl Into<TrafficMeasurementsListenerID> for DetectorsUIID {
+    fn into(self) -> TrafficMeasurementsListenerID {
+        TrafficMeasurementsListenerID::from_raw(self.as_raw())
+    }
+}
+
+impl Actor for DriverBehaviourUI {
+    type ID = DriverBehaviourUIID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct DriverBehaviourUIID {
+    _raw_id: RawID
+}
+
+impl Copy for DriverBehaviourUIID {}
+impl Clone for DriverBehaviourUIID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for DriverBehaviourUIID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "DriverBehaviourUIID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for DriverBehaviourUIID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for DriverBehaviourUIID {
+    fn eq(&self, other: &DriverBehaviourUIID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for DriverBehaviourUIID {}
+
+impl TypedID for DriverBehaviourUIID {
+    type Target = DriverBehaviourUI;
+
+    fn from_raw(id: RawID) -> Self {
+        DriverBehaviourUIID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl DriverBehaviourUIID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = DriverBehaviourUIID::from_raw(world.allocate_instance_id::<DriverBehaviourUI>());
+        let swarm = world.local_broadcast::<DriverBehaviourUI>();
+        world.send(swarm, MSG_DriverBehaviourUI_spawn(id, ));
+        id
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverBehaviourUI_spawn(pub DriverBehaviourUIID, );
+
+impl Into<DriverDistributionsRequesterID> for DriverBehaviourUIID {
+    fn into(self) -> DriverDistributionsRequesterID {
+        DriverDistributionsRequesterID::from_raw(self.as_raw())
+    }
+}
+
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 pub fn auto_setup(system: &mut ActorSystem) {
@@ -82,4 +407,39 @@ pub fn auto_setup(system: &mut ActorSystem) {
             LogUI::spawn(id, world)
         }, false
     );
-}
\ No newline at end of file
+
+    RoutingDiagnosticsListenerID::register_implementor::<RoutingUI>(system);
+    system.add_spawner::<RoutingUI, _, _>(
+        |&MSG_RoutingUI_spawn(id, ), world| {
+            RoutingUI::spawn(id, world)
+        }, false
+    );
+
+    TripStatisticsListenerID::register_implementor::<TripStatisticsUI>(system);
+    system.add_spawner::<TripStatisticsUI, _, _>(
+        |&MSG_TripStatisticsUI_spawn(id, ), world| {
+            TripStatisticsUI::spawn(id, world)
+        }, false
+    );
+
+    FailedTripListenerID::register_implementor::<FailedTripsUI>(system);
+    system.add_spawner::<FailedTripsUI, _, _>(
+        |&MSG_FailedTripsUI_spawn(id, ), world| {
+            FailedTripsUI::spawn(id, world)
+        }, false
+    );
+
+    TrafficMeasurementsListenerID::register_implementor::<DetectorsUI>(system);
+    system.add_spawner::<DetectorsUI, _, _>(
+        |&MSG_DetectorsUI_spawn(id, ), world| {
+            DetectorsUI::spawn(id, world)
+        }, false
+    );
+
+    DriverDistributionsRequesterID::register_implementor::<DriverBehaviourUI>(system);
+    system.add_spawner::<DriverBehaviourUI, _, _>(
+        |&MSG_DriverBehaviourUI_spawn(id, ), world| {
+            DriverBehaviourUI::spawn(id, world)
+        }, false
+    );
+}
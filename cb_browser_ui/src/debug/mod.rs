@@ -15,7 +15,7 @@ pub fn plan_grid(project_id: Serde<ProjectID>, n: Serde<isize>, n_lanes: Serde<u
 
     let plan_manager = CBPlanManagerID::global_first(world);
 
-    use ::transport::transport_planning::RoadIntent;
+    use ::transport::transport_planning::{RoadIntent, RoadClass};
     use ::descartes::P2;
 
     for x in -n.0 / 2..n.0 / 2 {
@@ -25,7 +25,7 @@ pub fn plan_grid(project_id: Serde<ProjectID>, n: Serde<isize>, n_lanes: Serde<u
         plan_manager.start_new_gesture(
             project_id.0,
             id,
-            CBGestureIntent::Road(RoadIntent::new(n_lanes.0, n_lanes.0)),
+            CBGestureIntent::Road(RoadIntent::new(n_lanes.0, n_lanes.0, RoadClass::default())),
             p1,
             world,
         );
@@ -39,7 +39,7 @@ pub fn plan_grid(project_id: Serde<ProjectID>, n: Serde<isize>, n_lanes: Serde<u
         plan_manager.start_new_gesture(
             project_id.0,
             id,
-            CBGestureIntent::Road(RoadIntent::new(n_lanes.0, n_lanes.0)),
+            CBGestureIntent::Road(RoadIntent::new(n_lanes.0, n_lanes.0, RoadClass::default())),
             p1,
             world,
         );
@@ -56,6 +56,44 @@ pub fn spawn_cars(tries_per_lane: usize) {
     }
 }
 
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn check_routes(n_pairs: u32) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    ::transport::pathfinding::oracle::PathfindingOracleID::global_first(world)
+        .check(n_pairs, world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn close_lanes(
+    position: Serde<::descartes::P2>,
+    radius: f32,
+    starts_in_minutes: u32,
+    lasts_minutes: u32,
+) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    use ::cb_time::units::Duration;
+    ::transport::roadworks::RoadworksID::spawn(
+        position.0,
+        radius,
+        Duration::from_minutes(starts_in_minutes as usize),
+        if lasts_minutes == 0 {
+            None
+        } else {
+            Some(Duration::from_minutes(lasts_minutes as usize))
+        },
+        world,
+    );
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn reopen_lanes(position: Serde<::descartes::P2>, radius: f32) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    ::transport::roadworks::end_near(position.0, radius, world);
+}
+
 use kay::{World, ActorSystem};
 use compact::{CVec, CString};
 use cb_util::log::{LogID, LogRecipient, LogRecipientID, Entry};
@@ -130,14 +168,322 @@ pub fn get_newest_log_messages() {
     );
 }
 
+use transport::pathfinding::diagnostics::{RoutingDiagnosticsListener,
+RoutingDiagnosticsListenerID, RoutingDiagnosticsID, RoutingSummary, LinkRoutingInfo};
+
+#[derive(Compact, Clone)]
+pub struct RoutingUI {
+    id: RoutingUIID,
+}
+
+impl RoutingUI {
+    pub fn spawn(id: RoutingUIID, _: &mut World) -> RoutingUI {
+        RoutingUI { id }
+    }
+}
+
+impl RoutingDiagnosticsListener for RoutingUI {
+    fn on_routing_diagnostics(
+        &mut self,
+        summary: RoutingSummary,
+        _links: &CVec<LinkRoutingInfo>,
+        _: &mut World,
+    ) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                debug: { routing: {"$set": @{Serde(summary)}} }
+            }));
+        }
+    }
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn get_routing_diagnostics() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    RoutingDiagnosticsID::global_first(world)
+        .get_diagnostics(RoutingUIID::local_first(world).into(), world);
+}
+
+use transport::pathfinding::trip::statistics::{TripStatisticsListener,
+TripStatisticsListenerID, TripStatisticsID, TripSummary, ODEntry};
+
+#[derive(Compact, Clone)]
+pub struct TripStatisticsUI {
+    id: TripStatisticsUIID,
+}
+
+impl TripStatisticsUI {
+    pub fn spawn(id: TripStatisticsUIID, _: &mut World) -> TripStatisticsUI {
+        TripStatisticsUI { id }
+    }
+}
+
+impl TripStatisticsListener for TripStatisticsUI {
+    fn on_trip_statistics(
+        &mut self,
+        summary: TripSummary,
+        od_matrix: &CVec<ODEntry>,
+        duration_histogram: &CVec<u32>,
+        _: &mut World,
+    ) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                debug: { trips: {"$set": {
+                    summary: @{Serde(summary)},
+                    odMatrix: @{Serde(od_matrix)},
+                    durationHistogram: @{Serde(duration_histogram)}
+                }} }
+            }));
+        }
+    }
+
+    fn on_trip_statistics_csv(
+        &mut self,
+        trips: &CString,
+        od_matrix: &CString,
+        duration_histogram: &CString,
+        _: &mut World,
+    ) {
+        let files = vec![
+            (trips.to_string(), "trips.csv"),
+            (od_matrix.to_string(), "od_matrix.csv"),
+            (duration_histogram.to_string(), "trip_durations.csv"),
+        ];
+
+        for (content, file_name) in files {
+            js! {
+                const blob = new Blob([@{content}], {type: "text/csv"});
+                const link = document.createElement("a");
+                link.href = URL.createObjectURL(blob);
+                link.download = @{file_name};
+                link.click();
+                URL.revokeObjectURL(link.href);
+            }
+        }
+    }
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn get_trip_statistics() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TripStatisticsID::global_first(world)
+        .get_statistics(TripStatisticsUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn export_trip_statistics() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TripStatisticsID::global_first(world)
+        .export_csv(TripStatisticsUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn reset_trip_statistics() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TripStatisticsID::global_first(world).reset(world);
+}
+
+use transport::pathfinding::trip::failures::{FailedTripListener, FailedTripListenerID,
+FailedTripReporterID, FailureGroup};
+
+#[derive(Compact, Clone)]
+pub struct FailedTripsUI {
+    id: FailedTripsUIID,
+}
+
+impl FailedTripsUI {
+    pub fn spawn(id: FailedTripsUIID, _: &mut World) -> FailedTripsUI {
+        FailedTripsUI { id }
+    }
+}
+
+impl FailedTripListener for FailedTripsUI {
+    fn on_failed_trips(
+        &mut self,
+        enabled: bool,
+        n_failures: u32,
+        groups: &CVec<FailureGroup>,
+        _: &mut World,
+    ) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                debug: { failedTrips: {"$set": {
+                    enabled: @{enabled},
+                    nFailures: @{n_failures},
+                    groups: @{Serde(groups)}
+                }} }
+            }));
+        }
+    }
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn get_failed_trips() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    FailedTripReporterID::global_first(world)
+        .get_failures(FailedTripsUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn set_failed_trip_reporting(enabled: bool) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    FailedTripReporterID::global_first(world).set_enabled(enabled, world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn clear_failed_trips() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    FailedTripReporterID::global_first(world).clear(world);
+}
+
+use transport::detectors::{TrafficMeasurementsListener, TrafficMeasurementsListenerID,
+TrafficMeasurementsID, DetectorMeasurement};
+
+#[derive(Compact, Clone)]
+pub struct DetectorsUI {
+    id: DetectorsUIID,
+}
+
+impl DetectorsUI {
+    pub fn spawn(id: DetectorsUIID, _: &mut World) -> DetectorsUI {
+        DetectorsUI { id }
+    }
+}
+
+impl TrafficMeasurementsListener for DetectorsUI {
+    fn on_traffic_measurements(&mut self, latest: &CVec<DetectorMeasurement>, _: &mut World) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                debug: { detectors: {"$set": @{Serde(latest)}} }
+            }));
+        }
+    }
+
+    fn on_traffic_measurements_csv(&mut self, measurements: &CString, _: &mut World) {
+        js! {
+            const blob = new Blob([@{measurements.to_string()}], {type: "text/csv"});
+            const link = document.createElement("a");
+            link.href = URL.createObjectURL(blob);
+            link.download = "detector_measurements.csv";
+            link.click();
+            URL.revokeObjectURL(link.href);
+        }
+    }
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn add_detectors(position: Serde<::descartes::P2>, radius: f32) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    ::transport::detectors::add_detectors_near(position.0, radius, world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn remove_detectors(position: Serde<::descartes::P2>, radius: f32) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    ::transport::detectors::remove_detectors_near(position.0, radius, world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn get_detector_measurements() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TrafficMeasurementsID::global_first(world)
+        .get_latest(DetectorsUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn export_detector_measurements() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TrafficMeasurementsID::global_first(world)
+        .export_csv(DetectorsUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn reset_detector_measurements() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    TrafficMeasurementsID::global_first(world).reset(world);
+}
+
+use transport::microtraffic::driver_behaviour::{DriverDistributions,
+DriverDistributionsRequester, DriverDistributionsRequesterID, DriverBehaviourSettingsID};
+
+#[derive(Compact, Clone)]
+pub struct DriverBehaviourUI {
+    id: DriverBehaviourUIID,
+}
+
+impl DriverBehaviourUI {
+    pub fn spawn(id: DriverBehaviourUIID, _: &mut World) -> DriverBehaviourUI {
+        DriverBehaviourUI { id }
+    }
+}
+
+impl DriverDistributionsRequester for DriverBehaviourUI {
+    fn on_driver_distributions(&mut self, distributions: DriverDistributions, _: &mut World) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                debug: { driverDistributions: {"$set": @{Serde(distributions)}} }
+            }));
+        }
+    }
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn get_driver_distributions() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    DriverBehaviourSettingsID::global_first(world)
+        .get_distributions(DriverBehaviourUIID::local_first(world).into(), world);
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn set_driver_distributions(distributions: Serde<DriverDistributions>) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+
+    DriverBehaviourSettingsID::global_first(world).set_distributions(distributions.0, world);
+}
+
 mod kay_auto;
 pub use self::kay_auto::*;
 
 pub fn setup(system: &mut ActorSystem) {
     system.register::<LogUI>();
+    system.register::<RoutingUI>();
+    system.register::<TripStatisticsUI>();
+    system.register::<FailedTripsUI>();
+    system.register::<DetectorsUI>();
+    system.register::<DriverBehaviourUI>();
     auto_setup(system);
 }
 
 pub fn spawn(world: &mut World) {
     LogUIID::spawn(world);
+    RoutingUIID::spawn(world);
+    TripStatisticsUIID::spawn(world);
+    FailedTripsUIID::spawn(world);
+    DetectorsUIID::spawn(world);
+    DriverBehaviourUIID::spawn(world);
 }
@@ -38,10 +38,15 @@ export const initialState = {
     projects: {
     },
     currentProject: null,
+    timeline: [],
+    issues: [],
+    pendingExport: null,
     hoveredControlPoint: {},
     hoveredInsertPoint: null,
     hoveredSplitPoint: null,
     hoveredChangeNLanesPoint: null,
+    // gesture whose intersection control is edited in the menu
+    selectedIntersectionControl: null,
     canvasMode: {
         intent: null,
         currentGesture: null,
@@ -66,7 +71,9 @@ export const settingsSpec = {
             key: /Mac|iPod|iPhone|iPad/.test(navigator.platform) ? 'command+shift+z' : 'ctrl+shift+z'
         }, description: "Redo Plan Step"
     },
-    finishGestureDistance: { default: 3.0, description: "Finish Gesture Double-Click Distance", min: 0.5, max: 10.0, step: 0.1 }
+    finishGestureDistance: { default: 3.0, description: "Finish Gesture Double-Click Distance", min: 0.5, max: 10.0, step: 0.1 },
+    authorName: { default: "", description: "Author Name (recorded in Project History)" },
+    geoOrigin: { default: "", description: "Location as \"longitude, latitude\" (for GeoJSON Export of Cities that weren't imported)" }
 }
 
 // STATE MUTATING ACTIONS
@@ -122,6 +129,7 @@ function startNewGesture(projectId, intent, startPoint) {
 
     return oldState => update(oldState, {
         planning: {
+            selectedIntersectionControl: { $set: null },
             canvasMode: {
                 currentGesture: { $set: gestureId },
                 addToEnd: { $set: true },
@@ -169,8 +177,11 @@ function splitGesture(projectId, gestureId, splitAt, doneSplitting) {
     });
 }
 
-function setNLanes(projectId, gestureId, nLanesForward, nLanesBackward, doneChanging) {
-    cbRustBrowser.set_n_lanes(projectId, gestureId, nLanesForward, nLanesBackward, doneChanging);
+function setNLanes(projectId, gestureId, roadIntent, nLanesForward, nLanesBackward, doneChanging) {
+    cbRustBrowser.set_road_intent(projectId, gestureId, Object.assign({}, roadIntent, {
+        n_lanes_forward: nLanesForward,
+        n_lanes_backward: nLanesBackward
+    }), doneChanging);
 
     return oldState => update(oldState, {
         planning: {
@@ -224,9 +235,6 @@ const shadersForLandUses = {
     Administrative: stripedShaders[2]
 };
 
-// TODO: share constants with Rust somehow
-const LANE_DISTANCE = 0.8 * 3.9;
-
 export function ShapesAndLayers(props) {
     const { state, setState } = props;
     const controlPointsInstances = [];
@@ -247,12 +255,17 @@ export function ShapesAndLayers(props) {
 
         let { gestureId: hoveredGestureId, pointIdx: hoveredPointIdx } = state.planning.hoveredControlPoint;
 
+        const gesturesWithIssues = new Set([].concat(...state.planning.issues.map(issue => issue.gestures)));
+
         for (let gestureId of Object.keys(gestures)) {
             const gesture = gestures[gestureId];
 
+            if (gesture.deleted) continue;
+
             for (let [pointIdx, point] of gesture.points.entries()) {
 
-                let isRelevant = (gesture.intent.Road && state.planning.planningMode === "roads")
+                let isRelevant = ((gesture.intent.Road || gesture.intent.Roundabout || gesture.intent.IntersectionControl) && state.planning.planningMode === "roads")
+                    || (gesture.intent.TransitLine && state.planning.planningMode === "transit")
                     || (gesture.intent.Zone && state.planning.planningMode === "zoning");
 
                 if (isRelevant) {
@@ -266,7 +279,9 @@ export function ShapesAndLayers(props) {
                         1.0, 0.0,
                         ...(isHovered
                             ? colors.controlPointHover
-                            : (gesture.fromMaster ? colors.controlPointMaster : colors.controlPointCurrentProject))
+                            : gesturesWithIssues.has(gestureId)
+                                ? colors.controlPointIssue
+                                : (gesture.fromMaster ? colors.controlPointMaster : colors.controlPointCurrentProject))
                     ]);
 
                     controlPointsInteractables.push(<Interactive3DShape
@@ -302,7 +317,17 @@ export function ShapesAndLayers(props) {
 
                             if (e.drag) {
                                 if (e.drag.end) {
-                                    if ((isFirst || isLast) && vec3.dist(e.drag.end, e.drag.start) < state.settings.planning.finishGestureDistance) {
+                                    if (gesture.intent.IntersectionControl && vec3.dist(e.drag.end, e.drag.start) < state.settings.planning.finishGestureDistance) {
+                                        // clicking an intersection control selects it for editing
+                                        setState(oldState => update(oldState, {
+                                            planning: {
+                                                selectedIntersectionControl: { $set: gestureId },
+                                                canvasMode: {
+                                                    intent: { $set: { IntersectionControl: gesture.intent.IntersectionControl } }
+                                                }
+                                            }
+                                        }))
+                                    } else if ((isFirst || isLast) && vec3.dist(e.drag.end, e.drag.start) < state.settings.planning.finishGestureDistance) {
                                         setState(oldState => update(oldState, {
                                             planning: {
                                                 canvasMode: {
@@ -330,7 +355,7 @@ export function ShapesAndLayers(props) {
 
     if (state.planning.planningMode === "roads") {
         for (let gestureId of Object.keys(state.planning.rendering.roadInfos)) {
-            let { centerLine, outline, nLanesForward, nLanesBackward } = state.planning.rendering.roadInfos[gestureId];
+            let { centerLine, outline, nLanesForward, nLanesBackward, roadIntent, laneDistance } = state.planning.rendering.roadInfos[gestureId];
 
             roadCenterInteractables.push(<Interactive3DShape
                 id={gestureId + "insert"}
@@ -375,8 +400,8 @@ export function ShapesAndLayers(props) {
                 shape={{
                     type: "path",
                     path: centerLine,
-                    maxDistanceLeft: LANE_DISTANCE * nLanesBackward,
-                    maxDistanceRight: LANE_DISTANCE * nLanesForward,
+                    maxDistanceLeft: laneDistance * nLanesBackward,
+                    maxDistanceRight: laneDistance * nLanesForward,
                 }}
                 zIndex={3}
                 cursorHover="col-resize"
@@ -413,8 +438,8 @@ export function ShapesAndLayers(props) {
                 shape={{
                     type: "path",
                     path: centerLine,
-                    maxDistanceLeft: LANE_DISTANCE * nLanesBackward + 2,
-                    maxDistanceRight: LANE_DISTANCE * nLanesForward + 2,
+                    maxDistanceLeft: laneDistance * nLanesBackward + 2,
+                    maxDistanceRight: laneDistance * nLanesForward + 2,
                 }}
                 zIndex={2}
                 cursorHover="ew-resize"
@@ -438,7 +463,7 @@ export function ShapesAndLayers(props) {
                                 newNLanesBackward = Math.max(0.0, Math.round(-orthogonalDistance / 3.0));
                             }
 
-                            setState(setNLanes(state.planning.currentProject, gestureId, newNLanesForward, newNLanesBackward, e.drag.end ? true : false));
+                            setState(setNLanes(state.planning.currentProject, gestureId, roadIntent, newNLanesForward, newNLanesBackward, e.drag.end ? true : false));
                         }
                     }
                     if (e.hover) {
@@ -458,9 +483,9 @@ export function ShapesAndLayers(props) {
 
                             let shiftedPoint;
                             if (orthogonalDistance > 0.0) {
-                                shiftedPoint = vec2.scaleAndAdd(vec2.create(), e.hover.projectedPosition, orthogonalRightDirection, LANE_DISTANCE * nLanesForward);
+                                shiftedPoint = vec2.scaleAndAdd(vec2.create(), e.hover.projectedPosition, orthogonalRightDirection, laneDistance * nLanesForward);
                             } else {
-                                shiftedPoint = vec2.scaleAndAdd(vec2.create(), e.hover.projectedPosition, orthogonalRightDirection, - LANE_DISTANCE * nLanesBackward);
+                                shiftedPoint = vec2.scaleAndAdd(vec2.create(), e.hover.projectedPosition, orthogonalRightDirection, - laneDistance * nLanesBackward);
                             }
 
                             setState(state => update(state, {
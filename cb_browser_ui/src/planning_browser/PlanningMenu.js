@@ -1,6 +1,6 @@
 import colors, { toCSS, fromLinFloat } from '../colors';
 import React from 'react';
-import { Button, Select, Divider, Icon } from 'antd';
+import { Button, Select, Divider, Icon, Input, InputNumber } from 'antd';
 const Option = Select.Option;
 import uuid from '../uuid';
 
@@ -12,7 +12,7 @@ function switchToProject(projectId) {
     console.log("switching to", projectId);
 
     return oldState => update(oldState, {
-        planning: { currentProject: { $set: projectId } }
+        planning: { currentProject: { $set: projectId }, selectedIntersectionControl: { $set: null } }
     })
 }
 
@@ -28,9 +28,13 @@ function implementProject(oldState) {
     return oldState;
 }
 
+function projectName(projectId, metadata) {
+    return (metadata && metadata.name) || `Project '${projectId.slice(0, 3).toUpperCase()}'`;
+}
+
 function startNewProject(oldState) {
     const projectId = uuid();
-    cbRustBrowser.start_new_project(projectId);
+    cbRustBrowser.start_new_project(projectId, projectName(projectId), oldState.settings.planning.authorName, "");
     return update(oldState, {
         planning: {
             currentProject: { $set: projectId },
@@ -38,6 +42,171 @@ function startNewProject(oldState) {
     });
 }
 
+function forkProject(timelineEntry) {
+    return oldState => {
+        const projectId = uuid();
+        const name = `Back to ${projectName(timelineEntry.project_id, timelineEntry.metadata)}`;
+        cbRustBrowser.fork_project(projectId, timelineEntry.master_step, name, oldState.settings.planning.authorName, "");
+        return update(oldState, {
+            planning: {
+                currentProject: { $set: projectId },
+            }
+        });
+    };
+}
+
+function revertProject(timelineEntry) {
+    return oldState => {
+        const projectId = uuid();
+        const name = `Revert ${projectName(timelineEntry.project_id, timelineEntry.metadata)}`;
+        cbRustBrowser.revert_project(projectId, timelineEntry.project_id, name, oldState.settings.planning.authorName, "");
+        return update(oldState, {
+            planning: {
+                currentProject: { $set: projectId },
+            }
+        });
+    };
+}
+
+function formatInstant(ticks) {
+    // TODO: share constants with Rust somehow
+    const TICKS_PER_SIM_MINUTE = 60 * 3;
+    const minutes = Math.floor(ticks / TICKS_PER_SIM_MINUTE);
+    const days = Math.floor(minutes / (60 * 24));
+    const hours = Math.floor(minutes / 60) % 24;
+    return `Day ${days + 1}, ${hours}:${String(minutes % 60).padStart(2, "0")}`;
+}
+
+function exportCity(format) {
+    return oldState => {
+        cbRustBrowser.export_city();
+        return update(oldState, {
+            planning: { pendingExport: { $set: format } }
+        });
+    };
+}
+
+const ROAD_LEVELS = {
+    ground: { Flat: 0 },
+    bridge: { Flat: 1 },
+    tunnel: { Flat: -1 },
+    rampUp: { Ramp: [0, 1] },
+    rampDown: { Ramp: [1, 0] },
+};
+
+function roadLevelOption(level) {
+    return Object.keys(ROAD_LEVELS).find(option => JSON.stringify(ROAD_LEVELS[option]) == JSON.stringify(level || ROAD_LEVELS.ground));
+}
+
+const MAX_LANE_USE_LANES = 8;
+
+// lane-use arrows from the inner to the outer lane, lanes without arrows are assigned automatically
+function laneUse(option, nLanes) {
+    return Array.from({ length: MAX_LANE_USE_LANES }, (_, l) => {
+        const innermost = l == 0;
+        const outermost = l == nLanes - 1;
+        if (option == "auto" || nLanes < 2 || l >= nLanes) {
+            return { inner_turn: false, straight: false, outer_turn: false };
+        } else if (option == "dedicatedTurns") {
+            return {
+                inner_turn: innermost,
+                straight: !innermost && (!outermost || nLanes == 2),
+                outer_turn: outermost
+            };
+        } else {
+            return { inner_turn: innermost, straight: true, outer_turn: outermost };
+        }
+    });
+}
+
+function laneUseOption(roadIntent) {
+    return ["auto", "dedicatedTurns", "sharedTurns"].find(option =>
+        JSON.stringify(laneUse(option, roadIntent.n_lanes_forward)) == JSON.stringify(roadIntent.lane_use_forward || laneUse("auto", 0))
+    );
+}
+
+const INTERSECTION_CONTROLS = {
+    signalled: { Signalled: { phase_order: [], phase_durations: [], yellow_duration: 10, all_red_duration: 3 } },
+    adaptive: { Adaptive: { phase_order: [], phase_durations: [], yellow_duration: 4, all_red_duration: 2 } },
+    unsignalled: "Unsignalled",
+    allWayStop: "AllWayStop",
+};
+
+function intersectionControlOption(control) {
+    if (!control) return null;
+    if (control.Signalled) return "signalled";
+    if (control.Adaptive) return "adaptive";
+    return Object.keys(INTERSECTION_CONTROLS).find(option => INTERSECTION_CONTROLS[option] == control);
+}
+
+// used for new intersection control gestures and applied to the selected one, if any
+function setIntersectionControl(control) {
+    return oldState => {
+        const selected = oldState.planning.selectedIntersectionControl;
+        if (selected) {
+            cbRustBrowser.set_intersection_control(oldState.planning.currentProject, selected, control, true);
+        }
+        return update(oldState, {
+            planning: {
+                canvasMode: {
+                    intent: { $set: { IntersectionControl: control } }
+                }
+            }
+        });
+    };
+}
+
+function setSignalProgram(control, changes) {
+    const kind = control.Signalled ? "Signalled" : "Adaptive";
+    return setIntersectionControl({ [kind]: Object.assign({}, control[kind], changes) });
+}
+
+// comma separated, ignoring anything that isn't a whole number
+function parseNumberList(text) {
+    return text.split(",").map(part => parseInt(part.trim(), 10)).filter(n => Number.isInteger(n) && n >= 0);
+}
+
+function SignalProgramEditor(props) {
+    const { control, setState } = props;
+    const program = control.Signalled || control.Adaptive;
+
+    return <span id="signal-program-editor">
+        <Input
+            style={{ width: 140 }}
+            addonBefore="Phases"
+            placeholder="as found"
+            title="Order in which the phases found for the intersection are run, e.g. 1, 0, 2"
+            defaultValue={program.phase_order.join(", ")}
+            onBlur={e => setState(setSignalProgram(control, { phase_order: parseNumberList(e.target.value) }))}
+        />
+        <Input
+            style={{ width: 160 }}
+            addonBefore="Green (s)"
+            placeholder="by lanes"
+            title="Green time of each phase in the order above"
+            defaultValue={program.phase_durations.join(", ")}
+            onBlur={e => setState(setSignalProgram(control, { phase_durations: parseNumberList(e.target.value) }))}
+        />
+        Yellow (s) <InputNumber
+            min={0} max={60} step={1}
+            value={program.yellow_duration}
+            onChange={value => Number.isInteger(value) && setState(setSignalProgram(control, { yellow_duration: value }))}
+        />
+        All-red (s) <InputNumber
+            min={0} max={60} step={1}
+            value={program.all_red_duration}
+            onChange={value => Number.isInteger(value) && setState(setSignalProgram(control, { all_red_duration: value }))}
+        />
+    </span>;
+}
+
+const TRANSIT_HEADWAYS = {
+    every5Minutes: 5,
+    every10Minutes: 10,
+    every20Minutes: 20,
+    every60Minutes: 60,
+};
+
 function undo(oldState) {
     if (oldState.planning.currentProject) {
         cbRustBrowser.undo(oldState.planning.currentProject);
@@ -90,13 +259,43 @@ export function Tools(props) {
                         </div>
                     )}
                 >{Object.keys(state.planning.projects).map(projectId =>
-                    <Option value={projectId}>Project '{projectId.slice(0, 3).toUpperCase()}'</Option>
+                    <Option value={projectId}>{projectName(projectId, state.planning.projects[projectId].metadata)}</Option>
                 )}</Select>
                 : <Button type="primary" onClick={() => setState(startNewProject)}>Start new project</Button>,
+            state.planning.timeline.some(entry => entry.metadata.name) &&
+            <Select
+                style={{ width: 240 }}
+                placeholder="History"
+                value={undefined}
+                dropdownMatchSelectWidth={false}
+                onChange={value => {
+                    const [action, idx] = value.split(":");
+                    const entry = state.planning.timeline[idx];
+                    setState(action == "fork" ? forkProject(entry) : revertProject(entry));
+                }}
+            >{state.planning.timeline.map((entry, idx) => [entry, idx]).filter(([entry]) => entry.metadata.name).reverse().map(([entry, idx]) =>
+                <Select.OptGroup label={`${projectName(entry.project_id, entry.metadata)} (${entry.metadata.author || "unknown"}, ${formatInstant(entry.implemented_at)})`}>
+                    <Option value={`fork:${idx}`}>Fork from here</Option>
+                    {entry.metadata.origin !== "BeforeTimeline" && <Option value={`revert:${idx}`}>Revert</Option>}
+                </Select.OptGroup>
+            )}</Select>,
+            <Select
+                style={{ width: 120 }}
+                placeholder="Export"
+                value={undefined}
+                onChange={format => setState(exportCity(format))}
+            >
+                <Option value="geojson">GeoJSON</Option>
+                <Option value="svg">SVG</Option>
+            </Select>,
             state.planning.currentProject && [
                 <Button type="primary"
                     onClick={() => setState(implementProject)}
                 >Implement</Button>,
+                state.planning.issues.length > 0 &&
+                <span style={{ color: toCSS(fromLinFloat(colors.controlPointIssue)), margin: '0 8px' }}
+                    title={state.planning.issues.map(issue => issue.kind).join(", ")}
+                >{state.planning.issues.length} {state.planning.issues.length == 1 ? "issue" : "issues"}</span>,
                 <Toolbar id="planning-history-toolbar"
                     options={{
                         undo: { description: "Undo", disabled: !state.planning.projects[state.planning.currentProject] || !state.planning.projects[state.planning.currentProject].undoable_history.length },
@@ -106,12 +305,157 @@ export function Tools(props) {
                 />,
                 state.planning.currentProject &&
                 <Toolbar id="planning-toolbar"
-                    options={{ roads: { description: "Roads" }, zoning: { description: "Zoning" } }}
+                    options={{ roads: { description: "Roads" }, transit: { description: "Transit" }, zoning: { description: "Zoning" } }}
                     value={state.planning.planningMode}
                     onChange={(value) => setState(oldState => update(oldState, {
                         planning: {
                             planningMode: { $set: value },
-                            canvasMode: { intent: { $set: value == "roads" ? { Road: { n_lanes_forward: 1, n_lanes_backward: 1 } } : null } }
+                            selectedIntersectionControl: { $set: null },
+                            canvasMode: {
+                                intent: {
+                                    $set: value == "roads" ? { Road: { n_lanes_forward: 1, n_lanes_backward: 1, road_class: "ResidentialStreet", level: ROAD_LEVELS.ground } }
+                                        : value == "transit" ? { TransitLine: { headway_minutes: TRANSIT_HEADWAYS.every10Minutes } }
+                                            : null
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                <Toolbar id="road-shape-toolbar"
+                    options={{
+                        road: { description: "Road" },
+                        roundabout: { description: "Roundabout" }
+                    }}
+                    value={state.planning.canvasMode.intent && (state.planning.canvasMode.intent.Roundabout ? "roundabout" : state.planning.canvasMode.intent.Road && "road")}
+                    onChange={newShape => setState(oldState => update(oldState, {
+                        planning: {
+                            selectedIntersectionControl: { $set: null },
+                            canvasMode: {
+                                intent: {
+                                    $set: newShape == "roundabout"
+                                        ? { Roundabout: { road_class: "ResidentialStreet" } }
+                                        : { Road: { n_lanes_forward: 1, n_lanes_backward: 1, road_class: "ResidentialStreet", level: ROAD_LEVELS.ground } }
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                <Toolbar id="intersection-control-toolbar"
+                    options={{
+                        signalled: { description: "Traffic Signals" },
+                        adaptive: { description: "Adaptive Signals" },
+                        unsignalled: { description: "Unsignalled" },
+                        allWayStop: { description: "All-way Stop" }
+                    }}
+                    value={intersectionControlOption(state.planning.canvasMode.intent && state.planning.canvasMode.intent.IntersectionControl)}
+                    onChange={newControlOption => setState(setIntersectionControl(INTERSECTION_CONTROLS[newControlOption]))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                state.planning.canvasMode.intent && state.planning.canvasMode.intent.IntersectionControl &&
+                (state.planning.canvasMode.intent.IntersectionControl.Signalled || state.planning.canvasMode.intent.IntersectionControl.Adaptive) &&
+                <SignalProgramEditor
+                    key={state.planning.selectedIntersectionControl || "new"}
+                    control={state.planning.canvasMode.intent.IntersectionControl}
+                    setState={setState} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road &&
+                <Toolbar id="road-class-toolbar"
+                    options={{
+                        ResidentialStreet: { description: "Street" },
+                        Arterial: { description: "Arterial" },
+                        Highway: { description: "Highway" },
+                        Alley: { description: "One-way Alley" }
+                    }}
+                    value={state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road && state.planning.canvasMode.intent.Road.road_class}
+                    onChange={newRoadClass => setState(oldState => update(oldState, {
+                        planning: {
+                            canvasMode: {
+                                intent: {
+                                    Road: {
+                                        $merge: newRoadClass == "Alley"
+                                            ? { n_lanes_forward: 1, n_lanes_backward: 0, road_class: newRoadClass }
+                                            : newRoadClass == "Highway"
+                                                ? { n_lanes_forward: 2, n_lanes_backward: 2, road_class: newRoadClass }
+                                                : { n_lanes_forward: 1, n_lanes_backward: 1, road_class: newRoadClass }
+                                    }
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road &&
+                <Toolbar id="road-level-toolbar"
+                    options={{
+                        ground: { description: "Ground" },
+                        bridge: { description: "Bridge" },
+                        tunnel: { description: "Tunnel" },
+                        rampUp: { description: "Ramp up" },
+                        rampDown: { description: "Ramp down" }
+                    }}
+                    value={roadLevelOption(state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road && state.planning.canvasMode.intent.Road.level)}
+                    onChange={newLevelOption => setState(oldState => update(oldState, {
+                        planning: {
+                            canvasMode: {
+                                intent: {
+                                    Road: { $merge: { level: ROAD_LEVELS[newLevelOption] } }
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road &&
+                <Toolbar id="road-parking-toolbar"
+                    options={{
+                        noParking: { description: "No Parking" },
+                        parking: { description: "On-street Parking" }
+                    }}
+                    value={state.planning.canvasMode.intent.Road.parking ? "parking" : "noParking"}
+                    onChange={newParkingOption => setState(oldState => update(oldState, {
+                        planning: {
+                            canvasMode: {
+                                intent: {
+                                    Road: { $merge: { parking: newParkingOption == "parking" } }
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "roads" &&
+                state.planning.canvasMode.intent && state.planning.canvasMode.intent.Road &&
+                <Toolbar id="lane-use-toolbar"
+                    options={{
+                        auto: { description: "Automatic Lane Use" },
+                        dedicatedTurns: { description: "Dedicated Turn Lanes" },
+                        sharedTurns: { description: "Shared Turn Lanes" }
+                    }}
+                    value={laneUseOption(state.planning.canvasMode.intent.Road)}
+                    onChange={newLaneUseOption => setState(oldState => update(oldState, {
+                        planning: {
+                            canvasMode: {
+                                intent: {
+                                    Road: {
+                                        $apply: roadIntent => Object.assign({}, roadIntent, {
+                                            lane_use_forward: laneUse(newLaneUseOption, roadIntent.n_lanes_forward),
+                                            lane_use_backward: laneUse(newLaneUseOption, roadIntent.n_lanes_backward)
+                                        })
+                                    }
+                                }
+                            }
+                        }
+                    }))} />,
+                state.planning.currentProject && state.planning.planningMode == "transit" &&
+                <Toolbar id="transit-headway-toolbar"
+                    options={{
+                        every5Minutes: { description: "Every 5 min" },
+                        every10Minutes: { description: "Every 10 min" },
+                        every20Minutes: { description: "Every 20 min" },
+                        every60Minutes: { description: "Hourly" }
+                    }}
+                    value={state.planning.canvasMode.intent && state.planning.canvasMode.intent.TransitLine
+                        && Object.keys(TRANSIT_HEADWAYS).find(option => TRANSIT_HEADWAYS[option] == state.planning.canvasMode.intent.TransitLine.headway_minutes)}
+                    onChange={newHeadway => setState(oldState => update(oldState, {
+                        planning: {
+                            canvasMode: {
+                                intent: { $set: { TransitLine: { headway_minutes: TRANSIT_HEADWAYS[newHeadway] } } }
+                            }
                         }
                     }))} />,
                 state.planning.currentProject && state.planning.planningMode == "zoning" &&
@@ -1,15 +1,18 @@
 use stdweb::serde::Serde;
 use kay::{World, Actor, External, ActorSystem, TypedID};
-use compact::{CHashMap};
+use compact::{CHashMap, CVec};
 use std::collections::HashMap;
 use descartes::{LinePath, P2};
 use michelangelo::{MeshGrouper};
 use cb_planning::{Project, GestureID, PrototypeID, PlanHistory, PlanResult,
-PlanHistoryUpdate, ProjectUpdate, PlanResultUpdate, ActionGroups};
-use cb_planning::plan_manager::ProjectID;
+PlanHistoryUpdate, ProjectUpdate, PlanResultUpdate, ActionGroups, PlanIssue, StepID};
+use cb_planning::plan_manager::{ProjectID, TimelineEntry};
 use cb_planning::plan_manager::ui::{PlanningUI, PlanningUIID};
 use planning::{CBPlanningLogic, CBPlanManagerID, CBGestureIntent, CBPrototypeKind};
+use planning::geo_reference::{GeoReferenceListener, GeoReferenceListenerID, GeoReferenceID};
 use ::land_use::zone_planning::{LandUse, LAND_USES};
+use ::transport::transport_planning::RoadIntent;
+use ::transport::transport_planning::intersection_control::IntersectionControl;
 use browser_utils::{updated_groups_to_js, to_js_mesh, FrameListener, FrameListenerID};
 
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
@@ -111,11 +114,10 @@ pub fn split_gesture(
 }
 
 #[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
-pub fn set_n_lanes(
+pub fn set_road_intent(
     project_id: Serde<ProjectID>,
     gesture_id: Serde<GestureID>,
-    n_lanes_forward: usize,
-    n_lanes_backward: usize,
+    road_intent: Serde<RoadIntent>,
     done_changing: bool,
 ) {
     let system = unsafe { &mut *SYSTEM };
@@ -123,10 +125,25 @@ pub fn set_n_lanes(
     CBPlanManagerID::global_first(world).set_intent(
         project_id.0,
         gesture_id.0,
-        CBGestureIntent::Road(::transport::transport_planning::RoadIntent {
-            n_lanes_forward: n_lanes_forward as u8,
-            n_lanes_backward: n_lanes_backward as u8,
-        }),
+        CBGestureIntent::Road(road_intent.0),
+        done_changing,
+        world,
+    )
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn set_intersection_control(
+    project_id: Serde<ProjectID>,
+    gesture_id: Serde<GestureID>,
+    control: Serde<IntersectionControl>,
+    done_changing: bool,
+) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    CBPlanManagerID::global_first(world).set_intent(
+        project_id.0,
+        gesture_id.0,
+        CBGestureIntent::IntersectionControl(control.0),
         done_changing,
         world,
     )
@@ -154,10 +171,70 @@ pub fn implement_project(project_id: Serde<ProjectID>) {
 }
 
 #[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
-pub fn start_new_project(project_id: Serde<ProjectID>) {
+pub fn start_new_project(
+    project_id: Serde<ProjectID>,
+    name: String,
+    author: String,
+    description: String,
+) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    CBPlanManagerID::global_first(world).start_new_project(
+        project_id.0,
+        name.into(),
+        author.into(),
+        description.into(),
+        world,
+    );
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn fork_project(
+    project_id: Serde<ProjectID>,
+    from_step: Serde<StepID>,
+    name: String,
+    author: String,
+    description: String,
+) {
     let system = unsafe { &mut *SYSTEM };
     let world = &mut system.world();
-    CBPlanManagerID::global_first(world).start_new_project(project_id.0, world);
+    CBPlanManagerID::global_first(world).fork_project(
+        project_id.0,
+        from_step.0,
+        name.into(),
+        author.into(),
+        description.into(),
+        world,
+    );
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn revert_project(
+    project_id: Serde<ProjectID>,
+    reverted_project_id: Serde<ProjectID>,
+    name: String,
+    author: String,
+    description: String,
+) {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    CBPlanManagerID::global_first(world).revert_project(
+        project_id.0,
+        reverted_project_id.0,
+        name.into(),
+        author.into(),
+        description.into(),
+        world,
+    );
+}
+
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), js_export)]
+pub fn export_city() {
+    let system = unsafe { &mut *SYSTEM };
+    let world = &mut system.world();
+    // the plan is requested once it is known where on earth the city is
+    GeoReferenceID::global_first(world)
+        .get_origin(BrowserPlanningUIID::local_first(world).into(), world);
 }
 
 #[derive(Compact, Clone)]
@@ -184,9 +261,12 @@ pub struct BrowserPlanningUINonPersistedState {
     // TODO: replace these with only known states and store them in JS only
     master_plan: PlanHistory<CBGestureIntent>,
     projects: HashMap<ProjectID, Project<CBGestureIntent>>,
+    timeline_len: usize,
     result_preview: PlanResult<CBPrototypeKind>,
     actions_preview: ActionGroups,
     awaiting_preview_update: bool,
+    /// (longitude, latitude) of the plane origin for the export in progress
+    export_origin: Option<(f64, f64)>,
 
     // planning geometry
     lanes_to_construct_grouper: MeshGrouper<PrototypeID>,
@@ -248,8 +328,10 @@ impl BrowserPlanningUI {
             state: External::new(BrowserPlanningUINonPersistedState {
                 master_plan: PlanHistory::new(),
                 projects: HashMap::new(),
+                timeline_len: 0,
                 result_preview: PlanResult::new(),
                 actions_preview: ActionGroups::new(),
+                export_origin: None,
                 awaiting_preview_update: false,
                 lanes_to_construct_grouper: MeshGrouper::new(2000),
                 lanes_to_construct_marker_grouper: MeshGrouper::new(2000),
@@ -279,6 +361,7 @@ impl FrameListener for BrowserPlanningUI {
                 .iter()
                 .map(|(project_id, project)| (*project_id, project.as_known_state()))
                 .collect(),
+            self.timeline_len as u32,
             world,
         );
 
@@ -306,8 +389,19 @@ impl PlanningUI<CBPlanningLogic> for BrowserPlanningUI {
         &mut self,
         master_update: &PlanHistoryUpdate<CBGestureIntent>,
         project_updates: &CHashMap<ProjectID, ProjectUpdate<CBGestureIntent>>,
+        timeline_update: &CVec<TimelineEntry>,
         _world: &mut World,
     ) {
+        if !timeline_update.is_empty() {
+            js! {
+                window.cbReactApp.boundSetState(oldState => update(oldState, {
+                    planning: {
+                        timeline: {"$push": @{Serde(timeline_update)}},
+                    }
+                }));
+            }
+            self.timeline_len += timeline_update.len();
+        }
         if !master_update.is_empty() {
             self.master_plan.apply_update(master_update);
             js! {
@@ -376,6 +470,7 @@ impl PlanningUI<CBPlanningLogic> for BrowserPlanningUI {
         effective_history: &PlanHistory<CBGestureIntent>,
         result_update: &PlanResultUpdate<CBPrototypeKind>,
         new_actions: &ActionGroups,
+        issues: &CVec<PlanIssue>,
         _world: &mut World,
     ) {
         use ::transport::transport_planning::{RoadPrototype, LanePrototype,
@@ -466,7 +561,7 @@ SwitchLanePrototype, IntersectionPrototype};
         for new_prototype in &result_update.new_prototypes {
             let corresponding_action = new_actions.corresponding_action(new_prototype.id);
             match new_prototype.kind {
-                CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(ref lane_path, _))) => {
+                CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(ref lane_path, ..))) => {
                     match corresponding_action {
                         Some(ref action) if action.is_construct() => {
                             lanes_to_construct_add.push((new_prototype.id, lane_mesh(lane_path)));
@@ -479,6 +574,7 @@ SwitchLanePrototype, IntersectionPrototype};
                 }
                 CBPrototypeKind::Road(RoadPrototype::SwitchLane(SwitchLanePrototype(
                     ref lane_path,
+                    _,
                 ))) => match corresponding_action {
                     Some(ref action) if action.is_construct() => {
                         lanes_to_construct_marker_gaps_add
@@ -492,7 +588,7 @@ SwitchLanePrototype, IntersectionPrototype};
                 })) => match corresponding_action {
                     Some(ref action) if action.is_construct() => {
                         let mut intersection_mesh = Mesh::empty();
-                        for &LanePrototype(ref lane_path, _) in
+                        for &LanePrototype(ref lane_path, ..) in
                             connecting_lanes.values().flat_map(|lanes| lanes)
                         {
                             intersection_mesh += lane_mesh(lane_path);
@@ -600,6 +696,8 @@ SwitchLanePrototype, IntersectionPrototype};
             outline: LinePath,
             n_lanes_forward: usize,
             n_lanes_backward: usize,
+            road_intent: RoadIntent,
+            lane_distance: f32,
         }
 
         let road_infos: HashMap<GestureID, RoadInfo> =
@@ -611,18 +709,16 @@ SwitchLanePrototype, IntersectionPrototype};
                         RoadInfo {
                             outline: ::descartes::Band::new_asymmetric(
                                 path.clone(),
-                                f32::from(road_intent.n_lanes_backward)
-                                    * ::dimensions::LANE_DISTANCE
-                                    + 0.4 * ::dimensions::LANE_DISTANCE,
-                                f32::from(road_intent.n_lanes_forward)
-                                    * ::dimensions::LANE_DISTANCE
-                                    + 0.4 * ::dimensions::LANE_DISTANCE,
+                                road_intent.backward_lanes_width(),
+                                road_intent.forward_lanes_width(),
                             )
                             .outline()
                             .0,
                             center_line: path,
                             n_lanes_forward: road_intent.n_lanes_forward as usize,
                             n_lanes_backward: road_intent.n_lanes_backward as usize,
+                            road_intent,
+                            lane_distance: road_intent.road_class.lane_distance(),
                         },
                     )
                 })
@@ -656,7 +752,8 @@ SwitchLanePrototype, IntersectionPrototype};
                         },
                     },
                     roadInfos: {"$set": @{Serde(road_infos)}},
-                }}
+                },
+                issues: {"$set": @{Serde(issues)}}}
             }));
         }
 
@@ -664,6 +761,108 @@ SwitchLanePrototype, IntersectionPrototype};
         self.actions_preview = new_actions.clone();
         self.awaiting_preview_update = false;
     }
+
+    fn on_project_preview_failed(
+        &mut self,
+        _project_id: ProjectID,
+        issues: &CVec<PlanIssue>,
+        _world: &mut World,
+    ) {
+        js! {
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                planning: {
+                    issues: {"$set": @{Serde(issues)}},
+                }
+            }));
+        }
+
+        self.awaiting_preview_update = false;
+    }
+
+    fn on_master_plan_and_result(
+        &mut self,
+        master_plan: &PlanHistory<CBGestureIntent>,
+        master_result: &PlanResult<CBPrototypeKind>,
+        _world: &mut World,
+    ) {
+        use ::stdweb::unstable::TryInto;
+        use ::planning::geo_export::{to_geojson, to_svg, GeoExportConfig};
+
+        let maybe_format: Result<String, _> = js! {
+            return window.cbReactApp.state.planning.pendingExport;
+        }
+        .try_into();
+
+        let (content, file_name, mime_type) = match maybe_format.as_ref().map(String::as_str) {
+            Ok("geojson") => {
+                let maybe_origin = self.export_origin.or_else(origin_from_settings);
+                if maybe_origin.is_none() {
+                    let message = "This city wasn't imported, so where it is on earth is unknown. \
+                                   Set its location in the planning settings to export GeoJSON.";
+                    js! {
+                        window.alert(@{message});
+                        window.cbReactApp.boundSetState(oldState => update(oldState, {
+                            planning: {
+                                pendingExport: {"$set": null},
+                            }
+                        }));
+                    }
+                    return;
+                }
+                (
+                    to_geojson(
+                        master_plan,
+                        master_result,
+                        GeoExportConfig {
+                            origin: maybe_origin,
+                        },
+                    ),
+                    "city.geojson",
+                    "application/geo+json",
+                )
+            }
+            Ok("svg") => (to_svg(master_result), "city.svg", "image/svg+xml"),
+            _ => return,
+        };
+
+        js! {
+            const blob = new Blob([@{content}], {type: @{mime_type}});
+            const link = document.createElement("a");
+            link.href = URL.createObjectURL(blob);
+            link.download = @{file_name};
+            link.click();
+            URL.revokeObjectURL(link.href);
+            window.cbReactApp.boundSetState(oldState => update(oldState, {
+                planning: {
+                    pendingExport: {"$set": null},
+                }
+            }));
+        }
+    }
+}
+
+impl GeoReferenceListener for BrowserPlanningUI {
+    fn on_geo_origin(&mut self, origin: Option<(f64, f64)>, world: &mut World) {
+        self.export_origin = origin;
+        CBPlanManagerID::global_first(world).get_master_plan_and_result(self.id.into(), world);
+    }
+}
+
+/// The location the user gave for cities that weren't imported, as "longitude, latitude"
+fn origin_from_settings() -> Option<(f64, f64)> {
+    use ::stdweb::unstable::TryInto;
+
+    let setting: String = js! {
+        return window.cbReactApp.state.settings.planning.geoOrigin || "";
+    }
+    .try_into()
+    .ok()?;
+
+    let mut parts = setting.split(',').map(|part| part.trim().parse::<f64>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(longitude)), Some(Ok(latitude)), None) => Some((longitude, latitude)),
+        _ => None,
+    }
 }
 
 mod kay_auto;
@@ -221,7 +221,7 @@ export class Settings extends React.Component {
                                 localStorage["cbsettings:" + aspect + ":" + key] = JSON.stringify(newValue);
                             };
 
-                            const onChangeInput = event => this.onChange(event.target.value);
+                            const onChangeInput = event => onChange(event.target.value);
 
                             let inputEls = [];
 
@@ -255,7 +255,7 @@ export class Settings extends React.Component {
                                     spec.trueDescription || "",
                                 ];
                             } else if (typeof spec.default === "string") {
-                                inputEls = <Input value={currentSettings[aspect][key]} {...{ onChangeInput }} />;
+                                inputEls = <Input value={currentSettings[aspect][key]} onChange={onChangeInput} />;
                             } else if (spec.default.key) {
                                 let splitKeys = currentSettings[aspect][key].key.split("+");
                                 splitKeys = splitKeys.length === 1 && splitKeys[0] === "" ? [] : splitKeys;
@@ -1,18 +1,26 @@
 import colors from '../colors';
 import renderOrder from '../renderOrder';
-import carMesh from './carMesh';
+import carMesh, { scaledCarMesh } from './carMesh';
 import { RenderLayer } from '../browser_utils/Utils';
 import React from 'react';
 
 export const initialState = {
     rendering: {
         staticMeshes: {
-            car: carMesh
+            car: carMesh,
+            truck: scaledCarMesh(10.0, 2.5, 3.2),
+            bus: scaledCarMesh(12.0, 2.5, 3.0),
+            bicycle: scaledCarMesh(1.8, 0.6, 1.1)
         },
         laneAsphaltGroups: new Map(),
         laneMarkerGroups: new Map(),
         laneMarkerGapGroups: new Map(),
-        carInstances: []
+        vehicleInstances: {
+            car: [],
+            truck: [],
+            bus: [],
+            bicycle: []
+        }
     }
 };
 
@@ -47,9 +55,9 @@ export function Layers(props) {
         <RenderLayer
             renderOrder={renderOrder.cars}
             decal={false}
-            batches={[{
-                mesh: state.transport.rendering.staticMeshes.car,
-                instances: state.transport.rendering.carInstances
-            }]} />
+            batches={["car", "truck", "bus", "bicycle"].map(vehicleType => ({
+                mesh: state.transport.rendering.staticMeshes[vehicleType],
+                instances: state.transport.rendering.vehicleInstances[vehicleType]
+            }))} />
     ];
 }
\ No newline at end of file
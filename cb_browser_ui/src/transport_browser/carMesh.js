@@ -9,7 +9,7 @@
 //
 // -2.25-----------X----------2.25
 
-export default {
+const carMesh = {
     vertices: new Float32Array([
         -2.25, -0.9, 0.00, // 0
         -2.25, -0.9, 0.80, // 1
@@ -59,4 +59,15 @@ export default {
         0xE, 0xF, 7,
         0xE, 7, 6,
     ])
-}
\ No newline at end of file
+}
+
+export default carMesh;
+
+// the same shape stretched to the size of other vehicles, all in m
+export function scaledCarMesh(length, width, height) {
+    const scale = [length / 4.5, width / 1.8, height / 1.65];
+    return {
+        vertices: carMesh.vertices.map((coordinate, i) => coordinate * scale[i % 3]),
+        indices: carMesh.indices
+    };
+}
@@ -26,7 +26,7 @@ impl ::std::ops::DerefMut for BrowserTransportUI {
 }
 
 pub struct BrowserTransportUINonPersistedState {
-    car_instance_buffers: HashMap<RawID, Vec<::michelangelo::Instance>>,
+    car_instance_buffers: HashMap<RawID, Vec<(VehicleType, ::michelangelo::Instance)>>,
     car_colors: Vec<[f32; 3]>,
 
     // transport geometry
@@ -61,19 +61,33 @@ impl FrameListener for BrowserTransportUI {
         ::transport::lane::LaneID::global_broadcast(world).get_car_info(self.id_as(), world);
         ::transport::lane::SwitchLaneID::global_broadcast(world).get_car_info(self.id_as(), world);
 
-        let mut car_instances = Vec::with_capacity(600_000);
+        let mut instances_by_type: HashMap<VehicleType, Vec<Instance>> = HashMap::new();
 
         for lane_instances in self.car_instance_buffers.values() {
-            car_instances.extend_from_slice(lane_instances);
+            for &(vehicle_type, ref instance) in lane_instances {
+                instances_by_type
+                    .entry(vehicle_type)
+                    .or_insert_with(|| Vec::with_capacity(100_000))
+                    .push(instance.clone());
+            }
         }
 
-        let car_instances_js: ::stdweb::web::TypedArray<f32> =
-            flatten_instances(&car_instances).into();
+        let instances_js = |vehicle_type: VehicleType| -> ::stdweb::web::TypedArray<f32> {
+            instances_by_type
+                .get(&vehicle_type)
+                .map(|instances| flatten_instances(instances).into())
+                .unwrap_or_else(|| flatten_instances(&[]).into())
+        };
 
         js! {
             window.cbReactApp.boundSetState(oldState => update(oldState, {
                 transport: {rendering: {
-                    carInstances: {"$set": @{car_instances_js}}
+                    vehicleInstances: {"$set": {
+                        car: @{instances_js(VehicleType::Car)},
+                        truck: @{instances_js(VehicleType::Truck)},
+                        bus: @{instances_js(VehicleType::Bus)},
+                        bicycle: @{instances_js(VehicleType::Bicycle)}
+                    }}
                 }}
             }))
         }
@@ -97,6 +111,7 @@ impl FrameListener for BrowserTransportUI {
 }
 
 use transport::ui::{TransportUI, TransportUIID, CarRenderInfo};
+use transport::microtraffic::VehicleType;
 
 impl TransportUI for BrowserTransportUI {
     fn on_lane_constructed(
@@ -227,11 +242,17 @@ impl TransportUI for BrowserTransportUI {
     fn on_car_info(&mut self, from_lane: RawID, infos: &CVec<CarRenderInfo>, _: &mut World) {
         let colored = infos
             .iter()
-            .map(|render_info| Instance {
-                instance_position: [render_info.position[0], render_info.position[1], 0.0],
-                instance_direction: render_info.direction,
-                instance_color: self.car_colors
-                    [render_info.trip.as_raw().instance_id as usize % self.car_colors.len()],
+            .map(|render_info| {
+                (
+                    render_info.vehicle_type,
+                    Instance {
+                        instance_position: [render_info.position[0], render_info.position[1], 0.0],
+                        instance_direction: render_info.direction,
+                        instance_color: self.car_colors[render_info.trip.as_raw().instance_id
+                            as usize
+                            % self.car_colors.len()],
+                    },
+                )
             })
             .collect();
         self.car_instance_buffers.insert(from_lane, colored);
@@ -1,6 +1,8 @@
 use kay::{World, Fate, ActorSystem};
 use compact::{CVec, CHashMap, Compact};
+use descartes::N;
 use ::{PrototypeID, Prototype, Action, ActionGroups};
+use snapping::SnappingConfig;
 use cb_time::actors::{Temporal, TemporalID};
 use cb_time::units::Instant;
 use cb_util::log::debug;
@@ -17,7 +19,21 @@ pub trait PrototypeKind: Compact + 'static {
     fn morphable_from(&self, other: &Self) -> bool;
 }
 
-pub trait GestureIntent: Compact + 'static {}
+pub trait GestureIntent: Compact + 'static {
+    fn snapping(&self) -> SnappingConfig {
+        SnappingConfig::none()
+    }
+
+    fn snaps_to_points_of(&self, _other: &Self) -> bool {
+        false
+    }
+
+    /// Distances to the left and right of `other`'s control line
+    /// that a parallel gesture with this intent should keep
+    fn parallel_offsets_to(&self, _other: &Self) -> Option<(N, N)> {
+        None
+    }
+}
 
 pub trait Constructable<PK: PrototypeKind> {
     fn morph(
@@ -11,14 +11,17 @@ extern crate descartes;
 extern crate cb_util;
 extern crate cb_time;
 
-use compact::{CVec, COption, CHashMap, Compact};
+use compact::{CVec, COption, CHashMap, CString, Compact};
 use descartes::{N, P2, AreaError};
 use cb_util::random::{seed, RngCore, Uuid, uuid};
+use cb_time::units::Instant;
 use std::hash::Hash;
+use std::borrow::Cow;
 
 pub mod construction;
 use construction::{PrototypeKind, GestureIntent};
 pub mod plan_manager;
+pub mod snapping;
 
 // idea for improvement:
 // - everything (Gestures, Prototypes) immutable (helps caching)
@@ -39,6 +42,17 @@ impl<GI: GestureIntent> Gesture<GI> {
             deleted: false,
         }
     }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    pub fn as_deleted(&self) -> Self {
+        Gesture {
+            deleted: true,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -98,17 +112,67 @@ impl<GI: GestureIntent> PlanHistory<GI> {
         let mut history = self.clone();
 
         for plan in plans {
-            for (gesture_id, gesture) in plan.gestures.pairs() {
-                history
-                    .gestures
-                    .insert(*gesture_id, VersionedGesture(gesture.clone(), plan.step_id));
-                history.steps.push(plan.step_id);
-            }
+            history.push_plan(plan);
         }
 
         history
     }
 
+    pub fn push_plan(&mut self, plan: &Plan<GI>) {
+        for (gesture_id, gesture) in plan.gestures.pairs() {
+            self.gestures
+                .insert(*gesture_id, VersionedGesture(gesture.clone(), plan.step_id));
+            self.steps.push(plan.step_id);
+        }
+    }
+
+    /// A plan that, applied on top of this history, results in the same
+    /// gestures as `target`, deleting gestures that `target` doesn't have
+    pub fn plan_towards(&self, target: &PlanHistory<GI>) -> Plan<GI> {
+        let changed_or_added = target.gestures.pairs().filter_map(
+            |(gesture_id, VersionedGesture(target_gesture, target_step))| {
+                match self.gestures.get(*gesture_id) {
+                    Some(VersionedGesture(_, own_step)) if own_step == target_step => None,
+                    _ => Some((*gesture_id, target_gesture.clone())),
+                }
+            },
+        );
+
+        let removed = self.gestures.pairs().filter_map(
+            |(gesture_id, VersionedGesture(own_gesture, _))| {
+                if own_gesture.deleted || target.gestures.contains_key(*gesture_id) {
+                    None
+                } else {
+                    Some((*gesture_id, own_gesture.as_deleted()))
+                }
+            },
+        );
+
+        Plan::from_gestures(changed_or_added.chain(removed).collect::<Vec<_>>())
+    }
+
+    pub fn without_deleted(&self) -> Cow<PlanHistory<GI>> {
+        if self
+            .gestures
+            .values()
+            .any(|VersionedGesture(gesture, _)| gesture.deleted)
+        {
+            let mut history = self.clone();
+            let deleted_ids = self
+                .gestures
+                .pairs()
+                .filter(|(_, VersionedGesture(gesture, _))| gesture.deleted)
+                .map(|(gesture_id, _)| *gesture_id)
+                .collect::<Vec<_>>();
+            for gesture_id in deleted_ids {
+                history.gestures.remove(gesture_id);
+            }
+            Cow::Owned(history)
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
+
     pub fn latest_step_id(&self) -> StepID {
         *self.steps.last().expect("should always have a step")
     }
@@ -233,8 +297,51 @@ impl<GI: GestureIntent> PlanHistoryUpdate<GI> {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum ProjectOrigin {
+    New,
+    /// Stands for everything implemented before the timeline was recorded
+    BeforeTimeline,
+    ForkedFrom(StepID),
+    RevertOf(plan_manager::ProjectID),
+}
+
+#[derive(Compact, Clone, Serialize, Deserialize)]
+pub struct ProjectMetadata {
+    pub name: CString,
+    pub author: CString,
+    pub description: CString,
+    pub created_at: Instant,
+    pub origin: ProjectOrigin,
+}
+
+impl ProjectMetadata {
+    pub fn new() -> ProjectMetadata {
+        ProjectMetadata {
+            name: CString::new(),
+            author: CString::new(),
+            description: CString::new(),
+            created_at: Instant::new(0),
+            origin: ProjectOrigin::New,
+        }
+    }
+}
+
+impl ::std::fmt::Debug for ProjectMetadata {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let (name, author): (&str, &str) = (&self.name, &self.author);
+        f.debug_struct("ProjectMetadata")
+            .field("name", &name)
+            .field("author", &author)
+            .field("created_at", &self.created_at)
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
 #[derive(Compact, Clone, Debug, Serialize, Deserialize)]
 pub struct Project<GI: GestureIntent> {
+    pub metadata: ProjectMetadata,
     undoable_history: CVec<Plan<GI>>,
     ongoing: Plan<GI>,
     redoable_history: CVec<Plan<GI>>,
@@ -243,6 +350,7 @@ pub struct Project<GI: GestureIntent> {
 impl<GI: GestureIntent + 'static> Project<GI> {
     pub fn new() -> Project<GI> {
         Project {
+            metadata: ProjectMetadata::new(),
             undoable_history: CVec::new(),
             ongoing: Plan::new(),
             redoable_history: CVec::new(),
@@ -251,6 +359,7 @@ impl<GI: GestureIntent + 'static> Project<GI> {
 
     pub fn from_plan(plan: Plan<GI>) -> Project<GI> {
         Project {
+            metadata: ProjectMetadata::new(),
             undoable_history: vec![plan].into(),
             ongoing: Plan::new(),
             redoable_history: CVec::new(),
@@ -749,6 +858,34 @@ impl ActionGroups {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PlanIssueKind {
+    CalculationFailed,
+    SelfIntersecting,
+    TooShort,
+    NoAccess,
+    TooManyConnections,
+}
+
+#[derive(Compact, Clone, Debug, Serialize, Deserialize)]
+pub struct PlanIssue {
+    pub gestures: CVec<GestureID>,
+    pub kind: PlanIssueKind,
+}
+
+impl PlanIssue {
+    pub fn new<I: IntoIterator<Item = GestureID>>(gestures: I, kind: PlanIssueKind) -> PlanIssue {
+        PlanIssue {
+            gestures: gestures.into_iter().collect(),
+            kind,
+        }
+    }
+}
+
+#[allow(type_alias_bounds)]
+pub type PlanValidationFn<PL: PlanningLogic> =
+    fn(&PlanHistory<PL::GestureIntent>, Option<&PlanResult<PL::PrototypeKind>>) -> Vec<PlanIssue>;
+
 #[allow(type_alias_bounds)]
 pub type PlanningStepFn<PL: PlanningLogic> =
     fn(
@@ -761,13 +898,18 @@ pub trait PlanningLogic: Compact + 'static {
     type PrototypeKind: PrototypeKind;
 
     fn planning_step_functions() -> &'static [PlanningStepFn<Self>];
+    fn plan_validation_functions() -> &'static [PlanValidationFn<Self>] {
+        &[]
+    }
+
     fn calculate_result(
         history: &PlanHistory<Self::GestureIntent>,
     ) -> Result<PlanResult<Self::PrototypeKind>, AreaError> {
+        let history = history.without_deleted();
         let mut result = PlanResult::new();
 
         for prototype_fn in Self::planning_step_functions() {
-            let new_prototypes = prototype_fn(history, &result)?;
+            let new_prototypes = prototype_fn(&history, &result)?;
 
             for (id, prototype) in new_prototypes
                 .into_iter()
@@ -780,4 +922,15 @@ pub trait PlanningLogic: Compact + 'static {
 
         Ok(result)
     }
+
+    fn validate(
+        history: &PlanHistory<Self::GestureIntent>,
+        result: Option<&PlanResult<Self::PrototypeKind>>,
+    ) -> Vec<PlanIssue> {
+        let history = history.without_deleted();
+        Self::plan_validation_functions()
+            .iter()
+            .flat_map(|validation_fn| validation_fn(&history, result))
+            .collect()
+    }
 }
@@ -10,8 +10,12 @@ use super::*;
 
 
 impl<Logic: PlanningLogic> PlanManagerID<Logic> {
-    pub fn get_all_plans(self, ui: PlanningUIID < Logic >, known_master: KnownHistoryState, known_projects: CHashMap < ProjectID , KnownProjectState >, world: &mut World) {
-        world.send(self.as_raw(), MSG_PlanManager_get_all_plans::<Logic>(ui, known_master, known_projects));
+    pub fn get_all_plans(self, ui: PlanningUIID < Logic >, known_master: KnownHistoryState, known_projects: CHashMap < ProjectID , KnownProjectState >, known_timeline_len: u32, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanManager_get_all_plans::<Logic>(ui, known_master, known_projects, known_timeline_len));
+    }
+    
+    pub fn get_master_plan_and_result(self, ui: PlanningUIID < Logic >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanManager_get_master_plan_and_result::<Logic>(ui));
     }
     
     pub fn get_project_preview_update(self, ui: PlanningUIID < Logic >, project_id: ProjectID, known_result: KnownPlanResultState < Logic :: PrototypeKind >, world: &mut World) {
@@ -52,7 +56,9 @@ impl<Logic: PlanningLogic> PlanManagerID<Logic> {
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_PlanManager_get_all_plans<Logic: PlanningLogic>(pub PlanningUIID < Logic >, pub KnownHistoryState, pub CHashMap < ProjectID , KnownProjectState >);
+struct MSG_PlanManager_get_all_plans<Logic: PlanningLogic>(pub PlanningUIID < Logic >, pub KnownHistoryState, pub CHashMap < ProjectID , KnownProjectState >, pub u32);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PlanManager_get_master_plan_and_result<Logic: PlanningLogic>(pub PlanningUIID < Logic >);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_PlanManager_get_project_preview_update<Logic: PlanningLogic>(pub PlanningUIID < Logic >, pub ProjectID, pub KnownPlanResultState < Logic :: PrototypeKind >);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
@@ -79,8 +85,14 @@ pub fn auto_setup<Logic: PlanningLogic>(system: &mut ActorSystem) {
     
     
     system.add_handler::<PlanManager<Logic>, _, _>(
-        |&MSG_PlanManager_get_all_plans::<Logic>(ui, ref known_master, ref known_projects), instance, world| {
-            instance.get_all_plans(ui, known_master, known_projects, world); Fate::Live
+        |&MSG_PlanManager_get_all_plans::<Logic>(ui, ref known_master, ref known_projects, known_timeline_len), instance, world| {
+            instance.get_all_plans(ui, known_master, known_projects, known_timeline_len, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<PlanManager<Logic>, _, _>(
+        |&MSG_PlanManager_get_master_plan_and_result::<Logic>(ui), instance, world| {
+            instance.get_master_plan_and_result(ui, world); Fate::Live
         }, false
     );
     
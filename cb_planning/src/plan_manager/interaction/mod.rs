@@ -1,8 +1,10 @@
 use kay::{World};
-use compact::{CHashMap, COption};
+use compact::{CHashMap, COption, CVec};
 use descartes::{P2, AreaError, LinePath};
 use ::{PlanHistory, PlanResult, ActionGroups, KnownHistoryState, KnownProjectState, ProjectUpdate,
-PlanningLogic, GestureID, Gesture, Plan, KnownPlanResultState};
+PlanningLogic, GestureID, Gesture, Plan, KnownPlanResultState, VersionedGesture, PlanIssue,
+PlanIssueKind, StepID};
+use ::snapping::{snap_point, SnappingAnchor};
 use super::{PlanManager, PlanManagerID, ProjectID};
 use super::ui::PlanningUIID;
 use cb_util::log::error;
@@ -13,17 +15,30 @@ pub struct PreviewSet<Logic: PlanningLogic> {
     history: PlanHistory<Logic::GestureIntent>,
     result: COption<PlanResult<Logic::PrototypeKind>>,
     actions: COption<ActionGroups>,
+    issues: CVec<PlanIssue>,
+}
+
+/// The gestures to blame for a plan whose result couldn't be calculated,
+/// remembered so that they are only searched for once per plan
+#[derive(Compact, Clone)]
+pub struct CalculationFailure {
+    steps: CVec<StepID>,
+    issue: PlanIssue,
 }
 
 #[derive(Compact, Clone)]
 pub struct PlanManagerUIState<Logic: PlanningLogic> {
     previews: CHashMap<ProjectID, PreviewSet<Logic>>,
+    /// Survives invalidating the previews, since the same failing plan is previewed
+    /// again and again while the user is looking at it
+    calculation_failures: CHashMap<ProjectID, CalculationFailure>,
 }
 
 impl<Logic: PlanningLogic> PlanManagerUIState<Logic> {
     pub fn new() -> Self {
         PlanManagerUIState {
             previews: CHashMap::new(),
+            calculation_failures: CHashMap::new(),
         }
     }
 
@@ -42,6 +57,7 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         ui: PlanningUIID<Logic>,
         known_master: &KnownHistoryState,
         known_projects: &CHashMap<ProjectID, KnownProjectState>,
+        known_timeline_len: u32,
         world: &mut World,
     ) {
         let master_update = self.master_plan.update_for(known_master);
@@ -73,7 +89,23 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
                     .map(|unmatched_id| (unmatched_id, ProjectUpdate::Removed)),
             )
             .collect();
-        ui.on_plans_update(master_update, project_updates_with_removals, world);
+        let timeline_update = self
+            .timeline
+            .iter()
+            .skip(known_timeline_len as usize)
+            .cloned()
+            .collect();
+        ui.on_plans_update(
+            master_update,
+            project_updates_with_removals,
+            timeline_update,
+            world,
+        );
+    }
+
+    /// Sends the complete master plan and its result, for exporting the city
+    pub fn get_master_plan_and_result(&mut self, ui: PlanningUIID<Logic>, world: &mut World) {
+        ui.on_master_plan_and_result(self.master_plan.clone(), self.master_result.clone(), world);
     }
 
     pub fn get_project_preview_update(
@@ -83,7 +115,7 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         known_result: &KnownPlanResultState<Logic::PrototypeKind>,
         world: &mut World,
     ) {
-        let (plan_history, maybe_result, maybe_actions) =
+        let (plan_history, maybe_result, maybe_actions, issues) =
             self.try_ensure_preview(project_id, world);
 
         if let (Some(result), Some(actions)) = (maybe_result, maybe_actions) {
@@ -92,8 +124,11 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
                 plan_history.clone(),
                 result.update_for(known_result),
                 actions.clone(),
+                issues.clone(),
                 world,
             );
+        } else {
+            ui.on_project_preview_failed(project_id, issues.clone(), world);
         }
     }
 
@@ -106,6 +141,7 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         &PlanHistory<Logic::GestureIntent>,
         Option<&PlanResult<Logic::PrototypeKind>>,
         Option<&ActionGroups>,
+        &CVec<PlanIssue>,
     ) {
         if !self.ui_state.previews.contains_key(project_id) {
             let preview_history = self
@@ -130,12 +166,21 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
                 .as_ref()
                 .map(|preview_plan_result| self.master_result.actions_to(preview_plan_result).0);
 
+            let mut issues = Logic::validate(&preview_history, maybe_preview_result.as_ref());
+
+            if maybe_preview_result.is_none() {
+                issues.push(self.calculation_failure_issue(project_id, &preview_history));
+            } else {
+                self.ui_state.calculation_failures.remove(project_id);
+            }
+
             self.ui_state.previews.insert(
                 project_id,
                 PreviewSet {
                     history: preview_history,
                     result: COption(maybe_preview_result),
                     actions: COption(maybe_preview_actions),
+                    issues: issues.into(),
                 },
             );
         }
@@ -150,9 +195,100 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
             &preview_set.history,
             preview_set.result.as_ref(),
             preview_set.actions.as_ref(),
+            &preview_set.issues,
         )
     }
 
+    fn calculation_failure_issue(
+        &mut self,
+        project_id: ProjectID,
+        failed_history: &PlanHistory<Logic::GestureIntent>,
+    ) -> PlanIssue {
+        if let Some(failure) = self.ui_state.calculation_failures.get(project_id) {
+            if failure.steps[..] == failed_history.steps[..] {
+                return failure.issue.clone();
+            }
+        }
+
+        let issue = self.find_calculation_failure_culprits(project_id, failed_history);
+        self.ui_state.calculation_failures.insert(
+            project_id,
+            CalculationFailure {
+                steps: failed_history.steps.clone(),
+                issue: issue.clone(),
+            },
+        );
+        issue
+    }
+
+    /// Bisects the gestures touched by the project, leaving out half of the suspects
+    /// at a time, until the ones without which the plan result can be calculated again
+    /// are found. If neither half alone is to blame, all remaining suspects are
+    fn find_calculation_failure_culprits(
+        &self,
+        project_id: ProjectID,
+        failed_history: &PlanHistory<Logic::GestureIntent>,
+    ) -> PlanIssue {
+        let project = self.projects.get(project_id).unwrap();
+
+        let mut suspects = project
+            .undoable_history
+            .iter()
+            .chain(Some(&project.ongoing))
+            .flat_map(|step| step.gestures.keys().cloned())
+            .collect::<Vec<_>>();
+        suspects.sort_by_key(|gesture_id| gesture_id.0);
+        suspects.dedup();
+
+        let calculates_without = |gestures: &[GestureID]| {
+            let mut history_without = failed_history.clone();
+            for gesture_id in gestures {
+                history_without.gestures.remove(*gesture_id);
+            }
+            Logic::calculate_result(&history_without).is_ok()
+        };
+
+        while suspects.len() > 1 {
+            let second_half = suspects.split_off(suspects.len() / 2);
+            if calculates_without(&suspects) {
+                // the culprits are in the first half
+            } else if calculates_without(&second_half) {
+                suspects = second_half;
+            } else {
+                suspects.extend(second_half);
+                break;
+            }
+        }
+
+        PlanIssue::new(suspects, PlanIssueKind::CalculationFailed)
+    }
+
+    fn snap_in_project(
+        &self,
+        project_id: ProjectID,
+        gesture_id: GestureID,
+        intent: &Logic::GestureIntent,
+        point: P2,
+        anchor: Option<SnappingAnchor>,
+    ) -> P2 {
+        let mut current_gestures = self
+            .master_plan
+            .gestures
+            .pairs()
+            .map(|(gesture_id, VersionedGesture(gesture, _))| (*gesture_id, gesture))
+            .collect::<::std::collections::HashMap<_, _>>();
+
+        if let Some(project) = self.projects.get(project_id) {
+            for step in project.current_history() {
+                for (gesture_id, gesture) in step.gestures.pairs() {
+                    current_gestures.insert(*gesture_id, gesture);
+                }
+            }
+        }
+
+        snap_point(point, gesture_id, intent, anchor, current_gestures)
+    }
+
     pub fn start_new_gesture(
         &mut self,
         project_id: ProjectID,
@@ -161,6 +297,7 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         start: P2,
         _: &mut World,
     ) {
+        let start = self.snap_in_project(project_id, new_gesture_id, intent, start, None);
         let new_gesture = Gesture::new(vec![start].into(), intent.clone());
 
         let new_step = Plan::from_gestures(Some((new_gesture_id, new_gesture)));
@@ -186,6 +323,30 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         let new_step = {
             let current_gesture = self.get_current_version_of(gesture_id, project_id);
 
+            let n_points = current_gesture.points.len();
+            let anchor = if n_points == 0 {
+                None
+            } else if add_to_end {
+                Some(SnappingAnchor::from_neighbors(
+                    &current_gesture.points,
+                    n_points - 1,
+                    n_points.checked_sub(2),
+                ))
+            } else {
+                Some(SnappingAnchor::from_neighbors(
+                    &current_gesture.points,
+                    0,
+                    Some(1).filter(|&idx| idx < n_points),
+                ))
+            };
+            let new_point = self.snap_in_project(
+                project_id,
+                gesture_id,
+                &current_gesture.intent,
+                new_point,
+                anchor,
+            );
+
             let changed_gesture = if add_to_end {
                 Gesture {
                     points: current_gesture
@@ -280,12 +441,37 @@ impl<Logic: PlanningLogic> PlanManager<Logic> {
         let current_change = {
             let current_gesture = self.get_current_version_of(gesture_id, project_id);
 
-            if point_index as usize >= current_gesture.points.len() {
+            let point_index = point_index as usize;
+
+            if point_index >= current_gesture.points.len() {
                 return;
             }
 
+            let anchor = if point_index > 0 {
+                Some(SnappingAnchor::from_neighbors(
+                    &current_gesture.points,
+                    point_index - 1,
+                    point_index.checked_sub(2),
+                ))
+            } else if current_gesture.points.len() > 1 {
+                Some(SnappingAnchor::from_neighbors(
+                    &current_gesture.points,
+                    1,
+                    Some(2).filter(|&idx| idx < current_gesture.points.len()),
+                ))
+            } else {
+                None
+            };
+            let new_position = self.snap_in_project(
+                project_id,
+                gesture_id,
+                &current_gesture.intent,
+                new_position,
+                anchor,
+            );
+
             let mut new_gesture_points = current_gesture.points.clone();
-            new_gesture_points[point_index as usize] = new_position;
+            new_gesture_points[point_index] = new_position;
 
             let new_gesture = Gesture {
                 points: new_gesture_points,
@@ -62,8 +62,16 @@ impl<Logic: PlanningLogic + 'static> PlanManagerID<Logic> {
         id
     }
     
-    pub fn start_new_project(self, project_id: ProjectID, world: &mut World) {
-        world.send(self.as_raw(), MSG_PlanManager_start_new_project(project_id));
+    pub fn start_new_project(self, project_id: ProjectID, name: CString, author: CString, description: CString, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanManager_start_new_project(project_id, name, author, description));
+    }
+    
+    pub fn fork_project(self, project_id: ProjectID, from_step: StepID, name: CString, author: CString, description: CString, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanManager_fork_project(project_id, from_step, name, author, description));
+    }
+    
+    pub fn revert_project(self, project_id: ProjectID, reverted_project_id: ProjectID, name: CString, author: CString, description: CString, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanManager_revert_project(project_id, reverted_project_id, name, author, description));
     }
     
     pub fn implement(self, project_id: ProjectID, world: &mut World) {
@@ -78,18 +86,27 @@ impl<Logic: PlanningLogic + 'static> PlanManagerID<Logic> {
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_PlanManager_spawn<Logic: PlanningLogic + 'static>(pub PlanManagerID<Logic>, );
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_PlanManager_start_new_project(pub ProjectID);
+struct MSG_PlanManager_start_new_project(pub ProjectID, pub CString, pub CString, pub CString);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PlanManager_fork_project(pub ProjectID, pub StepID, pub CString, pub CString, pub CString);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PlanManager_revert_project(pub ProjectID, pub ProjectID, pub CString, pub CString, pub CString);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_PlanManager_implement(pub ProjectID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_PlanManager_implement_artificial_project<Logic: PlanningLogic + 'static>(pub Project < Logic :: GestureIntent >, pub CVec < PrototypeID >);
 
+impl<Logic: PlanningLogic + 'static> Into<TemporalID> for PlanManagerID<Logic> {
+    fn into(self) -> TemporalID {
+        TemporalID::from_raw(self.as_raw())
+    }
+}
 
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 pub fn auto_setup<Logic: PlanningLogic + 'static>(system: &mut ActorSystem) {
     
-    
+    TemporalID::register_implementor::<PlanManager<Logic>>(system);
     system.add_spawner::<PlanManager<Logic>, _, _>(
         |&MSG_PlanManager_spawn::<Logic>(id, ), world| {
             PlanManager::<Logic>::spawn(id, world)
@@ -97,8 +114,20 @@ pub fn auto_setup<Logic: PlanningLogic + 'static>(system: &mut ActorSystem) {
     );
     
     system.add_handler::<PlanManager<Logic>, _, _>(
-        |&MSG_PlanManager_start_new_project(project_id), instance, world| {
-            instance.start_new_project(project_id, world); Fate::Live
+        |&MSG_PlanManager_start_new_project(project_id, ref name, ref author, ref description), instance, world| {
+            instance.start_new_project(project_id, name, author, description, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<PlanManager<Logic>, _, _>(
+        |&MSG_PlanManager_fork_project(project_id, from_step, ref name, ref author, ref description), instance, world| {
+            instance.fork_project(project_id, from_step, name, author, description, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<PlanManager<Logic>, _, _>(
+        |&MSG_PlanManager_revert_project(project_id, reverted_project_id, ref name, ref author, ref description), instance, world| {
+            instance.revert_project(project_id, reverted_project_id, name, author, description, world); Fate::Live
         }, false
     );
     
@@ -1,8 +1,10 @@
 use kay::{World, ActorSystem, TypedID};
 use ::construction::ConstructionID;
 use ::{PlanHistory, PlanResult, Gesture, Project, GestureID, PrototypeID, VersionedGesture,
-PlanningLogic};
-use compact::{CVec, CHashMap};
+PlanningLogic, ProjectMetadata, ProjectOrigin, StepID, Plan};
+use compact::{CVec, CHashMap, CString};
+use cb_time::actors::{Temporal, TemporalID};
+use cb_time::units::Instant;
 use cb_util::random::{Uuid, uuid};
 use cb_util::log::{error, info};
 const LOG_T: &str = "Planning";
@@ -20,6 +22,16 @@ impl ProjectID {
     }
 }
 
+/// One implemented project in the master plan timeline,
+/// `master_step` being the latest master step right after implementing it
+#[derive(Compact, Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub project_id: ProjectID,
+    pub metadata: ProjectMetadata,
+    pub implemented_at: Instant,
+    pub master_step: StepID,
+}
+
 #[derive(Compact, Clone)]
 //#[derive(Clone)]
 pub struct PlanManager<Logic: PlanningLogic + 'static> {
@@ -28,6 +40,11 @@ pub struct PlanManager<Logic: PlanningLogic + 'static> {
     master_result: PlanResult<Logic::PrototypeKind>,
     projects: CHashMap<ProjectID, Project<Logic::GestureIntent>>,
     implemented_projects: CHashMap<ProjectID, Project<Logic::GestureIntent>>,
+    timeline: CVec<TimelineEntry>,
+    /// The master plan as it was when the timeline started being recorded,
+    /// which all timeline entries are replayed on top of
+    timeline_base: PlanHistory<Logic::GestureIntent>,
+    current_instant: Instant,
     ui_state: PlanManagerUIState<Logic>,
 }
 
@@ -41,6 +58,9 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
             master_result: PlanResult::new(),
             projects: CHashMap::new(),
             implemented_projects: CHashMap::new(),
+            timeline: CVec::new(),
+            timeline_base: PlanHistory::new(),
+            current_instant: Instant::new(0),
             ui_state: PlanManagerUIState::new(),
         }
     }
@@ -69,8 +89,192 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
             .expect("Expected gesture (that point should be added to) to exist!")
     }
 
-    pub fn start_new_project(&mut self, project_id: ProjectID, _: &mut World) {
-        self.projects.insert(project_id, Project::new());
+    fn new_project_metadata(
+        &self,
+        name: &CString,
+        author: &CString,
+        description: &CString,
+        origin: ProjectOrigin,
+    ) -> ProjectMetadata {
+        ProjectMetadata {
+            name: name.clone(),
+            author: author.clone(),
+            description: description.clone(),
+            created_at: self.current_instant,
+            origin,
+        }
+    }
+
+    pub fn start_new_project(
+        &mut self,
+        project_id: ProjectID,
+        name: &CString,
+        author: &CString,
+        description: &CString,
+        _: &mut World,
+    ) {
+        let mut project = Project::new();
+        project.metadata = self.new_project_metadata(name, author, description, ProjectOrigin::New);
+        self.projects.insert(project_id, project);
+    }
+
+    /// Cities that were built before the timeline existed start it with one entry
+    /// standing for all of their history, so they can be forked back to that state
+    fn ensure_timeline_seeded(&mut self) {
+        if self.timeline.is_empty()
+            && self.timeline_base.gestures.is_empty()
+            && !self.master_plan.gestures.is_empty()
+        {
+            self.timeline_base = self.master_plan.clone();
+            let mut metadata = ProjectMetadata::new();
+            metadata.name = "City before the timeline".to_owned().into();
+            metadata.created_at = self.current_instant;
+            metadata.origin = ProjectOrigin::BeforeTimeline;
+            self.timeline.push(TimelineEntry {
+                // not an actual implemented project, so it can't be reverted
+                project_id: ProjectID::new(),
+                metadata,
+                implemented_at: self.current_instant,
+                master_step: self.timeline_base.latest_step_id(),
+            });
+        }
+    }
+
+    /// Reconstructs the master plan as it was right after `step_id`
+    /// by replaying the steps of all implemented projects up to it
+    fn master_plan_at(&self, step_id: StepID) -> Option<PlanHistory<Logic::GestureIntent>> {
+        let mut history = self.timeline_base.clone();
+        // artificial projects may have been added to the base since
+        if history.steps.contains(&step_id) {
+            return Some(history);
+        }
+
+        for entry in &self.timeline {
+            let project = match self.implemented_projects.get(entry.project_id) {
+                Some(project) => project,
+                None => continue,
+            };
+            for step in project.current_history() {
+                history.push_plan(step);
+                if step.step_id == step_id {
+                    return Some(history);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Reconstructs the master plan as it was right before
+    /// the project at `timeline_idx` was implemented
+    fn master_plan_before(&self, timeline_idx: usize) -> PlanHistory<Logic::GestureIntent> {
+        let mut history = self.timeline_base.clone();
+
+        for entry in &self.timeline[..timeline_idx] {
+            if let Some(project) = self.implemented_projects.get(entry.project_id) {
+                for step in project.current_history() {
+                    history.push_plan(step);
+                }
+            }
+        }
+
+        history
+    }
+
+    /// Starts a new project that, when implemented,
+    /// restores the master plan to how it was right after `from_step`
+    pub fn fork_project(
+        &mut self,
+        project_id: ProjectID,
+        from_step: StepID,
+        name: &CString,
+        author: &CString,
+        description: &CString,
+        world: &mut World,
+    ) {
+        self.ensure_timeline_seeded();
+        if let Some(past_master_plan) = self.master_plan_at(from_step) {
+            let mut project = Project::from_plan(self.master_plan.plan_towards(&past_master_plan));
+            project.metadata = self.new_project_metadata(
+                name,
+                author,
+                description,
+                ProjectOrigin::ForkedFrom(from_step),
+            );
+            self.projects.insert(project_id, project);
+        } else {
+            error(
+                LOG_T,
+                format!("Can't fork from unknown master step {:?}", from_step),
+                self.id,
+                world,
+            );
+        }
+    }
+
+    /// Starts a new project that undoes all changes of an implemented project,
+    /// except for gestures that later projects changed again
+    pub fn revert_project(
+        &mut self,
+        project_id: ProjectID,
+        reverted_project_id: ProjectID,
+        name: &CString,
+        author: &CString,
+        description: &CString,
+        world: &mut World,
+    ) {
+        self.ensure_timeline_seeded();
+        let maybe_timeline_idx = self
+            .timeline
+            .iter()
+            .position(|entry| entry.project_id == reverted_project_id);
+
+        if let (Some(timeline_idx), Some(reverted_project)) = (
+            maybe_timeline_idx,
+            self.implemented_projects.get(reverted_project_id),
+        ) {
+            let master_plan_before = self.master_plan_before(timeline_idx);
+            let reverted_steps = reverted_project
+                .current_history()
+                .iter()
+                .map(|step| step.step_id)
+                .collect::<Vec<_>>();
+
+            let reverting_gestures = reverted_project
+                .current_history()
+                .iter()
+                .flat_map(|step| step.gestures.keys())
+                .filter_map(|gesture_id| {
+                    let VersionedGesture(current_gesture, current_step) =
+                        self.master_plan.gestures.get(*gesture_id)?;
+                    if !reverted_steps.contains(current_step) {
+                        return None;
+                    }
+                    Some(match master_plan_before.gestures.get(*gesture_id) {
+                        Some(VersionedGesture(gesture_before, _)) => {
+                            (*gesture_id, gesture_before.clone())
+                        }
+                        None => (*gesture_id, current_gesture.as_deleted()),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut project = Project::from_plan(Plan::from_gestures(reverting_gestures));
+            project.metadata = self.new_project_metadata(
+                name,
+                author,
+                description,
+                ProjectOrigin::RevertOf(reverted_project_id),
+            );
+            self.projects.insert(project_id, project);
+        } else {
+            error(
+                LOG_T,
+                format!("Can't revert unknown project {:?}", reverted_project_id),
+                self.id,
+                world,
+            );
+        }
     }
 
     pub fn implement(&mut self, project_id: ProjectID, world: &mut World) {
@@ -79,9 +283,28 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
             .remove(project_id)
             .expect("Project should exist");
 
-        self.master_plan = project.apply_to(&self.master_plan);
+        self.ensure_timeline_seeded();
+        if self.apply_to_master_plan(&project, world) {
+            self.timeline.push(TimelineEntry {
+                project_id,
+                metadata: project.metadata.clone(),
+                implemented_at: self.current_instant,
+                master_step: self.master_plan.latest_step_id(),
+            });
+            self.implemented_projects.insert(project_id, project);
+        }
+    }
+
+    /// Leaves the master plan as it was if the result of applying the project can't be
+    /// calculated, so replaying the timeline always ends up at the master plan
+    fn apply_to_master_plan(
+        &mut self,
+        project: &Project<Logic::GestureIntent>,
+        world: &mut World,
+    ) -> bool {
+        let new_master_plan = project.apply_to(&self.master_plan);
 
-        match Logic::calculate_result(&self.master_plan) {
+        match Logic::calculate_result(&new_master_plan) {
             Ok(result) => {
                 let (actions, new_prototypes) = self.master_result.actions_to(&result);
                 ConstructionID::<Logic::PrototypeKind>::global_first(world).implement(
@@ -89,10 +312,11 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
                     new_prototypes,
                     world,
                 );
-                self.implemented_projects.insert(project_id, project);
+                self.master_plan = new_master_plan;
                 self.master_result = result;
 
                 self.ui_state.invalidate_all();
+                true
             }
             Err(err) => {
                 let err_str = match err {
@@ -102,10 +326,13 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
                     _ => format!("Implement Plan Error: {:?}", err),
                 };
                 error(LOG_T, err_str, self.id, world);
+                false
             }
         }
     }
 
+    /// Artificial projects don't show up in the timeline. They become part of its base
+    /// instead, so forking and reverting leave them alone
     pub fn implement_artificial_project(
         &mut self,
         project: &Project<Logic::GestureIntent>,
@@ -116,9 +343,10 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
             .iter()
             .all(|prototype_id| self.master_result.prototypes.contains_key(*prototype_id))
         {
-            let project_id = ProjectID::new();
-            self.projects.insert(project_id, project.clone());
-            self.implement(project_id, world);
+            self.ensure_timeline_seeded();
+            if self.apply_to_master_plan(project, world) {
+                self.timeline_base = project.apply_to(&self.timeline_base);
+            }
         } else {
             info(
                 LOG_T,
@@ -130,6 +358,14 @@ impl<Logic: PlanningLogic + 'static> PlanManager<Logic> {
     }
 }
 
+impl<Logic: PlanningLogic + 'static> Temporal for PlanManager<Logic> {
+    fn tick(&mut self, _dt: f32, current_instant: Instant, _: &mut World) {
+        self.current_instant = current_instant;
+        // right after loading a city, before anyone looks at its timeline
+        self.ensure_timeline_seeded();
+    }
+}
+
 pub fn setup<Logic: PlanningLogic + 'static>(system: &mut ActorSystem) {
     system.register::<PlanManager<Logic>>();
     auto_setup::<Logic>(system);
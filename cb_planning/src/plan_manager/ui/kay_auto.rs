@@ -50,40 +50,66 @@ impl<Logic: PlanningLogic> TypedID for PlanningUIID<Logic> {
 impl<Logic: PlanningLogic, Act: Actor + PlanningUI<Logic>> TraitIDFrom<Act> for PlanningUIID<Logic> {}
 
 impl<Logic: PlanningLogic> PlanningUIID<Logic> {
-    pub fn on_plans_update(self, master_update: PlanHistoryUpdate < Logic :: GestureIntent >, project_updates: CHashMap < ProjectID , ProjectUpdate < Logic :: GestureIntent > >, world: &mut World) {
-        world.send(self.as_raw(), MSG_PlanningUI_on_plans_update::<Logic>(master_update, project_updates));
+    pub fn on_plans_update(self, master_update: PlanHistoryUpdate < Logic :: GestureIntent >, project_updates: CHashMap < ProjectID , ProjectUpdate < Logic :: GestureIntent > >, timeline_update: CVec < TimelineEntry >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanningUI_on_plans_update::<Logic>(master_update, project_updates, timeline_update));
     }
     
-    pub fn on_project_preview_update(self, project_id: ProjectID, effective_history: PlanHistory < Logic :: GestureIntent >, result_update: PlanResultUpdate < Logic :: PrototypeKind >, new_actions: ActionGroups, world: &mut World) {
-        world.send(self.as_raw(), MSG_PlanningUI_on_project_preview_update::<Logic>(project_id, effective_history, result_update, new_actions));
+    pub fn on_project_preview_update(self, project_id: ProjectID, effective_history: PlanHistory < Logic :: GestureIntent >, result_update: PlanResultUpdate < Logic :: PrototypeKind >, new_actions: ActionGroups, issues: CVec < PlanIssue >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanningUI_on_project_preview_update::<Logic>(project_id, effective_history, result_update, new_actions, issues));
+    }
+    
+    pub fn on_project_preview_failed(self, project_id: ProjectID, issues: CVec < PlanIssue >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanningUI_on_project_preview_failed::<Logic>(project_id, issues));
+    }
+    
+    pub fn on_master_plan_and_result(self, master_plan: PlanHistory < Logic :: GestureIntent >, master_result: PlanResult < Logic :: PrototypeKind >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PlanningUI_on_master_plan_and_result::<Logic>(master_plan, master_result));
     }
 
     pub fn register_trait(system: &mut ActorSystem) {
         system.register_trait::<PlanningUIRepresentative<Logic>>();
         system.register_trait_message::<MSG_PlanningUI_on_plans_update<Logic>>();
         system.register_trait_message::<MSG_PlanningUI_on_project_preview_update<Logic>>();
+        system.register_trait_message::<MSG_PlanningUI_on_project_preview_failed<Logic>>();
+        system.register_trait_message::<MSG_PlanningUI_on_master_plan_and_result<Logic>>();
     }
 
     pub fn register_implementor<Act: Actor + PlanningUI<Logic>>(system: &mut ActorSystem) {
         system.register_implementor::<Act, PlanningUIRepresentative<Logic>>();
         system.add_handler::<Act, _, _>(
-            |&MSG_PlanningUI_on_plans_update::<Logic>(ref master_update, ref project_updates), instance, world| {
-                instance.on_plans_update(master_update, project_updates, world); Fate::Live
+            |&MSG_PlanningUI_on_plans_update::<Logic>(ref master_update, ref project_updates, ref timeline_update), instance, world| {
+                instance.on_plans_update(master_update, project_updates, timeline_update, world); Fate::Live
+            }, false
+        );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_PlanningUI_on_project_preview_update::<Logic>(project_id, ref effective_history, ref result_update, ref new_actions, ref issues), instance, world| {
+                instance.on_project_preview_update(project_id, effective_history, result_update, new_actions, issues, world); Fate::Live
             }, false
         );
         
         system.add_handler::<Act, _, _>(
-            |&MSG_PlanningUI_on_project_preview_update::<Logic>(project_id, ref effective_history, ref result_update, ref new_actions), instance, world| {
-                instance.on_project_preview_update(project_id, effective_history, result_update, new_actions, world); Fate::Live
+            |&MSG_PlanningUI_on_project_preview_failed::<Logic>(project_id, ref issues), instance, world| {
+                instance.on_project_preview_failed(project_id, issues, world); Fate::Live
+            }, false
+        );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_PlanningUI_on_master_plan_and_result::<Logic>(ref master_plan, ref master_result), instance, world| {
+                instance.on_master_plan_and_result(master_plan, master_result, world); Fate::Live
             }, false
         );
     }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_PlanningUI_on_plans_update<Logic: PlanningLogic>(pub PlanHistoryUpdate < Logic :: GestureIntent >, pub CHashMap < ProjectID , ProjectUpdate < Logic :: GestureIntent > >);
+struct MSG_PlanningUI_on_plans_update<Logic: PlanningLogic>(pub PlanHistoryUpdate < Logic :: GestureIntent >, pub CHashMap < ProjectID , ProjectUpdate < Logic :: GestureIntent > >, pub CVec < TimelineEntry >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PlanningUI_on_project_preview_update<Logic: PlanningLogic>(pub ProjectID, pub PlanHistory < Logic :: GestureIntent >, pub PlanResultUpdate < Logic :: PrototypeKind >, pub ActionGroups, pub CVec < PlanIssue >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PlanningUI_on_project_preview_failed<Logic: PlanningLogic>(pub ProjectID, pub CVec < PlanIssue >);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_PlanningUI_on_project_preview_update<Logic: PlanningLogic>(pub ProjectID, pub PlanHistory < Logic :: GestureIntent >, pub PlanResultUpdate < Logic :: PrototypeKind >, pub ActionGroups);
+struct MSG_PlanningUI_on_master_plan_and_result<Logic: PlanningLogic>(pub PlanHistory < Logic :: GestureIntent >, pub PlanResult < Logic :: PrototypeKind >);
 
 
 
@@ -1,14 +1,15 @@
 use kay::World;
-use compact::CHashMap;
-use ::{PlanHistory, PlanHistoryUpdate, ProjectUpdate, PlanResultUpdate, ActionGroups,
-PlanningLogic};
-use super::ProjectID;
+use compact::{CHashMap, CVec};
+use ::{PlanHistory, PlanHistoryUpdate, ProjectUpdate, PlanResult, PlanResultUpdate, ActionGroups,
+PlanningLogic, PlanIssue};
+use super::{ProjectID, TimelineEntry};
 
 pub trait PlanningUI<Logic: PlanningLogic> {
     fn on_plans_update(
         &mut self,
         master_update: &PlanHistoryUpdate<Logic::GestureIntent>,
         project_updates: &CHashMap<ProjectID, ProjectUpdate<Logic::GestureIntent>>,
+        timeline_update: &CVec<TimelineEntry>,
         _world: &mut World,
     );
 
@@ -18,6 +19,21 @@ pub trait PlanningUI<Logic: PlanningLogic> {
         effective_history: &PlanHistory<Logic::GestureIntent>,
         result_update: &PlanResultUpdate<Logic::PrototypeKind>,
         new_actions: &ActionGroups,
+        issues: &CVec<PlanIssue>,
+        _world: &mut World,
+    );
+
+    fn on_project_preview_failed(
+        &mut self,
+        _project_id: ProjectID,
+        issues: &CVec<PlanIssue>,
+        _world: &mut World,
+    );
+
+    fn on_master_plan_and_result(
+        &mut self,
+        master_plan: &PlanHistory<Logic::GestureIntent>,
+        master_result: &PlanResult<Logic::PrototypeKind>,
         _world: &mut World,
     );
 }
@@ -0,0 +1,231 @@
+use descartes::{N, P2, V2, WithUniqueOrthogonal};
+use construction::GestureIntent;
+use {Gesture, GestureID};
+
+#[derive(Copy, Clone, Debug)]
+pub struct SnappingConfig {
+    pub to_endpoints: Option<N>,
+    pub to_intersections: Option<N>,
+    pub to_parallels: Option<N>,
+    pub angle_increment: Option<N>,
+    pub segment_length: Option<N>,
+}
+
+impl SnappingConfig {
+    pub fn none() -> SnappingConfig {
+        SnappingConfig {
+            to_endpoints: None,
+            to_intersections: None,
+            to_parallels: None,
+            angle_increment: None,
+            segment_length: None,
+        }
+    }
+}
+
+impl Default for SnappingConfig {
+    fn default() -> SnappingConfig {
+        SnappingConfig::none()
+    }
+}
+
+/// Where a new or moved point sits within its gesture, used for
+/// angle and segment length snapping relative to its neighbors
+#[derive(Copy, Clone, Debug)]
+pub struct SnappingAnchor {
+    pub point: P2,
+    pub reference_direction: Option<V2>,
+}
+
+impl SnappingAnchor {
+    /// `away_from_idx` is the neighbor's own other neighbor, if it has one
+    pub fn from_neighbors(
+        points: &[P2],
+        neighbor_idx: usize,
+        away_from_idx: Option<usize>,
+    ) -> Self {
+        let point = points[neighbor_idx];
+        let reference_direction = away_from_idx
+            .map(|away_from_idx| point - points[away_from_idx])
+            .filter(|direction| direction.norm() > 0.01)
+            .map(|direction| direction.normalize());
+        SnappingAnchor {
+            point,
+            reference_direction,
+        }
+    }
+}
+
+pub fn snap_point<'a, GI, I>(
+    point: P2,
+    gesture_id: GestureID,
+    intent: &GI,
+    anchor: Option<SnappingAnchor>,
+    other_gestures: I,
+) -> P2
+where
+    GI: GestureIntent,
+    I: IntoIterator<Item = (GestureID, &'a Gesture<GI>)>,
+{
+    let config = intent.snapping();
+
+    let other_gestures = other_gestures
+        .into_iter()
+        .filter(|(other_id, other)| *other_id != gesture_id && !other.deleted)
+        .collect::<Vec<_>>();
+
+    let point_snapping_gestures = other_gestures
+        .iter()
+        .filter(|(_, other)| intent.snaps_to_points_of(&other.intent))
+        .map(|(_, other)| *other)
+        .collect::<Vec<_>>();
+
+    if let Some(radius) = config.to_endpoints {
+        let endpoints = point_snapping_gestures
+            .iter()
+            .flat_map(|other| other.points.first().into_iter().chain(other.points.last()))
+            .cloned();
+
+        if let Some(endpoint) = closest_within(point, endpoints, radius) {
+            return endpoint;
+        }
+    }
+
+    if let Some(radius) = config.to_intersections {
+        let nearby_segments = point_snapping_gestures
+            .iter()
+            .flat_map(|other| other.points.windows(2))
+            .filter(|segment| distance_to_segment(point, segment[0], segment[1]) < radius)
+            .collect::<Vec<_>>();
+
+        let intersections = nearby_segments
+            .iter()
+            .enumerate()
+            .flat_map(|(i, segment_a)| {
+                nearby_segments[i + 1..].iter().filter_map(move |segment_b| {
+                    segment_intersection(segment_a[0], segment_a[1], segment_b[0], segment_b[1])
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(intersection) = closest_within(point, intersections, radius) {
+            return intersection;
+        }
+    }
+
+    if let Some(radius) = config.to_parallels {
+        let mut best_correction: Option<V2> = None;
+
+        for (_, other) in &other_gestures {
+            if let Some((left_offset, right_offset)) = intent.parallel_offsets_to(&other.intent) {
+                for segment in other.points.windows(2) {
+                    let (start, end) = (segment[0], segment[1]);
+                    let length = (end - start).norm();
+                    if length < 0.01 {
+                        continue;
+                    }
+                    let direction = (end - start) / length;
+                    let along = (point - start).dot(&direction);
+                    if along < 0.0 || along > length {
+                        continue;
+                    }
+                    let right = direction.orthogonal_right();
+                    let signed_distance = (point - start).dot(&right);
+                    let target_distance = if signed_distance > 0.0 {
+                        right_offset
+                    } else {
+                        -left_offset
+                    };
+                    let correction = right * (target_distance - signed_distance);
+                    if correction.norm() < radius
+                        && best_correction
+                            .map(|best| correction.norm() < best.norm())
+                            .unwrap_or(true)
+                    {
+                        best_correction = Some(correction);
+                    }
+                }
+            }
+        }
+
+        if let Some(correction) = best_correction {
+            return point + correction;
+        }
+    }
+
+    if let Some(SnappingAnchor {
+        point: anchor_point,
+        reference_direction,
+    }) = anchor
+    {
+        let delta = point - anchor_point;
+        let mut length = delta.norm();
+        if length < 0.01 {
+            return point;
+        }
+        let reference_angle = reference_direction
+            .map(|reference| reference.y.atan2(reference.x))
+            .unwrap_or(0.0);
+        let mut angle = delta.y.atan2(delta.x);
+
+        if let Some(increment) = config.angle_increment {
+            let relative_angle = angle - reference_angle;
+            let snapped_relative_angle = (relative_angle / increment).round() * increment;
+            if (snapped_relative_angle - relative_angle).abs() < increment / 4.0 {
+                angle = reference_angle + snapped_relative_angle;
+            }
+        }
+
+        if let Some(segment_length) = config.segment_length {
+            let snapped_length = (length / segment_length).round().max(1.0) * segment_length;
+            if (snapped_length - length).abs() < segment_length / 4.0 {
+                length = snapped_length;
+            }
+        }
+
+        anchor_point + V2::new(angle.cos(), angle.sin()) * length
+    } else {
+        point
+    }
+}
+
+fn closest_within<I: IntoIterator<Item = P2>>(point: P2, candidates: I, radius: N) -> Option<P2> {
+    candidates
+        .into_iter()
+        .map(|candidate| (candidate, (candidate - point).norm()))
+        .filter(|&(_, distance)| distance < radius)
+        .fold(None, |closest: Option<(P2, N)>, (candidate, distance)| {
+            match closest {
+                Some((_, closest_distance)) if closest_distance <= distance => closest,
+                _ => Some((candidate, distance)),
+            }
+        })
+        .map(|(candidate, _)| candidate)
+}
+
+fn distance_to_segment(point: P2, start: P2, end: P2) -> N {
+    let delta = end - start;
+    let length_squared = delta.norm_squared();
+    if length_squared < 0.0001 {
+        return (point - start).norm();
+    }
+    let t = ((point - start).dot(&delta) / length_squared).max(0.0).min(1.0);
+    (point - (start + delta * t)).norm()
+}
+
+fn segment_intersection(start_a: P2, end_a: P2, start_b: P2, end_b: P2) -> Option<P2> {
+    let delta_a = end_a - start_a;
+    let delta_b = end_b - start_b;
+    let denominator = delta_a.x * delta_b.y - delta_a.y * delta_b.x;
+    if denominator.abs() < 0.0001 {
+        return None;
+    }
+    let between = start_b - start_a;
+    let t = (between.x * delta_b.y - between.y * delta_b.x) / denominator;
+    let u = (between.x * delta_a.y - between.y * delta_a.x) / denominator;
+    if t >= 0.0 && t <= 1.0 && u >= 0.0 && u <= 1.0 {
+        Some(start_a + delta_a * t)
+    } else {
+        None
+    }
+}
@@ -39,7 +39,7 @@ pub struct NetworkConfig {
     pub skip_ratio: usize,
 }
 
-pub fn match_cmd_line_args(version: &str) -> (NetworkConfig, String) {
+pub fn match_cmd_line_args(version: &str) -> (NetworkConfig, String, Option<String>) {
     use self::clap::{Arg, App};
     let matches = App::new("citybound")
         .version(version.trim())
@@ -107,6 +107,12 @@ pub fn match_cmd_line_args(version: &str) -> (NetworkConfig, String) {
                 .default_value("5")
                 .help("How many network turns to skip if server/client are ahead"),
         )
+        .arg(
+            Arg::with_name("import")
+                .long("import")
+                .value_name("file.geojson/file.osm")
+                .help("Road network and land use to import into the city (GeoJSON or OSM XML)"),
+        )
         .get_matches();
 
     (
@@ -119,6 +125,7 @@ pub fn match_cmd_line_args(version: &str) -> (NetworkConfig, String) {
             skip_ratio: matches.value_of("skip-ratio").unwrap().parse().unwrap(),
         },
         matches.value_of("CITY_FOLDER").unwrap().to_owned(),
+        matches.value_of("import").map(str::to_owned),
     )
 }
 
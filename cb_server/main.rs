@@ -15,7 +15,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 fn main() {
-    let (network_config, city_folder) = init::match_cmd_line_args(VERSION);
+    let (network_config, city_folder, maybe_import_path) = init::match_cmd_line_args(VERSION);
 
     init::print_start_message(VERSION, &network_config);
 
@@ -71,6 +71,32 @@ fn main() {
         } else {
             cb_simulation::spawn_for_server(world)
         };
+        if let Some(ref import_path) = maybe_import_path {
+            if savegame_exists {
+                println!(
+                    "Not importing {} into the existing savegame {}, since that would duplicate \
+                     everything imported before. Use a new city folder to import.",
+                    import_path, &city_folder
+                );
+            } else {
+                use cb_simulation::planning::geo_import::{import_file, GeoImportConfig};
+                match import_file(::std::path::Path::new(import_path), GeoImportConfig::default()) {
+                    Ok((project, (longitude, latitude))) => {
+                        println!("Importing {}...", import_path);
+                        cb_simulation::planning::geo_reference::GeoReferenceID::global_first(world)
+                            .set_origin(longitude, latitude, world);
+                        cb_simulation::planning::CBPlanManagerID::global_first(world)
+                            .implement_artificial_project(
+                                project,
+                                cb_simulation::compact::CVec::new(),
+                                world,
+                            );
+                    }
+                    Err(err) => println!("Couldn't import {}: {:?}", import_path, err),
+                }
+            }
+        }
+
         println!(
             "Simulation running.\n(You can stop this process at any point and the savegame should \
              be fine)"
@@ -0,0 +1,96 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for FreightTruck {
+    type ID = FreightTruckID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct FreightTruckID {
+    _raw_id: RawID
+}
+
+impl Copy for FreightTruckID {}
+impl Clone for FreightTruckID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for FreightTruckID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "FreightTruckID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for FreightTruckID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for FreightTruckID {
+    fn eq(&self, other: &FreightTruckID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for FreightTruckID {}
+
+impl TypedID for FreightTruckID {
+    type Target = FreightTruck;
+
+    fn from_raw(id: RawID) -> Self {
+        FreightTruckID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl FreightTruckID {
+    pub fn spawn(supplier: HouseholdID, receiver: HouseholdID, cargo: Inventory, instant: Instant, world: &mut World) -> Self {
+        let id = FreightTruckID::from_raw(world.allocate_instance_id::<FreightTruck>());
+        let swarm = world.local_broadcast::<FreightTruck>();
+        world.send(swarm, MSG_FreightTruck_spawn(id, supplier, receiver, cargo, instant));
+        id
+    }
+
+    pub fn unload(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_FreightTruck_unload());
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FreightTruck_spawn(pub FreightTruckID, pub HouseholdID, pub HouseholdID, pub Inventory, pub Instant);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FreightTruck_unload();
+
+impl Into<TripListenerID> for FreightTruckID {
+    fn into(self) -> TripListenerID {
+        TripListenerID::from_raw(self.as_raw())
+    }
+}
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    TripListenerID::register_implementor::<FreightTruck>(system);
+    system.add_spawner::<FreightTruck, _, _>(
+        |&MSG_FreightTruck_spawn(id, supplier, receiver, ref cargo, instant), world| {
+            FreightTruck::spawn(id, supplier, receiver, cargo, instant, world)
+        }, false
+    );
+
+    system.add_handler::<FreightTruck, _, _>(
+        |&MSG_FreightTruck_unload(), instance, world| {
+            instance.unload(world)
+        }, false
+    );
+}
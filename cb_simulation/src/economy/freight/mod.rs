@@ -0,0 +1,113 @@
+//! Freight: goods that businesses buy from each other are loaded onto trucks at
+//! the supplier and only become available to the buyer once a truck arrives.
+
+use kay::{ActorSystem, World, Fate, Actor};
+use cb_time::units::Instant;
+
+use super::resources::{Entry, Inventory, ResourceAmount};
+use super::households::HouseholdID;
+use transport::pathfinding::RoughLocationID;
+use transport::pathfinding::trip::{TripID, TripListener, TripListenerID, TripResult, TripFate,
+ModeChoice};
+use transport::microtraffic::VehicleType;
+
+use cb_util::log::debug;
+const LOG_T: &str = "Freight";
+
+/// Total amount of goods a single truck can carry
+const TRUCK_CAPACITY: ResourceAmount = 100.0;
+
+/// Loads goods onto as many trucks as needed, sharing the cargo evenly between them
+pub fn dispatch(
+    supplier: HouseholdID,
+    receiver: HouseholdID,
+    goods: &Inventory,
+    instant: Instant,
+    world: &mut World,
+) {
+    let total: ResourceAmount = goods.iter().map(|&Entry(_, amount)| amount).sum();
+    let n_trucks = (total / TRUCK_CAPACITY).ceil().max(1.0);
+    let cargo: Inventory = goods
+        .iter()
+        .map(|&Entry(resource, amount)| (resource, amount / n_trucks))
+        .collect();
+
+    for _ in 0..(n_trucks as usize) {
+        FreightTruckID::spawn(supplier, receiver, cargo.clone(), instant, world);
+    }
+}
+
+#[derive(Compact, Clone)]
+pub struct FreightTruck {
+    id: FreightTruckID,
+    supplier: HouseholdID,
+    receiver: HouseholdID,
+    cargo: Inventory,
+}
+
+impl FreightTruck {
+    pub fn spawn(
+        id: FreightTruckID,
+        supplier: HouseholdID,
+        receiver: HouseholdID,
+        cargo: &Inventory,
+        instant: Instant,
+        world: &mut World,
+    ) -> FreightTruck {
+        TripID::spawn(
+            supplier.into(),
+            receiver.into(),
+            Some(id.into()),
+            ModeChoice::Drive,
+            VehicleType::Truck,
+            instant,
+            world,
+        );
+
+        FreightTruck {
+            id,
+            supplier,
+            receiver,
+            cargo: cargo.clone(),
+        }
+    }
+
+    pub fn unload(&mut self, _: &mut World) -> Fate {
+        Fate::Die
+    }
+}
+
+impl TripListener for FreightTruck {
+    fn trip_created(&mut self, _trip: TripID, _: &mut World) {}
+
+    fn trip_result(
+        &mut self,
+        _trip: TripID,
+        result: TripResult,
+        _rough_source: RoughLocationID,
+        _rough_destination: RoughLocationID,
+        world: &mut World,
+    ) {
+        if let TripFate::Success(_) = result.fate {
+            self.receiver.freight_arrived(self.cargo.clone(), world);
+        } else {
+            // undeliverable goods go back to the supplier instead of vanishing
+            debug(
+                LOG_T,
+                format!("Freight to {:?} failed: {:?}", self.receiver, result.fate),
+                self.id(),
+                world,
+            );
+            self.supplier.freight_arrived(self.cargo.clone(), world);
+        }
+        self.id.unload(world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<FreightTruck>();
+    auto_setup(system);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
@@ -89,6 +89,13 @@ impl Household for Bakery {
             .unwrap_or(0.0)
     }
 
+    fn delivered_by_freight(resource: Resource) -> bool {
+        match resource {
+            Flour | DairyGoods => true,
+            _ => false,
+        }
+    }
+
     fn interesting_resources() -> &'static [Resource] {
         &[
             Resource::Money,
@@ -93,6 +93,13 @@ impl Household for GroceryShop {
             .unwrap_or(0.0)
     }
 
+    fn delivered_by_freight(resource: Resource) -> bool {
+        match resource {
+            Produce | Grain | Flour | BakedGoods | Meat | DairyGoods => true,
+            _ => false,
+        }
+    }
+
     fn interesting_resources() -> &'static [Resource] {
         &[
             Money, Groceries, Produce, Grain, Flour, BakedGoods, Meat, DairyGoods,
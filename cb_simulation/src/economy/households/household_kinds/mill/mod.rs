@@ -86,6 +86,13 @@ impl Household for Mill {
             .unwrap_or(0.0)
     }
 
+    fn delivered_by_freight(resource: Resource) -> bool {
+        match resource {
+            Grain => true,
+            _ => false,
+        }
+    }
+
     fn interesting_resources() -> &'static [Resource] {
         &[Resource::Money, Resource::Grain, Resource::Flour]
     }
@@ -62,6 +62,14 @@ impl HouseholdID {
         world.send(self.as_raw(), MSG_Household_provide_deal(deal, member));
     }
     
+    pub fn undo_receive_deal(self, deal: Deal, member: MemberIdx, world: &mut World) {
+        world.send(self.as_raw(), MSG_Household_undo_receive_deal(deal, member));
+    }
+    
+    pub fn freight_arrived(self, cargo: Inventory, world: &mut World) {
+        world.send(self.as_raw(), MSG_Household_freight_arrived(cargo));
+    }
+    
     pub fn task_succeeded(self, member: MemberIdx, world: &mut World) {
         world.send(self.as_raw(), MSG_Household_task_succeeded(member));
     }
@@ -167,6 +175,8 @@ impl HouseholdID {
         system.register_trait_message::<MSG_Household_decay>();
         system.register_trait_message::<MSG_Household_receive_deal>();
         system.register_trait_message::<MSG_Household_provide_deal>();
+        system.register_trait_message::<MSG_Household_undo_receive_deal>();
+        system.register_trait_message::<MSG_Household_freight_arrived>();
         system.register_trait_message::<MSG_Household_task_succeeded>();
         system.register_trait_message::<MSG_Household_task_failed>();
         system.register_trait_message::<MSG_Household_reset_member_task>();
@@ -214,6 +224,18 @@ impl HouseholdID {
             }, false
         );
         
+        system.add_handler::<Act, _, _>(
+            |&MSG_Household_undo_receive_deal(ref deal, member), instance, world| {
+                instance.undo_receive_deal(deal, member, world); Fate::Live
+            }, false
+        );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_Household_freight_arrived(ref cargo), instance, world| {
+                instance.freight_arrived(cargo, world); Fate::Live
+            }, false
+        );
+        
         system.add_handler::<Act, _, _>(
             |&MSG_Household_task_succeeded(member), instance, world| {
                 instance.task_succeeded(member, world); Fate::Live
@@ -373,6 +395,10 @@ struct MSG_Household_receive_deal(pub Deal, pub MemberIdx);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Household_provide_deal(pub Deal, pub MemberIdx);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Household_undo_receive_deal(pub Deal, pub MemberIdx);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Household_freight_arrived(pub Inventory);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Household_task_succeeded(pub MemberIdx);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Household_task_failed(pub MemberIdx, pub RoughLocationID);
@@ -31,8 +31,10 @@ impl MemberIdx {
 use super::market::{MarketID, Deal, EvaluatedDeal, EvaluationRequester, EvaluationRequesterID,
 TripCostEstimatorID, EvaluatedSearchResult};
 use super::resources::{Resource, ResourceAmount, ResourceMap, Entry, Inventory};
+use super::freight;
 use transport::pathfinding::{RoughLocationID, RoughLocation};
-use transport::pathfinding::trip::{TripListener, TripID, TripResult, TripFate};
+use transport::pathfinding::trip::{TripListener, TripID, TripResult, TripFate, ModeChoice};
+use transport::microtraffic::VehicleType;
 use self::tasks::{Task, TaskState, TaskEndSchedulerID};
 pub use self::offers::{Offer, OfferIdx, OfferID};
 
@@ -61,6 +63,11 @@ pub trait Household:
         -amount * Self::importance(resource, time)
     }
     fn interesting_resources() -> &'static [Resource];
+    /// Goods bought as this resource are brought by truck after the deal
+    /// instead of becoming available right away
+    fn delivered_by_freight(_resource: Resource) -> bool {
+        false
+    }
     fn decay(&mut self, dt: Duration, world: &mut World);
 
     fn household_name(&self) -> String;
@@ -68,7 +75,15 @@ pub trait Household:
 
     fn receive_deal(&mut self, deal: &Deal, member: MemberIdx, _: &mut World) {
         let core = self.core_mut();
-        deal.delta.give_to_shared_private(
+        let mut received_now = Inventory::new();
+        for &Entry(resource, amount) in deal.delta.iter() {
+            if amount > 0.0 && Self::delivered_by_freight(resource) {
+                *core.member_freight[member.as_idx()].mut_entry_or(resource, 0.0) += amount;
+            } else {
+                received_now.insert(resource, amount);
+            }
+        }
+        received_now.give_to_shared_private(
             &mut core.resources,
             &mut core.member_resources[member.as_idx()],
             Self::is_shared,
@@ -88,6 +103,37 @@ pub trait Household:
         }
     }
 
+    /// Takes back what a deal that fell through handed over right away.
+    /// Goods that were still waiting for their truck never arrived, so they are left out
+    fn undo_receive_deal(&mut self, deal: &Deal, member: MemberIdx, world: &mut World) {
+        let mut received_now = Inventory::new();
+        for &Entry(resource, amount) in deal.delta.iter() {
+            if !(amount > 0.0 && Self::delivered_by_freight(resource)) {
+                received_now.insert(resource, amount);
+            }
+        }
+        self.provide_deal(
+            &Deal {
+                duration: deal.duration,
+                delta: received_now,
+            },
+            member,
+            world,
+        );
+    }
+
+    /// Freight is only exchanged between businesses, which share all resources
+    fn freight_arrived(&mut self, cargo: &Inventory, _: &mut World) {
+        cargo.give_to(&mut self.core_mut().resources);
+    }
+
+    fn take_member_freight(&mut self, member: MemberIdx) -> Inventory {
+        ::std::mem::replace(
+            &mut self.core_mut().member_freight[member.as_idx()],
+            Inventory::new(),
+        )
+    }
+
     fn task_succeeded(&mut self, member: MemberIdx, world: &mut World) {
         {
             debug(
@@ -565,6 +611,8 @@ pub trait Household:
                 source,
                 offer.household.into(),
                 Some(self.id_as()),
+                ModeChoice::ByDistance,
+                VehicleType::Car,
                 instant,
                 world,
             );
@@ -658,6 +706,9 @@ pub trait Household:
                     world,
                 );
 
+                // goods that never left the supplier aren't delivered anymore
+                self.take_member_freight(matching_task_member);
+
                 if let Some((_, offer)) =
                     self.core().member_tasks[matching_task_member.as_idx()].goal
                 {
@@ -696,6 +747,11 @@ pub trait Household:
             offer
                 .household
                 .started_actively_using(offer.idx, self.id_as(), member, world);
+
+            let freight = self.take_member_freight(member);
+            if !freight.is_empty() {
+                freight::dispatch(offer.household, self.id_as(), &freight, start, world);
+            }
         }
         self.core_mut().member_tasks[member.as_idx()].state = TaskState::StartedAt(start, location);
     }
@@ -713,6 +769,7 @@ pub trait Household:
                 TripResult {
                     location_now: None,
                     fate: TripFate::ForceStopped,
+                    last_lane: None,
                 },
                 world,
             )
@@ -830,8 +887,16 @@ pub trait Household:
         world: &mut World,
     ) {
         let offer = self.get_offer(offer_idx).clone(); // borrow checker too dumb
-        self.receive_deal(&offer.deal, offer.offering_member, world);
-        requester.provide_deal(offer.deal, requester_member, world);
+        {
+            // the goods never left, so they don't come back by truck either
+            let core = self.core_mut();
+            offer.deal.delta.give_to_shared_private(
+                &mut core.resources,
+                &mut core.member_resources[offer.offering_member.as_idx()],
+                Self::is_shared,
+            );
+        }
+        requester.undo_receive_deal(offer.deal, requester_member, world);
     }
 
     fn started_using(
@@ -1008,6 +1073,8 @@ pub enum DecisionState {
 pub struct HouseholdCore {
     pub resources: Inventory,
     pub member_resources: CVec<Inventory>,
+    /// Goods bought by each member that are waiting to be sent off by the supplier
+    pub member_freight: CVec<Inventory>,
     pub member_tasks: CVec<Task>,
     pub decision_state: DecisionState,
     pub used_offers: ResourceMap<OfferID>,
@@ -1040,6 +1107,7 @@ impl HouseholdCore {
         HouseholdCore {
             resources: Inventory::new(),
             member_resources: vec![Inventory::new(); n_members].into(),
+            member_freight: vec![Inventory::new(); n_members].into(),
             member_tasks: vec![Task::idle_at(initial_location); n_members].into(),
             decision_state: DecisionState::None,
             used_offers: ResourceMap::new(),
@@ -6,11 +6,13 @@ use planning::CBPlanManagerID;
 pub mod resources;
 pub mod market;
 pub mod households;
+pub mod freight;
 pub mod immigration_and_development;
 
 pub fn setup(system: &mut ActorSystem) {
     market::setup(system);
     households::setup(system);
+    freight::setup(system);
     immigration_and_development::setup(system);
 }
 
@@ -85,6 +85,22 @@ impl BuildingID {
     pub fn reconnect(self, new_location: PreciseLocation, new_connection_point: P2, world: &mut World) {
         world.send(self.as_raw(), MSG_Building_reconnect(new_location, new_connection_point));
     }
+    
+    pub fn on_street_parking_found(self, trip: TripID, maybe_spot: Option < (LaneID, PreciseLocation) >, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Building_on_street_parking_found(trip, maybe_spot, instant));
+    }
+    
+    pub fn street_parking_closed(self, lane: LaneID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Building_street_parking_closed(lane));
+    }
+    
+    pub fn offer_street_parking(self, lane: LaneID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Building_offer_street_parking(lane));
+    }
+    
+    pub fn on_waiting_cars_parked(self, lane: LaneID, n_parked: u16, world: &mut World) {
+        world.send(self.as_raw(), MSG_Building_on_waiting_cars_parked(lane, n_parked));
+    }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
@@ -101,6 +117,14 @@ struct MSG_Building_finally_destroy();
 struct MSG_Building_get_ui_info(pub LandUseUIID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Building_reconnect(pub PreciseLocation, pub P2);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Building_on_street_parking_found(pub TripID, pub Option < (LaneID, PreciseLocation) >, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Building_street_parking_closed(pub LaneID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Building_offer_street_parking(pub LaneID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Building_on_waiting_cars_parked(pub LaneID, pub u16);
 
 impl Into<ConstructableID<CBPrototypeKind>> for BuildingID {
     fn into(self) -> ConstructableID<CBPrototypeKind> {
@@ -175,4 +199,28 @@ pub fn auto_setup(system: &mut ActorSystem) {
             instance.reconnect(new_location, new_connection_point, world); Fate::Live
         }, false
     );
+    
+    system.add_handler::<Building, _, _>(
+        |&MSG_Building_on_street_parking_found(trip, maybe_spot, instant), instance, world| {
+            instance.on_street_parking_found(trip, maybe_spot, instant, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Building, _, _>(
+        |&MSG_Building_street_parking_closed(lane), instance, world| {
+            instance.street_parking_closed(lane, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Building, _, _>(
+        |&MSG_Building_offer_street_parking(lane), instance, world| {
+            instance.offer_street_parking(lane, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Building, _, _>(
+        |&MSG_Building_on_waiting_cars_parked(lane, n_parked), instance, world| {
+            instance.on_waiting_cars_parked(lane, n_parked, world); Fate::Live
+        }, false
+    );
 }
\ No newline at end of file
@@ -69,6 +69,7 @@ pub struct Building {
     style: BuildingStyle,
     being_destroyed_for: COption<CBConstructionID>,
     started_reconnect: bool,
+    parking: LotParking,
 }
 
 //use stagemaster::geometry::add_debug_line;
@@ -93,6 +94,7 @@ impl Building {
             style,
             being_destroyed_for: COption(None),
             started_reconnect: false,
+            parking: LotParking::new(parking_capacity_for_style(style)),
         }
     }
 
@@ -282,16 +284,98 @@ impl Building {
 }
 
 use transport::pathfinding::{RoughLocation, RoughLocationID, RoughLocationResolve};
+use transport::pathfinding::trip::TripID;
+use transport::parking::{LotParking, ParkingSpot, MAX_PARKING_SEARCH_HOPS};
 use cb_time::units::Instant;
 
 impl RoughLocation for Building {
     fn resolve(&self) -> RoughLocationResolve {
         RoughLocationResolve::Done(self.location, self.lot.center_point())
     }
+
+    fn take_car(&mut self, trip: TripID, instant: Instant, world: &mut World) {
+        match self.parking.take_car() {
+            Some(ParkingSpot::OnStreet(lane)) => {
+                lane.unpark_car(self.id, trip, self.id_as(), instant, world)
+            }
+            Some(ParkingSpot::Lot) | Some(ParkingSpot::Unparked) => {
+                trip.car_taken(true, instant, world)
+            }
+            None => trip.car_taken(false, instant, world),
+        }
+    }
+
+    fn find_parking(
+        &mut self,
+        trip: TripID,
+        arrival_lane: LaneID,
+        instant: Instant,
+        world: &mut World,
+    ) {
+        if self.parking.try_occupy() {
+            trip.parking_found(ParkingSpot::Lot, None, instant, world);
+        } else {
+            arrival_lane.search_street_parking(
+                self.id,
+                trip,
+                MAX_PARKING_SEARCH_HOPS,
+                instant,
+                world,
+            );
+        }
+    }
+}
+
+impl Building {
+    pub fn on_street_parking_found(
+        &mut self,
+        trip: TripID,
+        maybe_spot: Option<(LaneID, PreciseLocation)>,
+        instant: Instant,
+        world: &mut World,
+    ) {
+        if let Some((lane, spot)) = maybe_spot {
+            self.parking.cars_on_street.push(lane);
+            trip.parking_found(ParkingSpot::OnStreet(lane), Some(spot), instant, world);
+        } else {
+            self.parking.cars_waiting += 1;
+            trip.parking_found(ParkingSpot::Unparked, None, instant, world);
+        }
+    }
+
+    pub fn street_parking_closed(&mut self, lane: LaneID, _: &mut World) {
+        self.parking.displace_car(lane);
+    }
+
+    /// A lane with street parking nearby, where waiting cars could be parked
+    pub fn offer_street_parking(&mut self, lane: LaneID, world: &mut World) {
+        if self.parking.cars_waiting > 0 {
+            lane.park_waiting_cars(self.id, self.parking.cars_waiting, world);
+        }
+    }
+
+    pub fn on_waiting_cars_parked(&mut self, lane: LaneID, n_parked: u16, _: &mut World) {
+        let n_parked = n_parked.min(self.parking.cars_waiting);
+        self.parking.cars_waiting -= n_parked;
+        for _ in 0..n_parked {
+            self.parking.cars_on_street.push(lane);
+        }
+    }
 }
 
 const FAMILIES_PER_NEIGHBORING_TOWN: usize = 5;
 
+pub fn parking_capacity_for_style(style: BuildingStyle) -> u16 {
+    match style {
+        BuildingStyle::FamilyHouse => 1,
+        BuildingStyle::GroceryShop => 6,
+        BuildingStyle::Bakery => 4,
+        BuildingStyle::Mill => 4,
+        BuildingStyle::Field => 2,
+        BuildingStyle::NeighboringTownConnection => 1000,
+    }
+}
+
 pub fn units_for_style(style: BuildingStyle) -> CVec<Unit> {
     match style {
         BuildingStyle::FamilyHouse => vec![Unit(None, UnitType::Dwelling)],
@@ -1,5 +1,5 @@
 use compact::CVec;
-use descartes::{P2, V2, Area, ClosedLinePath, LinePath, PointContainer,
+use descartes::{N, P2, V2, Area, ClosedLinePath, LinePath, PointContainer,
 AreaError, WithUniqueOrthogonal, AreaEmbedding, AreaFilter};
 use land_use::buildings::BuildingStyle;
 use ordered_float::OrderedFloat;
@@ -9,8 +9,9 @@ use cb_util::random::{seed, RngCore};
 use transport::transport_planning::{RoadPrototype, LanePrototype};
 
 use cb_planning::{PlanHistory, VersionedGesture, PlanResult, Prototype, PrototypeID, GestureID,
-StepID};
+StepID, PlanIssue, PlanIssueKind};
 use planning::{CBPrototypeKind, CBGestureIntent};
+use dimensions::LANE_DISTANCE;
 
 #[derive(Compact, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ZoneIntent {
@@ -125,6 +126,125 @@ pub enum LotOccupancy {
     Occupied(BuildingStyle),
 }
 
+const ZONE_ACCESS_DISTANCE: N = 3.0 * LANE_DISTANCE;
+const ZONE_ACCESS_SAMPLING_DISTANCE: N = 5.0;
+
+pub fn validate(
+    history: &PlanHistory<CBGestureIntent>,
+    maybe_result: Option<&PlanResult<CBPrototypeKind>>,
+) -> Vec<PlanIssue> {
+    let zone_outlines = history
+        .gestures
+        .pairs()
+        .filter_map(|(gesture_id, VersionedGesture(gesture, _))| {
+            if let CBGestureIntent::Zone(ZoneIntent::LandUse(_)) = gesture.intent {
+                if gesture.points.len() >= 3 {
+                    return Some((*gesture_id, &gesture.points));
+                }
+            }
+            None
+        })
+        .collect::<Vec<_>>();
+
+    let self_intersecting = zone_outlines
+        .iter()
+        .filter(|(_, points)| is_self_intersecting(points))
+        .map(|(gesture_id, _)| PlanIssue::new(Some(*gesture_id), PlanIssueKind::SelfIntersecting))
+        .collect::<Vec<_>>();
+
+    let no_access = maybe_result
+        .map(|result| {
+            let lane_paths_with_bounds = result
+                .prototypes
+                .values()
+                .filter_map(|prototype| {
+                    if let CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(
+                        ref path,
+                        _,
+                        road_class,
+                        ..
+                    ))) = prototype.kind
+                    {
+                        if !road_class.allows_building_access() {
+                            return None;
+                        }
+                        let (min, max) = path.points.iter().fold(
+                            (path.start(), path.start()),
+                            |(min, max), point| {
+                                (
+                                    P2::new(min.x.min(point.x), min.y.min(point.y)),
+                                    P2::new(max.x.max(point.x), max.y.max(point.y)),
+                                )
+                            },
+                        );
+                        Some((path, min, max))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let has_lane_nearby = |point: P2| {
+                lane_paths_with_bounds.iter().any(|&(path, min, max)| {
+                    point.x > min.x - ZONE_ACCESS_DISTANCE
+                        && point.x < max.x + ZONE_ACCESS_DISTANCE
+                        && point.y > min.y - ZONE_ACCESS_DISTANCE
+                        && point.y < max.y + ZONE_ACCESS_DISTANCE
+                        && path
+                            .project(point)
+                            .map(|(_, projected)| {
+                                (projected - point).norm() < ZONE_ACCESS_DISTANCE
+                            })
+                            .unwrap_or(false)
+                })
+            };
+
+            zone_outlines
+                .iter()
+                .filter_map(|(gesture_id, points)| {
+                    let outline = LinePath::new(
+                        points.iter().chain(points.first()).cloned().collect(),
+                    )?;
+                    let n_samples =
+                        (outline.length() / ZONE_ACCESS_SAMPLING_DISTANCE).ceil() as usize;
+                    let accessible = (0..=n_samples).any(|i| {
+                        has_lane_nearby(outline.along(i as N * ZONE_ACCESS_SAMPLING_DISTANCE))
+                    });
+                    if accessible {
+                        None
+                    } else {
+                        Some(PlanIssue::new(Some(*gesture_id), PlanIssueKind::NoAccess))
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(Vec::new);
+
+    self_intersecting.into_iter().chain(no_access).collect()
+}
+
+fn is_self_intersecting(points: &[P2]) -> bool {
+    let n = points.len();
+    let segment = |i: usize| (points[i], points[(i + 1) % n]);
+
+    (0..n).any(|i| {
+        ((i + 2)..n)
+            .filter(|&j| !(i == 0 && j == n - 1))
+            .any(|j| {
+                let ((start_a, end_a), (start_b, end_b)) = (segment(i), segment(j));
+                let (delta_a, delta_b) = (end_a - start_a, end_b - start_b);
+                let denominator = delta_a.x * delta_b.y - delta_a.y * delta_b.x;
+                if denominator.abs() < 0.0001 {
+                    return false;
+                }
+                let between = start_b - start_a;
+                let t = (between.x * delta_b.y - between.y * delta_b.x) / denominator;
+                let u = (between.x * delta_a.y - between.y * delta_a.x) / denominator;
+                t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0
+            })
+    })
+}
+
 pub fn calculate_prototypes(
     history: &PlanHistory<CBGestureIntent>,
     current_result: &PlanResult<CBPrototypeKind>,
@@ -246,7 +366,7 @@ pub fn calculate_prototypes(
     ];
 
     for prototype in current_result.prototypes.values() {
-        if let CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(ref path, _))) =
+        if let CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(ref path, ..))) =
             prototype.kind
         {
             let distance = (path.start() - P2::new(0.0, 0.0)).norm();
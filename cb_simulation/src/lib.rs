@@ -9,6 +9,8 @@ extern crate fnv;
 extern crate roaring;
 extern crate uuid;
 extern crate arrayvec;
+#[macro_use]
+extern crate serde_json;
 extern crate cb_util;
 pub extern crate cb_time;
 extern crate cb_planning;
@@ -40,6 +42,7 @@ pub fn setup_common(system: &mut kay::ActorSystem) {
         economy::setup,
         land_use::setup,
         environment::setup,
+        planning::geo_reference::setup,
     ] {
         setup_fn(system)
     }
@@ -54,5 +57,6 @@ pub fn spawn_for_server(world: &mut kay::World) -> cb_time::actors::TimeID {
     transport::spawn(world, time);
     economy::spawn(world, time, plan_manager);
     environment::vegetation::spawn(world, plan_manager);
+    planning::geo_reference::spawn(world);
     time
 }
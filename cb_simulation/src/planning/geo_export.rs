@@ -0,0 +1,401 @@
+//! Gets a city out of Citybound: writes the master plan gestures and the built
+//! prototypes of a plan result as GeoJSON (for GIS tools) or SVG (for reports).
+
+use descartes::{N, P2, Area, LinePath};
+use serde_json::Value;
+use cb_planning::{PlanHistory, PlanResult, VersionedGesture};
+use transport::transport_planning::{RoadPrototype, LanePrototype, SwitchLanePrototype,
+SidewalkPrototype, RightOfWay};
+use land_use::zone_planning::{ZoneIntent, LotPrototype, LandUse};
+use environment::vegetation::PlantPrototype;
+use planning::{CBGestureIntent, CBPrototypeKind};
+
+const EARTH_RADIUS: f64 = 6_371_000.0;
+const SVG_MARGIN: N = 20.0;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct GeoExportConfig {
+    /// (longitude, latitude) of the plane origin. If given, coordinates are
+    /// exported as WGS84 longitude/latitude, otherwise as plane meters
+    pub origin: Option<(f64, f64)>,
+}
+
+/// Inverse of the equirectangular projection used when importing
+fn plane_to_geo(point: P2, config: GeoExportConfig) -> [f64; 2] {
+    match config.origin {
+        Some((origin_lon, origin_lat)) => [
+            origin_lon
+                + (f64::from(point.x) / (EARTH_RADIUS * origin_lat.to_radians().cos()))
+                    .to_degrees(),
+            origin_lat + (f64::from(point.y) / EARTH_RADIUS).to_degrees(),
+        ],
+        None => [f64::from(point.x), f64::from(point.y)],
+    }
+}
+
+fn line_coordinates(points: &[P2], config: GeoExportConfig) -> Value {
+    points
+        .iter()
+        .map(|point| plane_to_geo(*point, config))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn point_coordinates(point: P2, config: GeoExportConfig) -> Value {
+    plane_to_geo(point, config).to_vec().into()
+}
+
+fn ring_contains(ring: &[P2], point: P2) -> bool {
+    let mut inside = false;
+    for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Each outer boundary becomes one polygon, together with the holes directly inside it.
+/// A boundary is a hole if it lies within an odd number of other boundaries
+fn area_coordinates(area: &Area, config: GeoExportConfig) -> Value {
+    let rings = area
+        .primitives
+        .iter()
+        .map(|primitive| &primitive.boundary.path().points[..])
+        .collect::<Vec<_>>();
+    let depths = rings
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            rings
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| j != i && ring_contains(other, ring[0]))
+                .count()
+        })
+        .collect::<Vec<_>>();
+
+    (0..rings.len())
+        .filter(|&outer| depths[outer] % 2 == 0)
+        .map(|outer| {
+            let holes = (0..rings.len()).filter(|&hole| {
+                depths[hole] == depths[outer] + 1 && ring_contains(rings[outer], rings[hole][0])
+            });
+            Some(outer)
+                .into_iter()
+                .chain(holes)
+                .map(|i| line_coordinates(rings[i], config))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn feature(geometry_type: &str, coordinates: Value, properties: Value) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": geometry_type,
+            "coordinates": coordinates,
+        },
+        "properties": properties,
+    })
+}
+
+pub fn to_geojson(
+    history: &PlanHistory<CBGestureIntent>,
+    result: &PlanResult<CBPrototypeKind>,
+    config: GeoExportConfig,
+) -> String {
+    let gesture_features = history
+        .gestures
+        .pairs()
+        .filter(|(_, VersionedGesture(gesture, _))| !gesture.is_deleted())
+        .filter_map(|(gesture_id, VersionedGesture(gesture, _))| {
+            let gesture_id = gesture_id.0.to_string();
+            match gesture.intent {
+                CBGestureIntent::Road(road_intent) if gesture.points.len() >= 2 => Some(feature(
+                    "LineString",
+                    line_coordinates(&gesture.points, config),
+                    json!({
+                        "layer": "road_gesture",
+                        "gesture_id": gesture_id,
+                        "n_lanes_forward": road_intent.n_lanes_forward,
+                        "n_lanes_backward": road_intent.n_lanes_backward,
+                        "road_class": road_intent.road_class.to_string(),
+                        "start_level": road_intent.level.start_level(),
+                        "end_level": road_intent.level.end_level(),
+                        "parking": road_intent.parking,
+                    }),
+                )),
+                CBGestureIntent::Roundabout(roundabout_intent) if gesture.points.len() >= 2 => {
+                    Some(feature(
+                        "Point",
+                        point_coordinates(gesture.points[0], config),
+                        json!({
+                            "layer": "roundabout_gesture",
+                            "gesture_id": gesture_id,
+                            "radius": (gesture.points[1] - gesture.points[0]).norm(),
+                            "road_class": roundabout_intent.road_class.to_string(),
+                        }),
+                    ))
+                }
+                CBGestureIntent::IntersectionControl(ref control) if !gesture.points.is_empty() => {
+                    Some(feature(
+                        "Point",
+                        point_coordinates(gesture.points[0], config),
+                        json!({
+                            "layer": "intersection_control_gesture",
+                            "gesture_id": gesture_id,
+                            "control": control,
+                        }),
+                    ))
+                }
+                CBGestureIntent::TransitLine(line_intent) if gesture.points.len() >= 2 => {
+                    Some(feature(
+                        "LineString",
+                        line_coordinates(&gesture.points, config),
+                        json!({
+                            "layer": "transit_line_gesture",
+                            "gesture_id": gesture_id,
+                            "headway_minutes": line_intent.headway_minutes,
+                        }),
+                    ))
+                }
+                CBGestureIntent::Zone(ZoneIntent::LandUse(land_use))
+                    if gesture.points.len() >= 3 =>
+                {
+                    let ring = gesture
+                        .points
+                        .iter()
+                        .chain(gesture.points.first())
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    Some(feature(
+                        "Polygon",
+                        vec![line_coordinates(&ring, config)].into(),
+                        json!({
+                            "layer": "zone_gesture",
+                            "gesture_id": gesture_id,
+                            "land_use": land_use.to_string(),
+                        }),
+                    ))
+                }
+                _ => None,
+            }
+        });
+
+    let prototype_features = result.prototypes.values().filter_map(|prototype| {
+        let prototype_id = format!("{:?}", prototype.id);
+        match prototype.kind {
+            CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(
+                ref path,
+                _,
+                road_class,
+                right_of_way,
+                parking_spots,
+            ))) => Some(feature(
+                "LineString",
+                line_coordinates(&path.points, config),
+                json!({
+                    "layer": "lane",
+                    "prototype_id": prototype_id,
+                    "road_class": road_class.to_string(),
+                    "speed_limit": road_class.speed_limit(),
+                    "yields": right_of_way == RightOfWay::Yield,
+                    "parking_spots": parking_spots,
+                }),
+            )),
+            CBPrototypeKind::Road(RoadPrototype::SwitchLane(SwitchLanePrototype(ref path, _))) => {
+                Some(feature(
+                    "LineString",
+                    line_coordinates(&path.points, config),
+                    json!({"layer": "switch_lane", "prototype_id": prototype_id}),
+                ))
+            }
+            CBPrototypeKind::Road(RoadPrototype::Sidewalk(SidewalkPrototype(ref path, ..))) => {
+                Some(feature(
+                    "LineString",
+                    line_coordinates(&path.points, config),
+                    json!({"layer": "sidewalk", "prototype_id": prototype_id}),
+                ))
+            }
+            CBPrototypeKind::Road(RoadPrototype::PavedArea(ref area)) => Some(feature(
+                "MultiPolygon",
+                area_coordinates(area, config),
+                json!({"layer": "paved_area", "prototype_id": prototype_id}),
+            )),
+            CBPrototypeKind::Lot(LotPrototype {
+                ref lot,
+                ref occupancy,
+            }) => Some(feature(
+                "MultiPolygon",
+                area_coordinates(&lot.area, config),
+                json!({
+                    "layer": "lot",
+                    "prototype_id": prototype_id,
+                    "land_uses": lot
+                        .land_uses
+                        .iter()
+                        .map(|land_use| land_use.to_string())
+                        .collect::<Vec<_>>(),
+                    "occupancy": format!("{:?}", occupancy),
+                }),
+            )),
+            CBPrototypeKind::Plant(PlantPrototype {
+                vegetation_type,
+                position,
+            }) => Some(feature(
+                "Point",
+                point_coordinates(position, config),
+                json!({
+                    "layer": "plant",
+                    "prototype_id": prototype_id,
+                    "vegetation_type": format!("{:?}", vegetation_type),
+                }),
+            )),
+            _ => None,
+        }
+    });
+
+    json!({
+        "type": "FeatureCollection",
+        "features": gesture_features.chain(prototype_features).collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
+fn svg_color(land_use: LandUse) -> &'static str {
+    match land_use {
+        LandUse::Residential => "#eacb52",
+        LandUse::Commercial => "#d55e00",
+        LandUse::Industrial => "#9a6fb0",
+        LandUse::Agricultural => "#a8c66c",
+        LandUse::Recreational => "#5fb85f",
+        LandUse::Administrative => "#4a90d9",
+    }
+}
+
+/// SVG has y pointing down, so all points are mirrored along the x axis
+fn svg_path_data(points: &[P2], closed: bool) -> String {
+    let mut data = points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            format!(
+                "{}{:.2} {:.2}",
+                if i == 0 { "M" } else { "L" },
+                point.x,
+                -point.y
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if closed {
+        data.push_str(" Z");
+    }
+    data
+}
+
+fn svg_area_data(area: &Area) -> String {
+    area.primitives
+        .iter()
+        .map(|primitive| svg_path_data(&primitive.boundary.path().points, true))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_svg(result: &PlanResult<CBPrototypeKind>) -> String {
+    let mut lots = Vec::new();
+    let mut paved_areas = Vec::new();
+    let mut lanes = Vec::new();
+    let mut plants = Vec::new();
+    let mut all_points = Vec::new();
+
+    for prototype in result.prototypes.values() {
+        match prototype.kind {
+            CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(ref path, ..))) => {
+                lanes.push(path_element(path, "none", "#ffffff", 0.3));
+            }
+            CBPrototypeKind::Road(RoadPrototype::PavedArea(ref area)) => {
+                all_points.extend(area.primitives.iter().flat_map(|primitive| {
+                    primitive.boundary.path().points.iter().cloned()
+                }));
+                paved_areas.push(format!(
+                    r##"<path d="{}" fill="#777777" fill-rule="evenodd"/>"##,
+                    svg_area_data(area)
+                ));
+            }
+            CBPrototypeKind::Lot(LotPrototype { ref lot, .. }) => {
+                all_points.extend(lot.area.primitives.iter().flat_map(|primitive| {
+                    primitive.boundary.path().points.iter().cloned()
+                }));
+                let color = lot
+                    .land_uses
+                    .first()
+                    .map(|land_use| svg_color(*land_use))
+                    .unwrap_or("#cccccc");
+                lots.push(format!(
+                    r##"<path d="{}" fill="{}" fill-opacity="0.6" {}/>"##,
+                    svg_area_data(&lot.area),
+                    color,
+                    r##"stroke="#333333" stroke-width="0.2" fill-rule="evenodd""##
+                ));
+            }
+            CBPrototypeKind::Plant(PlantPrototype { position, .. }) => {
+                all_points.push(position);
+                plants.push(format!(
+                    r##"<circle cx="{:.2}" cy="{:.2}" r="1.5" fill="#2f7d32"/>"##,
+                    position.x, -position.y
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let (min, max) = all_points.iter().fold(
+        (
+            P2::new(::std::f32::MAX, ::std::f32::MAX),
+            P2::new(::std::f32::MIN, ::std::f32::MIN),
+        ),
+        |(min, max), point| {
+            (
+                P2::new(min.x.min(point.x), min.y.min(point.y)),
+                P2::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        },
+    );
+    let (min, max) = if all_points.is_empty() {
+        (P2::new(0.0, 0.0), P2::new(0.0, 0.0))
+    } else {
+        (min, max)
+    };
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.2} {:.2} {:.2} {:.2}">"#,
+            "\n<g id=\"lots\">\n{}\n</g>\n<g id=\"paved_areas\">\n{}\n</g>",
+            "\n<g id=\"lanes\">\n{}\n</g>\n<g id=\"plants\">\n{}\n</g>\n</svg>\n"
+        ),
+        min.x - SVG_MARGIN,
+        -max.y - SVG_MARGIN,
+        max.x - min.x + 2.0 * SVG_MARGIN,
+        max.y - min.y + 2.0 * SVG_MARGIN,
+        lots.join("\n"),
+        paved_areas.join("\n"),
+        lanes.join("\n"),
+        plants.join("\n"),
+    )
+}
+
+fn path_element(path: &LinePath, fill: &str, stroke: &str, stroke_width: N) -> String {
+    format!(
+        r#"<path d="{}" fill="{}" stroke="{}" stroke-width="{}"/>"#,
+        svg_path_data(&path.points, false),
+        fill,
+        stroke,
+        stroke_width
+    )
+}
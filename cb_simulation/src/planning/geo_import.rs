@@ -0,0 +1,501 @@
+//! Seeds plans from real geography: reads GeoJSON or OpenStreetMap XML extracts
+//! and turns roads and land use areas into gestures of one artificial project.
+
+use std::collections::HashMap;
+use std::path::Path;
+use descartes::{N, P2};
+use serde_json::{self, Value};
+use cb_planning::{Gesture, GestureID, Plan, Project};
+use transport::transport_planning::{RoadIntent, RoadClass, RoadLevel};
+use land_use::zone_planning::{ZoneIntent, LandUse};
+use planning::CBGestureIntent;
+
+const EARTH_RADIUS: f64 = 6_371_000.0;
+const SIMPLIFICATION_TOLERANCE: N = 1.0;
+const MIN_POINT_DISTANCE: N = 0.5;
+const MAX_IMPORTED_LANES: u8 = 6;
+
+#[derive(Copy, Clone, Debug)]
+pub struct GeoImportConfig {
+    /// (longitude, latitude) that ends up at the plane origin,
+    /// defaults to the center of the bounding box of all imported geometry
+    pub origin: Option<(f64, f64)>,
+    pub import_zones: bool,
+}
+
+impl Default for GeoImportConfig {
+    fn default() -> GeoImportConfig {
+        GeoImportConfig {
+            origin: None,
+            import_zones: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GeoImportError {
+    Io(::std::io::Error),
+    Json(serde_json::Error),
+    Osm(String),
+    UnknownFormat,
+}
+
+type Tags = HashMap<String, String>;
+
+enum RawFeature {
+    Line(Vec<(f64, f64)>, Tags),
+    Area(Vec<(f64, f64)>, Tags),
+}
+
+/// Imported gestures, with the (longitude, latitude) that ended up at the plane origin
+pub struct GeoImport {
+    pub gestures: Vec<Gesture<CBGestureIntent>>,
+    pub origin: (f64, f64),
+}
+
+/// Returns the imported project together with the (longitude, latitude)
+/// that ended up at the plane origin, to export the city at the same location again
+pub fn import_file(
+    path: &Path,
+    config: GeoImportConfig,
+) -> Result<(Project<CBGestureIntent>, (f64, f64)), GeoImportError> {
+    let source = ::std::fs::read_to_string(path).map_err(GeoImportError::Io)?;
+
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let GeoImport { gestures, origin } = match extension {
+        Some("json") | Some("geojson") => import_geojson(&source, config)?,
+        Some("osm") | Some("xml") => import_osm_xml(&source, config)?,
+        _ => return Err(GeoImportError::UnknownFormat),
+    };
+
+    let mut project = Project::from_plan(Plan::from_gestures(
+        gestures
+            .into_iter()
+            .map(|gesture| (GestureID::new(), gesture)),
+    ));
+    project.metadata.name = format!(
+        "Import of {}",
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    )
+    .into();
+    Ok((project, origin))
+}
+
+pub fn import_geojson(
+    source: &str,
+    config: GeoImportConfig,
+) -> Result<GeoImport, GeoImportError> {
+    let root: Value = serde_json::from_str(source).map_err(GeoImportError::Json)?;
+
+    let features = match root["type"].as_str() {
+        Some("FeatureCollection") => root["features"].as_array().cloned().unwrap_or_default(),
+        Some("Feature") => vec![root.clone()],
+        _ => return Err(GeoImportError::UnknownFormat),
+    };
+
+    let mut raw_features = Vec::new();
+
+    for feature in &features {
+        let tags = feature["properties"]
+            .as_object()
+            .map(|properties| {
+                properties
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let value = match value {
+                            Value::String(string) => string.clone(),
+                            Value::Number(number) => number.to_string(),
+                            Value::Bool(boolean) => boolean.to_string(),
+                            _ => return None,
+                        };
+                        Some((key.clone(), value))
+                    })
+                    .collect::<Tags>()
+            })
+            .unwrap_or_default();
+
+        let geometry = &feature["geometry"];
+        let coordinates = &geometry["coordinates"];
+
+        match geometry["type"].as_str() {
+            Some("LineString") => {
+                raw_features.push(RawFeature::Line(geojson_positions(coordinates), tags))
+            }
+            Some("MultiLineString") => {
+                for line in coordinates.as_array().into_iter().flatten() {
+                    raw_features.push(RawFeature::Line(geojson_positions(line), tags.clone()))
+                }
+            }
+            Some("Polygon") => {
+                raw_features.push(RawFeature::Area(geojson_positions(&coordinates[0]), tags))
+            }
+            Some("MultiPolygon") => {
+                for polygon in coordinates.as_array().into_iter().flatten() {
+                    raw_features.push(RawFeature::Area(
+                        geojson_positions(&polygon[0]),
+                        tags.clone(),
+                    ))
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(gestures_from_raw_features(raw_features, config))
+}
+
+fn geojson_positions(coordinates: &Value) -> Vec<(f64, f64)> {
+    coordinates
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|position| Some((position[0].as_f64()?, position[1].as_f64()?)))
+        .collect()
+}
+
+/// Only reads nodes and ways, multipolygon relations are ignored
+pub fn import_osm_xml(
+    source: &str,
+    config: GeoImportConfig,
+) -> Result<GeoImport, GeoImportError> {
+    let mut nodes = HashMap::<i64, (f64, f64)>::new();
+    let mut ways = Vec::<(Vec<i64>, Tags)>::new();
+    let mut current_way: Option<(Vec<i64>, Tags)> = None;
+
+    for element in source.split('<').skip(1) {
+        let element = element
+            .split('>')
+            .next()
+            .ok_or_else(|| GeoImportError::Osm("Unterminated element".to_owned()))?
+            .trim();
+
+        if element.starts_with('?') || element.starts_with('!') {
+            continue;
+        }
+
+        if element.starts_with("/way") {
+            ways.extend(current_way.take());
+            continue;
+        }
+
+        let self_closing = element.ends_with('/');
+        let element = element.trim_end_matches('/');
+        let name = element.split_whitespace().next().unwrap_or("");
+        let attributes = xml_attributes(element);
+
+        match name {
+            "node" => {
+                let id = attributes.get("id").and_then(|value| value.parse::<i64>().ok());
+                let coordinate =
+                    |key: &str| attributes.get(key).and_then(|value| value.parse::<f64>().ok());
+                if let (Some(id), Some(lat), Some(lon)) =
+                    (id, coordinate("lat"), coordinate("lon"))
+                {
+                    nodes.insert(id, (lon, lat));
+                }
+            }
+            "way" => {
+                current_way = Some((Vec::new(), Tags::new()));
+                if self_closing {
+                    ways.extend(current_way.take());
+                }
+            }
+            "nd" => {
+                if let (Some((refs, _)), Some(node_ref)) = (
+                    current_way.as_mut(),
+                    attributes.get("ref").and_then(|value| value.parse().ok()),
+                ) {
+                    refs.push(node_ref);
+                }
+            }
+            "tag" => {
+                if let (Some((_, tags)), Some(key), Some(value)) = (
+                    current_way.as_mut(),
+                    attributes.get("k"),
+                    attributes.get("v"),
+                ) {
+                    tags.insert(key.clone(), value.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let raw_features = ways
+        .into_iter()
+        .filter_map(|(refs, tags)| {
+            let positions = refs
+                .iter()
+                .filter_map(|node_ref| nodes.get(node_ref).cloned())
+                .collect::<Vec<_>>();
+            if positions.len() < 2 {
+                return None;
+            }
+            let is_closed = refs.len() > 2 && refs.first() == refs.last();
+            if is_closed && !tags.contains_key("highway") {
+                Some(RawFeature::Area(positions, tags))
+            } else {
+                Some(RawFeature::Line(positions, tags))
+            }
+        })
+        .collect();
+
+    Ok(gestures_from_raw_features(raw_features, config))
+}
+
+fn xml_attributes(element: &str) -> HashMap<&str, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = element;
+
+    while let Some(equals_idx) = rest.find('=') {
+        let key = rest[..equals_idx].split_whitespace().last().unwrap_or("");
+        let after_equals = rest[equals_idx + 1..].trim_start();
+        let quote = match after_equals.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => quote,
+            _ => break,
+        };
+        let value_and_rest = &after_equals[1..];
+        let value_end = match value_and_rest.find(quote) {
+            Some(value_end) => value_end,
+            None => break,
+        };
+        attributes.insert(key, xml_unescape(&value_and_rest[..value_end]));
+        rest = &value_and_rest[value_end + 1..];
+    }
+
+    attributes
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn gestures_from_raw_features(raw_features: Vec<RawFeature>, config: GeoImportConfig) -> GeoImport {
+    let origin = config.origin.unwrap_or_else(|| {
+        let all_positions = raw_features.iter().flat_map(|feature| match feature {
+            RawFeature::Line(positions, _) | RawFeature::Area(positions, _) => positions.iter(),
+        });
+        let (min, max) = all_positions.fold(
+            ((::std::f64::MAX, ::std::f64::MAX), (::std::f64::MIN, ::std::f64::MIN)),
+            |((min_lon, min_lat), (max_lon, max_lat)), &(lon, lat)| {
+                (
+                    (min_lon.min(lon), min_lat.min(lat)),
+                    (max_lon.max(lon), max_lat.max(lat)),
+                )
+            },
+        );
+        ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0)
+    });
+
+    let to_plane = |positions: &[(f64, f64)]| {
+        let points = positions
+            .iter()
+            .map(|&position| project_to_plane(position, origin))
+            .collect::<Vec<_>>();
+        simplify(&points, SIMPLIFICATION_TOLERANCE)
+    };
+
+    let gestures = raw_features
+        .iter()
+        .filter_map(|feature| match feature {
+            RawFeature::Line(positions, tags) => {
+                let (road_intent, reversed) = road_intent_from_tags(tags)?;
+                let mut points = to_plane(positions);
+                if reversed {
+                    points.reverse();
+                }
+                if points.len() >= 2 {
+                    Some(Gesture::new(points.into(), CBGestureIntent::Road(road_intent)))
+                } else {
+                    None
+                }
+            }
+            RawFeature::Area(positions, tags) if config.import_zones => {
+                let land_use = land_use_from_tags(tags)?;
+                let mut points = to_plane(positions);
+                if points.len() > 1 && (points[0] - points[points.len() - 1]).norm() < 0.1 {
+                    points.pop();
+                }
+                if points.len() >= 3 {
+                    Some(Gesture::new(
+                        points.into(),
+                        CBGestureIntent::Zone(ZoneIntent::LandUse(land_use)),
+                    ))
+                } else {
+                    None
+                }
+            }
+            RawFeature::Area(..) => None,
+        })
+        .collect();
+
+    GeoImport { gestures, origin }
+}
+
+/// Equirectangular projection around `origin`, precise enough at city scale
+fn project_to_plane((lon, lat): (f64, f64), (origin_lon, origin_lat): (f64, f64)) -> P2 {
+    let x = EARTH_RADIUS * (lon - origin_lon).to_radians() * origin_lat.to_radians().cos();
+    let y = EARTH_RADIUS * (lat - origin_lat).to_radians();
+    P2::new(x as N, y as N)
+}
+
+/// Douglas-Peucker simplification that also drops points that are too close together
+fn simplify(points: &[P2], tolerance: N) -> Vec<P2> {
+    let mut deduplicated: Vec<P2> = Vec::with_capacity(points.len());
+    for &point in points {
+        if deduplicated
+            .last()
+            .map(|&last| (point - last).norm() >= MIN_POINT_DISTANCE)
+            .unwrap_or(true)
+        {
+            deduplicated.push(point);
+        }
+    }
+
+    if deduplicated.len() <= 2 {
+        return deduplicated;
+    }
+
+    let mut keep = vec![false; deduplicated.len()];
+    keep[0] = true;
+    keep[deduplicated.len() - 1] = true;
+    let mut ranges = vec![(0, deduplicated.len() - 1)];
+
+    while let Some((start, end)) = ranges.pop() {
+        let (start_point, end_point) = (deduplicated[start], deduplicated[end]);
+        let direction = end_point - start_point;
+        let length = direction.norm();
+
+        let farthest = (start + 1..end)
+            .map(|i| {
+                let offset = deduplicated[i] - start_point;
+                let distance = if length < 0.001 {
+                    offset.norm()
+                } else {
+                    (offset.x * direction.y - offset.y * direction.x).abs() / length
+                };
+                (i, distance)
+            })
+            .fold(None, |farthest: Option<(usize, N)>, (i, distance)| match farthest {
+                Some((_, farthest_distance)) if farthest_distance >= distance => farthest,
+                _ => Some((i, distance)),
+            });
+
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                ranges.push((start, i));
+                ranges.push((i, end));
+            }
+        }
+    }
+
+    deduplicated
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| if keep { Some(point) } else { None })
+        .collect()
+}
+
+/// Returns the intent and whether the road is drawn against the direction of travel
+fn road_intent_from_tags(tags: &Tags) -> Option<(RoadIntent, bool)> {
+    let highway = tags.get("highway")?.as_str();
+    let highway_class = highway.trim_end_matches("_link");
+
+    let (default_lanes_per_direction, road_class): (u8, _) = match highway_class {
+        "motorway" => (2, RoadClass::Highway),
+        "trunk" | "primary" => (2, RoadClass::Arterial),
+        "secondary" | "tertiary" => (1, RoadClass::Arterial),
+        "unclassified" | "residential" | "living_street" | "road" => {
+            (1, RoadClass::ResidentialStreet)
+        }
+        "service" => (1, RoadClass::Alley),
+        _ => return None,
+    };
+
+    let parse_lanes = |key: &str| tags.get(key).and_then(|value| value.trim().parse::<u8>().ok());
+    let lanes = parse_lanes("lanes");
+
+    let oneway = tags.get("oneway").map(String::as_str);
+    let is_roundabout = tags.get("junction").map(String::as_str) == Some("roundabout");
+    let reversed = oneway == Some("-1") || oneway == Some("reverse");
+    let is_oneway = reversed
+        || match oneway {
+            Some("yes") | Some("true") | Some("1") => true,
+            Some("no") | Some("false") | Some("0") => false,
+            _ => highway_class == "motorway" || highway != highway_class || is_roundabout,
+        };
+
+    let (n_lanes_forward, n_lanes_backward) = if is_oneway {
+        (
+            parse_lanes("lanes:forward")
+                .or(lanes)
+                .unwrap_or(default_lanes_per_direction),
+            0,
+        )
+    } else {
+        // a two-way road has at least one lane in each direction, even if tagged with
+        // fewer lanes in total, like narrow streets that oncoming cars share
+        (
+            parse_lanes("lanes:forward")
+                .or_else(|| lanes.map(|lanes| lanes / 2 + lanes % 2))
+                .unwrap_or(default_lanes_per_direction)
+                .max(1),
+            parse_lanes("lanes:backward")
+                .or_else(|| lanes.map(|lanes| lanes / 2))
+                .unwrap_or(default_lanes_per_direction)
+                .max(1),
+        )
+    };
+
+    // OSM layers only tell crossing ways apart, they are not actual heights
+    let level = tags
+        .get("layer")
+        .and_then(|layer| layer.trim().parse::<i8>().ok())
+        .unwrap_or_else(|| {
+            let is_tagged = |key: &str| tags.get(key).map_or(false, |value| value != "no");
+            if is_tagged("bridge") {
+                1
+            } else if is_tagged("tunnel") {
+                -1
+            } else {
+                0
+            }
+        });
+
+    Some((
+        RoadIntent::new(
+            n_lanes_forward.max(1).min(MAX_IMPORTED_LANES),
+            n_lanes_backward.min(MAX_IMPORTED_LANES),
+            road_class,
+        )
+        .with_level(RoadLevel::Flat(level)),
+        reversed,
+    ))
+}
+
+fn land_use_from_tags(tags: &Tags) -> Option<LandUse> {
+    let land_use = match tags.get("landuse").map(String::as_str) {
+        Some("residential") => LandUse::Residential,
+        Some("commercial") | Some("retail") => LandUse::Commercial,
+        Some("industrial") => LandUse::Industrial,
+        Some("farmland") | Some("farmyard") | Some("meadow") | Some("orchard")
+        | Some("vineyard") => LandUse::Agricultural,
+        Some("recreation_ground") | Some("village_green") | Some("grass") => {
+            LandUse::Recreational
+        }
+        Some("institutional") | Some("civic_admin") => LandUse::Administrative,
+        _ => match tags.get("leisure").map(String::as_str) {
+            Some("park") | Some("playground") => LandUse::Recreational,
+            _ => return None,
+        },
+    };
+    Some(land_use)
+}
@@ -0,0 +1,167 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct GeoReferenceListenerID {
+    _raw_id: RawID
+}
+
+impl Copy for GeoReferenceListenerID {}
+impl Clone for GeoReferenceListenerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for GeoReferenceListenerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "GeoReferenceListenerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for GeoReferenceListenerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for GeoReferenceListenerID {
+    fn eq(&self, other: &GeoReferenceListenerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for GeoReferenceListenerID {}
+
+pub struct GeoReferenceListenerRepresentative;
+
+impl ActorOrActorTrait for GeoReferenceListenerRepresentative {
+    type ID = GeoReferenceListenerID;
+}
+
+impl TypedID for GeoReferenceListenerID {
+    type Target = GeoReferenceListenerRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        GeoReferenceListenerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + GeoReferenceListener> TraitIDFrom<Act> for GeoReferenceListenerID {}
+
+impl GeoReferenceListenerID {
+    pub fn on_geo_origin(self, origin: Option < (f64, f64) >, world: &mut World) {
+        world.send(self.as_raw(), MSG_GeoReferenceListener_on_geo_origin(origin));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<GeoReferenceListenerRepresentative>();
+        system.register_trait_message::<MSG_GeoReferenceListener_on_geo_origin>();
+    }
+
+    pub fn register_implementor<Act: Actor + GeoReferenceListener>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, GeoReferenceListenerRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_GeoReferenceListener_on_geo_origin(origin), instance, world| {
+                instance.on_geo_origin(origin, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_GeoReferenceListener_on_geo_origin(pub Option < (f64, f64) >);
+
+impl Actor for GeoReference {
+    type ID = GeoReferenceID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct GeoReferenceID {
+    _raw_id: RawID
+}
+
+impl Copy for GeoReferenceID {}
+impl Clone for GeoReferenceID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for GeoReferenceID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "GeoReferenceID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for GeoReferenceID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for GeoReferenceID {
+    fn eq(&self, other: &GeoReferenceID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for GeoReferenceID {}
+
+impl TypedID for GeoReferenceID {
+    type Target = GeoReference;
+
+    fn from_raw(id: RawID) -> Self {
+        GeoReferenceID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl GeoReferenceID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = GeoReferenceID::from_raw(world.allocate_instance_id::<GeoReference>());
+        let swarm = world.local_broadcast::<GeoReference>();
+        world.send(swarm, MSG_GeoReference_spawn(id, ));
+        id
+    }
+
+    pub fn set_origin(self, longitude: f64, latitude: f64, world: &mut World) {
+        world.send(self.as_raw(), MSG_GeoReference_set_origin(longitude, latitude));
+    }
+
+    pub fn get_origin(self, requester: GeoReferenceListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_GeoReference_get_origin(requester));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_GeoReference_spawn(pub GeoReferenceID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_GeoReference_set_origin(pub f64, pub f64);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_GeoReference_get_origin(pub GeoReferenceListenerID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    GeoReferenceListenerID::register_trait(system);
+    system.add_spawner::<GeoReference, _, _>(
+        |&MSG_GeoReference_spawn(id, ), world| {
+            GeoReference::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<GeoReference, _, _>(
+        |&MSG_GeoReference_set_origin(longitude, latitude), instance, world| {
+            instance.set_origin(longitude, latitude, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<GeoReference, _, _>(
+        |&MSG_GeoReference_get_origin(requester), instance, world| {
+            instance.get_origin(requester, world); Fate::Live
+        }, false
+    );
+}
@@ -0,0 +1,41 @@
+//! Remembers where on earth the city is, so that it can be exported to GIS tools
+//! at the same location it was imported from.
+
+use kay::{ActorSystem, World};
+
+pub trait GeoReferenceListener {
+    fn on_geo_origin(&mut self, origin: Option<(f64, f64)>, world: &mut World);
+}
+
+#[derive(Compact, Clone)]
+pub struct GeoReference {
+    id: GeoReferenceID,
+    /// (longitude, latitude) of the plane origin, unknown for cities that weren't imported
+    origin: Option<(f64, f64)>,
+}
+
+impl GeoReference {
+    pub fn spawn(id: GeoReferenceID, _: &mut World) -> GeoReference {
+        GeoReference { id, origin: None }
+    }
+
+    pub fn set_origin(&mut self, longitude: f64, latitude: f64, _: &mut World) {
+        self.origin = Some((longitude, latitude));
+    }
+
+    pub fn get_origin(&mut self, requester: GeoReferenceListenerID, world: &mut World) {
+        requester.on_geo_origin(self.origin, world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<GeoReference>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    GeoReferenceID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
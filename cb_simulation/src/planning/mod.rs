@@ -1,12 +1,20 @@
 use kay::World;
 use compact::CVec;
-use transport::transport_planning::{RoadIntent, RoadPrototype};
+use descartes::N;
+use transport::transport_planning::{RoadIntent, RoundaboutIntent, RoadPrototype};
+use transport::transport_planning::intersection_control::IntersectionControl;
+use transport::transit::transit_planning::{TransitLineIntent, TransitLinePrototype};
 use land_use::zone_planning::{ZoneIntent, BuildingIntent, LotPrototype};
 use environment::vegetation::{PlantIntent, PlantPrototype};
-use cb_planning::{PlanningLogic, PrototypeID, PlanningStepFn};
+use cb_planning::{PlanningLogic, PrototypeID, PlanningStepFn, PlanValidationFn};
 use cb_planning::plan_manager::{PlanManager, PlanManagerID};
 use cb_planning::construction::{Construction, ConstructionID, PrototypeKind, GestureIntent,
 ConstructableID};
+use cb_planning::snapping::SnappingConfig;
+
+pub mod geo_import;
+pub mod geo_export;
+pub mod geo_reference;
 
 #[derive(Copy, Clone)]
 pub struct CBPlanningLogic {}
@@ -18,10 +26,18 @@ impl PlanningLogic for CBPlanningLogic {
     fn planning_step_functions() -> &'static [PlanningStepFn<Self>] {
         &[
             ::transport::transport_planning::calculate_prototypes,
+            ::transport::transit::transit_planning::calculate_prototypes,
             ::land_use::zone_planning::calculate_prototypes,
             ::environment::vegetation::calculate_prototypes,
         ]
     }
+
+    fn plan_validation_functions() -> &'static [PlanValidationFn<Self>] {
+        &[
+            ::transport::transport_planning::validate,
+            ::land_use::zone_planning::validate,
+        ]
+    }
 }
 
 pub type CBPlanManager = PlanManager<CBPlanningLogic>;
@@ -32,16 +48,79 @@ pub type CBConstructionID = ConstructionID<CBPrototypeKind>;
 #[derive(Compact, Clone, Debug, Serialize, Deserialize)]
 pub enum CBGestureIntent {
     Road(RoadIntent),
+    Roundabout(RoundaboutIntent),
+    /// Applies to the intersection containing the first gesture point
+    IntersectionControl(IntersectionControl),
+    /// The gesture points are the stops of the line
+    TransitLine(TransitLineIntent),
     Zone(ZoneIntent),
     Building(BuildingIntent),
     Plant(PlantIntent),
 }
 
-impl GestureIntent for CBGestureIntent {}
+const ROAD_SNAPPING_RADIUS: N = 6.0;
+const ZONE_SNAPPING_RADIUS: N = 4.0;
+
+impl GestureIntent for CBGestureIntent {
+    fn snapping(&self) -> SnappingConfig {
+        match *self {
+            CBGestureIntent::Road(_) => SnappingConfig {
+                to_endpoints: Some(ROAD_SNAPPING_RADIUS),
+                to_intersections: Some(ROAD_SNAPPING_RADIUS),
+                to_parallels: Some(ROAD_SNAPPING_RADIUS),
+                angle_increment: Some(::std::f32::consts::PI / 12.0),
+                segment_length: None,
+            },
+            CBGestureIntent::Roundabout(_) => SnappingConfig {
+                to_endpoints: Some(ROAD_SNAPPING_RADIUS),
+                to_intersections: Some(ROAD_SNAPPING_RADIUS),
+                to_parallels: None,
+                angle_increment: None,
+                segment_length: None,
+            },
+            CBGestureIntent::Zone(_) => SnappingConfig {
+                to_endpoints: Some(ZONE_SNAPPING_RADIUS),
+                to_intersections: Some(ZONE_SNAPPING_RADIUS),
+                to_parallels: Some(ZONE_SNAPPING_RADIUS),
+                angle_increment: Some(::std::f32::consts::PI / 4.0),
+                segment_length: Some(5.0),
+            },
+            CBGestureIntent::IntersectionControl(_)
+            | CBGestureIntent::TransitLine(_)
+            | CBGestureIntent::Building(_)
+            | CBGestureIntent::Plant(_) => SnappingConfig::none(),
+        }
+    }
+
+    fn snaps_to_points_of(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CBGestureIntent::Road(_), CBGestureIntent::Road(_)) => true,
+            (CBGestureIntent::Road(_), CBGestureIntent::Roundabout(_)) => true,
+            (CBGestureIntent::Roundabout(_), CBGestureIntent::Road(_)) => true,
+            (CBGestureIntent::Zone(_), CBGestureIntent::Zone(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn parallel_offsets_to(&self, other: &Self) -> Option<(N, N)> {
+        match (self, other) {
+            // assumes the new road runs in the same direction as the existing one
+            (CBGestureIntent::Road(self_road), CBGestureIntent::Road(other_road)) => Some((
+                other_road.backward_width() + self_road.forward_width(),
+                other_road.forward_width() + self_road.backward_width(),
+            )),
+            (CBGestureIntent::Zone(_), CBGestureIntent::Road(other_road)) => {
+                Some((other_road.backward_width(), other_road.forward_width()))
+            }
+            _ => None,
+        }
+    }
+}
 
 #[derive(Compact, Clone, Serialize, Deserialize, Debug)]
 pub enum CBPrototypeKind {
     Road(RoadPrototype),
+    TransitLine(TransitLinePrototype),
     Lot(LotPrototype),
     Plant(PlantPrototype),
 }
@@ -55,6 +134,9 @@ impl PrototypeKind for CBPrototypeKind {
     ) -> CVec<ConstructableID<CBPrototypeKind>> {
         match self {
             CBPrototypeKind::Road(ref road_prototype) => road_prototype.construct(report_to, world),
+            CBPrototypeKind::TransitLine(ref line_prototype) => {
+                line_prototype.construct(report_to, world)
+            }
             CBPrototypeKind::Lot(ref lot_prototype) => {
                 lot_prototype.construct(prototype_id, report_to, world)
             }
@@ -69,6 +151,10 @@ impl PrototypeKind for CBPrototypeKind {
             (&CBPrototypeKind::Road(ref self_road), &CBPrototypeKind::Road(ref other_road)) => {
                 self_road.morphable_from(other_road)
             }
+            (
+                &CBPrototypeKind::TransitLine(ref self_line),
+                &CBPrototypeKind::TransitLine(ref other_line),
+            ) => self_line.morphable_from(other_line),
             (&CBPrototypeKind::Lot(ref self_lot), &CBPrototypeKind::Lot(ref other_lot)) => {
                 self_lot.morphable_from(other_lot)
             }
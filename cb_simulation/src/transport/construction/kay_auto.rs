@@ -10,10 +10,10 @@ use super::*;
 
 
 impl LaneID {
-    pub fn spawn_and_connect(path: LinePath, on_intersection: bool, timings: CVec < bool >, report_to: CBConstructionID, world: &mut World) -> Self {
+    pub fn spawn_and_connect(path: LinePath, on_intersection: bool, timings: CVec < SignalAspect >, road_class: RoadClass, right_of_way: RightOfWay, parking_spots: u16, report_to: CBConstructionID, world: &mut World) -> Self {
         let id = LaneID::from_raw(world.allocate_instance_id::<Lane>());
         let swarm = world.local_broadcast::<Lane>();
-        world.send(swarm, MSG_Lane_spawn_and_connect(id, path, on_intersection, timings, report_to));
+        world.send(swarm, MSG_Lane_spawn_and_connect(id, path, on_intersection, timings, road_class, right_of_way, parking_spots, report_to));
         id
     }
     
@@ -25,8 +25,8 @@ impl LaneID {
         world.send(self.as_raw(), MSG_Lane_connect(other_id, other_start, other_end, other_length, reply_needed));
     }
     
-    pub fn connect_overlaps(self, other_id: LaneID, other_path: LinePath, reply_needed: bool, world: &mut World) {
-        world.send(self.as_raw(), MSG_Lane_connect_overlaps(other_id, other_path, reply_needed));
+    pub fn connect_overlaps(self, other_id: LaneID, other_path: LinePath, other_right_of_way: RightOfWay, reply_needed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_connect_overlaps(other_id, other_path, other_right_of_way, reply_needed));
     }
     
     pub fn connect_to_switch(self, other_id: SwitchLaneID, world: &mut World) {
@@ -59,13 +59,13 @@ impl LaneID {
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_Lane_spawn_and_connect(pub LaneID, pub LinePath, pub bool, pub CVec < bool >, pub CBConstructionID);
+struct MSG_Lane_spawn_and_connect(pub LaneID, pub LinePath, pub bool, pub CVec < SignalAspect >, pub RoadClass, pub RightOfWay, pub u16, pub CBConstructionID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Lane_start_connecting_overlaps(pub CVec < LaneID >);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Lane_connect(pub LaneID, pub P2, pub P2, pub N, pub bool);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_Lane_connect_overlaps(pub LaneID, pub LinePath, pub bool);
+struct MSG_Lane_connect_overlaps(pub LaneID, pub LinePath, pub RightOfWay, pub bool);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Lane_connect_to_switch(pub SwitchLaneID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
@@ -89,10 +89,10 @@ impl Into<ConstructableID<CBPrototypeKind>> for LaneID {
 
 
 impl SwitchLaneID {
-    pub fn spawn_and_connect(path: LinePath, report_to: CBConstructionID, world: &mut World) -> Self {
+    pub fn spawn_and_connect(path: LinePath, road_class: RoadClass, report_to: CBConstructionID, world: &mut World) -> Self {
         let id = SwitchLaneID::from_raw(world.allocate_instance_id::<SwitchLane>());
         let swarm = world.local_broadcast::<SwitchLane>();
-        world.send(swarm, MSG_SwitchLane_spawn_and_connect(id, path, report_to));
+        world.send(swarm, MSG_SwitchLane_spawn_and_connect(id, path, road_class, report_to));
         id
     }
     
@@ -114,7 +114,7 @@ impl SwitchLaneID {
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_SwitchLane_spawn_and_connect(pub SwitchLaneID, pub LinePath, pub CBConstructionID);
+struct MSG_SwitchLane_spawn_and_connect(pub SwitchLaneID, pub LinePath, pub RoadClass, pub CBConstructionID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_SwitchLane_connect_switch_to_normal(pub LaneID, pub LinePath);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
@@ -136,8 +136,8 @@ pub fn auto_setup(system: &mut ActorSystem) {
     
     ConstructableID::<CBPrototypeKind>::register_implementor::<Lane>(system);
     system.add_spawner::<Lane, _, _>(
-        |&MSG_Lane_spawn_and_connect(id, ref path, on_intersection, ref timings, report_to), world| {
-            Lane::spawn_and_connect(id, path, on_intersection, timings, report_to, world)
+        |&MSG_Lane_spawn_and_connect(id, ref path, on_intersection, ref timings, road_class, right_of_way, parking_spots, report_to), world| {
+            Lane::spawn_and_connect(id, path, on_intersection, timings, road_class, right_of_way, parking_spots, report_to, world)
         }, false
     );
     
@@ -154,8 +154,8 @@ pub fn auto_setup(system: &mut ActorSystem) {
     );
     
     system.add_handler::<Lane, _, _>(
-        |&MSG_Lane_connect_overlaps(other_id, ref other_path, reply_needed), instance, world| {
-            instance.connect_overlaps(other_id, other_path, reply_needed, world); Fate::Live
+        |&MSG_Lane_connect_overlaps(other_id, ref other_path, other_right_of_way, reply_needed), instance, world| {
+            instance.connect_overlaps(other_id, other_path, other_right_of_way, reply_needed, world); Fate::Live
         }, false
     );
     
@@ -202,8 +202,8 @@ pub fn auto_setup(system: &mut ActorSystem) {
     );
     ConstructableID::<CBPrototypeKind>::register_implementor::<SwitchLane>(system);
     system.add_spawner::<SwitchLane, _, _>(
-        |&MSG_SwitchLane_spawn_and_connect(id, ref path, report_to), world| {
-            SwitchLane::spawn_and_connect(id, path, report_to, world)
+        |&MSG_SwitchLane_spawn_and_connect(id, ref path, road_class, report_to), world| {
+            SwitchLane::spawn_and_connect(id, path, road_class, report_to, world)
         }, false
     );
     
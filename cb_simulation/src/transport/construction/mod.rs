@@ -6,14 +6,20 @@ use itertools::Itertools;
 use ordered_float::OrderedFloat;
 
 use super::lane::{Lane, LaneID, SwitchLane, SwitchLaneID};
-use super::lane::connectivity::Interaction;
+use super::lane::connectivity::{Interaction, ConflictPriority};
 use super::microtraffic::LaneLikeID;
 
 use cb_planning::Prototype;
 use cb_planning::construction::{Constructable, ConstructableID};
 use planning::{CBConstructionID, CBPrototypeKind};
 use super::transport_planning::{RoadPrototype, LanePrototype, SwitchLanePrototype,
-IntersectionPrototype};
+IntersectionPrototype, RoadClass, RightOfWay};
+use super::transport_planning::intersection_control::{SignalAspect, IntersectionControl};
+use super::signal_control::SignalControllerID;
+use super::parking::StreetParking;
+use super::pedestrians::SidewalkID;
+use super::roadworks::RoadworksID;
+use super::detectors::TrafficMeasurementsID;
 
 use cb_util::log::debug;
 const LOG_T: &str = "Transport Construction";
@@ -28,38 +34,74 @@ impl RoadPrototype {
         world: &mut World,
     ) -> CVec<ConstructableID<CBPrototypeKind>> {
         match *self {
-            RoadPrototype::Lane(LanePrototype(ref path, _)) => {
+            RoadPrototype::Lane(LanePrototype(
+                ref path,
+                _,
+                road_class,
+                right_of_way,
+                parking_spots,
+            )) => {
+                vec![LaneID::spawn_and_connect(
+                    path.clone(),
+                    false,
+                    CVec::new(),
+                    road_class,
+                    right_of_way,
+                    parking_spots,
+                    report_to,
+                    world,
+                )
+                .into()]
+                .into()
+            }
+            RoadPrototype::SwitchLane(SwitchLanePrototype(ref path, road_class)) => {
                 vec![
-                    LaneID::spawn_and_connect(path.clone(), false, CVec::new(), report_to, world)
+                    SwitchLaneID::spawn_and_connect(path.clone(), road_class, report_to, world)
                         .into(),
                 ]
                 .into()
             }
-            RoadPrototype::SwitchLane(SwitchLanePrototype(ref path)) => {
-                vec![SwitchLaneID::spawn_and_connect(path.clone(), report_to, world).into()].into()
-            }
             RoadPrototype::Intersection(IntersectionPrototype {
                 ref connecting_lanes,
+                ref control,
+                ref phases,
                 ..
             }) => {
-                let ids = connecting_lanes
-                    .values()
-                    .flat_map(|group| {
-                        group
+                let ids_by_connection = connecting_lanes
+                    .pairs()
+                    .map(|(connection, group)| {
+                        let ids = group
                             .iter()
-                            .map(|&LanePrototype(ref path, ref timings)| {
-                                LaneID::spawn_and_connect(
-                                    path.clone(),
-                                    true,
-                                    timings.clone(),
-                                    report_to,
-                                    world,
-                                )
-                            })
-                            .collect::<Vec<_>>()
+                            .map(
+                                |&LanePrototype(
+                                    ref path,
+                                    ref timings,
+                                    road_class,
+                                    right_of_way,
+                                    parking_spots,
+                                )| {
+                                    LaneID::spawn_and_connect(
+                                        path.clone(),
+                                        true,
+                                        timings.clone(),
+                                        road_class,
+                                        right_of_way,
+                                        parking_spots,
+                                        report_to,
+                                        world,
+                                    )
+                                },
+                            )
+                            .collect::<Vec<_>>();
+                        (*connection, ids)
                     })
                     .collect::<Vec<_>>();
 
+                let ids = ids_by_connection
+                    .iter()
+                    .flat_map(|&(_, ref ids)| ids.iter().cloned())
+                    .collect::<Vec<_>>();
+
                 for id in &ids {
                     id.start_connecting_overlaps(
                         ids.iter().filter(|&other| other != id).cloned().collect(),
@@ -67,7 +109,32 @@ impl RoadPrototype {
                     )
                 }
 
-                ids.into_iter().map(std::convert::Into::into).collect()
+                let maybe_controller = if let IntersectionControl::Adaptive(ref program) = *control
+                {
+                    let phase_lanes = phases
+                        .iter()
+                        .map(|connections_in_phase| {
+                            ids_by_connection
+                                .iter()
+                                .filter(|&&(connection, _)| {
+                                    connections_in_phase.contains(&connection)
+                                })
+                                .flat_map(|&(_, ref ids)| ids.iter().cloned())
+                                .collect()
+                        })
+                        .collect();
+                    Some(SignalControllerID::spawn(program.clone(), phase_lanes, report_to, world))
+                } else {
+                    None
+                };
+
+                ids.into_iter()
+                    .map(std::convert::Into::into)
+                    .chain(maybe_controller.map(std::convert::Into::into))
+                    .collect()
+            }
+            RoadPrototype::Sidewalk(ref sidewalk) => {
+                vec![SidewalkID::spawn(sidewalk.clone(), report_to, world).into()].into()
             }
             RoadPrototype::PavedArea(_) => CVec::new(),
         }
@@ -77,10 +144,27 @@ impl RoadPrototype {
 impl Constructable<CBPrototypeKind> for Lane {
     fn morph(
         &mut self,
-        _new_prototype: &Prototype<CBPrototypeKind>,
+        new_prototype: &Prototype<CBPrototypeKind>,
         report_to: CBConstructionID,
         world: &mut World,
     ) {
+        // intersections are morphed when only their control changed
+        if let CBPrototypeKind::Road(RoadPrototype::Intersection(IntersectionPrototype {
+            ref connecting_lanes,
+            ..
+        })) = new_prototype.kind
+        {
+            let path = &self.construction.path;
+            let maybe_new_lane = connecting_lanes
+                .values()
+                .flat_map(|group| group.iter())
+                .find(|&&LanePrototype(ref new_path, ..)| new_path.rough_eq_by(path, 0.05));
+
+            if let Some(&LanePrototype(_, ref timings, _, right_of_way, _)) = maybe_new_lane {
+                self.microtraffic.set_timings(timings.clone());
+                self.connectivity.right_of_way = right_of_way;
+            }
+        }
         report_to.action_done(self.id_as(), world);
     }
     fn destruct(&mut self, report_to: CBConstructionID, world: &mut World) -> Fate {
@@ -135,7 +219,10 @@ impl Lane {
         id: LaneID,
         path: &LinePath,
         on_intersection: bool,
-        timings: &CVec<bool>,
+        timings: &CVec<SignalAspect>,
+        road_class: RoadClass,
+        right_of_way: RightOfWay,
+        parking_spots: u16,
         report_to: CBConstructionID,
         world: &mut World,
     ) -> Lane {
@@ -150,13 +237,24 @@ impl Lane {
         if !on_intersection {
             SwitchLaneID::global_broadcast(world).connect_switch_to_normal(id, path.clone(), world);
         }
+        RoadworksID::global_broadcast(world).apply_to_lane(id, world);
+        TrafficMeasurementsID::global_first(world).place_on_lane(id, world);
         report_to.action_done(id.into(), world);
-        Lane::spawn(id, path, on_intersection, timings, world)
+        let mut lane = Lane::spawn(id, path, on_intersection, timings, road_class, world);
+        lane.connectivity.right_of_way = right_of_way;
+        lane.parking = StreetParking::new(parking_spots);
+        lane
     }
 
     pub fn start_connecting_overlaps(&mut self, lanes: &CVec<LaneID>, world: &mut World) {
         for &lane_id in lanes.iter() {
-            lane_id.connect_overlaps(self.id, self.construction.path.clone(), true, world);
+            lane_id.connect_overlaps(
+                self.id,
+                self.construction.path.clone(),
+                self.connectivity.right_of_way,
+                true,
+                world,
+            );
         }
     }
 
@@ -190,6 +288,8 @@ impl Lane {
                 self.connectivity.interactions.push(Interaction::Next {
                     next: other_id,
                     green: false,
+                    must_stop: false,
+                    closed: false,
                 });
             }
 
@@ -234,8 +334,39 @@ impl Lane {
         &mut self,
         other_id: LaneID,
         other_path: &LinePath,
+        other_right_of_way: RightOfWay,
         reply_needed: bool,
         world: &mut World,
+    ) {
+        // lanes following each other (like on a roundabout ring) only interact as such
+        let path = &self.construction.path;
+        let are_consecutive = path
+            .end()
+            .rough_eq_by(other_path.start(), LANE_CONNECTION_TOLERANCE)
+            || other_path
+                .end()
+                .rough_eq_by(path.start(), LANE_CONNECTION_TOLERANCE);
+
+        if !are_consecutive {
+            self.add_overlap_interaction(other_id, other_path, other_right_of_way);
+        }
+
+        if reply_needed {
+            other_id.connect_overlaps(
+                self.id,
+                self.construction.path.clone(),
+                self.connectivity.right_of_way,
+                false,
+                world,
+            );
+        }
+    }
+
+    fn add_overlap_interaction(
+        &mut self,
+        other_id: LaneID,
+        other_path: &LinePath,
+        other_right_of_way: RightOfWay,
     ) {
         let &(ref lane_band, ref lane_outline) = unsafe {
             MEMOIZED_BANDS_OUTLINES
@@ -295,15 +426,18 @@ impl Lane {
                         end: exit_distance,
                         conflicting_end: other_exit_distance.max(other_entry_distance),
                         can_weave,
+                        priority: match (self.connectivity.right_of_way, other_right_of_way) {
+                            (RightOfWay::Priority, RightOfWay::Yield) => ConflictPriority::Own,
+                            (RightOfWay::Yield, RightOfWay::Priority) => {
+                                ConflictPriority::Partner
+                            }
+                            _ => ConflictPriority::Shared,
+                        },
                     });
             } else {
                 panic!("both entry and exit should exist")
             }
         }
-
-        if reply_needed {
-            other_id.connect_overlaps(self.id, self.construction.path.clone(), false, world);
-        }
     }
 
     pub fn connect_to_switch(&mut self, other_id: SwitchLaneID, world: &mut World) {
@@ -334,12 +468,14 @@ impl Lane {
         self.microtraffic.obstacles.drain();
 
         let self_as_rough_location = self.id_as();
+        let self_as_lane_like = self.id_as();
 
         for car in self.microtraffic.cars.drain() {
             car.trip.finish(
                 TripResult {
                     location_now: Some(self_as_rough_location),
                     fate: TripFate::HopDisconnected,
+                    last_lane: Some(self_as_lane_like),
                 },
                 world,
             );
@@ -357,12 +493,14 @@ impl Lane {
         self.microtraffic.obstacles.drain();
 
         let self_as_rough_location = self.id_as();
+        let self_as_lane_like = self.id_as();
 
         for car in self.microtraffic.cars.drain() {
             car.trip.finish(
                 TripResult {
                     location_now: Some(self_as_rough_location),
                     fate: TripFate::HopDisconnected,
+                    last_lane: Some(self_as_lane_like),
                 },
                 world,
             );
@@ -439,12 +577,14 @@ impl Lane {
                 TripResult {
                     location_now: None,
                     fate: TripFate::LaneUnbuilt,
+                    last_lane: Some(self.id_as()),
                 },
                 world,
             );
         }
 
         ::transport::pathfinding::road_pathfinding::on_unbuild(self, world);
+        ::transport::parking::on_unbuild(self, world);
     }
 }
 
@@ -460,7 +600,7 @@ impl Lane {
         world: &mut World,
     ) {
         if let Some(location) = self.pathfinding.location {
-            if !self.connectivity.on_intersection {
+            if !self.connectivity.on_intersection && self.road_class.allows_building_access() {
                 let path = &self.construction.path;
                 let distance = path.distance_to(lot_position);
 
@@ -484,6 +624,10 @@ impl Lane {
                             projected_point,
                             world,
                         );
+
+                        if self.parking.capacity > 0 {
+                            building.offer_street_parking(self.id, world);
+                        }
                     }
                 }
             }
@@ -495,12 +639,14 @@ impl SwitchLane {
     pub fn spawn_and_connect(
         id: SwitchLaneID,
         path: &LinePath,
+        road_class: RoadClass,
         report_to: CBConstructionID,
         world: &mut World,
     ) -> SwitchLane {
         LaneID::global_broadcast(world).connect_to_switch(id, world);
+        RoadworksID::global_broadcast(world).apply_to_switch_lane(id, world);
 
-        let lane = SwitchLane::spawn(id, path, world);
+        let lane = SwitchLane::spawn(id, path, road_class, world);
         super::ui::on_build_switch(&lane, world);
 
         report_to.action_done(id.into(), world);
@@ -581,6 +727,7 @@ impl SwitchLane {
                             start: left_start_on_other_distance,
                             end: left_end_on_other_distance,
                             is_left: false,
+                            closed: self.microtraffic.is_closed(),
                         },
                         world,
                     );
@@ -592,6 +739,7 @@ impl SwitchLane {
                             start: right_start_on_other_distance,
                             end: right_end_on_other_distance,
                             is_left: true,
+                            closed: self.microtraffic.is_closed(),
                         },
                         world,
                     );
@@ -660,6 +808,7 @@ impl SwitchLane {
                 TripResult {
                     location_now: None,
                     fate: TripFate::LaneUnbuilt,
+                    last_lane: Some(self.id_as()),
                 },
                 world,
             );
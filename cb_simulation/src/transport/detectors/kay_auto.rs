@@ -0,0 +1,267 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TrafficMeasurementsListenerID {
+    _raw_id: RawID
+}
+
+impl Copy for TrafficMeasurementsListenerID {}
+impl Clone for TrafficMeasurementsListenerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TrafficMeasurementsListenerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TrafficMeasurementsListenerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TrafficMeasurementsListenerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TrafficMeasurementsListenerID {
+    fn eq(&self, other: &TrafficMeasurementsListenerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TrafficMeasurementsListenerID {}
+
+pub struct TrafficMeasurementsListenerRepresentative;
+
+impl ActorOrActorTrait for TrafficMeasurementsListenerRepresentative {
+    type ID = TrafficMeasurementsListenerID;
+}
+
+impl TypedID for TrafficMeasurementsListenerID {
+    type Target = TrafficMeasurementsListenerRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        TrafficMeasurementsListenerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + TrafficMeasurementsListener> TraitIDFrom<Act> for TrafficMeasurementsListenerID {}
+
+impl TrafficMeasurementsListenerID {
+    pub fn on_traffic_measurements(self, latest: CVec < DetectorMeasurement >, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurementsListener_on_traffic_measurements(latest));
+    }
+
+    pub fn on_traffic_measurements_csv(self, measurements: CString, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurementsListener_on_traffic_measurements_csv(measurements));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<TrafficMeasurementsListenerRepresentative>();
+        system.register_trait_message::<MSG_TrafficMeasurementsListener_on_traffic_measurements>();
+        system.register_trait_message::<MSG_TrafficMeasurementsListener_on_traffic_measurements_csv>();
+    }
+
+    pub fn register_implementor<Act: Actor + TrafficMeasurementsListener>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, TrafficMeasurementsListenerRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_TrafficMeasurementsListener_on_traffic_measurements(ref latest), instance, world| {
+                instance.on_traffic_measurements(latest, world); Fate::Live
+            }, false
+        );
+
+        system.add_handler::<Act, _, _>(
+            |&MSG_TrafficMeasurementsListener_on_traffic_measurements_csv(ref measurements), instance, world| {
+                instance.on_traffic_measurements_csv(measurements, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurementsListener_on_traffic_measurements(pub CVec < DetectorMeasurement >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurementsListener_on_traffic_measurements_csv(pub CString);
+
+impl Actor for TrafficMeasurements {
+    type ID = TrafficMeasurementsID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TrafficMeasurementsID {
+    _raw_id: RawID
+}
+
+impl Copy for TrafficMeasurementsID {}
+impl Clone for TrafficMeasurementsID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TrafficMeasurementsID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TrafficMeasurementsID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TrafficMeasurementsID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TrafficMeasurementsID {
+    fn eq(&self, other: &TrafficMeasurementsID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TrafficMeasurementsID {}
+
+impl TypedID for TrafficMeasurementsID {
+    type Target = TrafficMeasurements;
+
+    fn from_raw(id: RawID) -> Self {
+        TrafficMeasurementsID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TrafficMeasurementsID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = TrafficMeasurementsID::from_raw(world.allocate_instance_id::<TrafficMeasurements>());
+        let swarm = world.local_broadcast::<TrafficMeasurements>();
+        world.send(swarm, MSG_TrafficMeasurements_spawn(id, ));
+        id
+    }
+
+    pub fn record(self, measurement: DetectorMeasurement, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_record(measurement));
+    }
+
+    pub fn reset(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_reset());
+    }
+
+    pub fn get_latest(self, requester: TrafficMeasurementsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_get_latest(requester));
+    }
+
+    pub fn export_csv(self, requester: TrafficMeasurementsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_export_csv(requester));
+    }
+
+    pub fn detector_placed(self, location: P2, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_detector_placed(location));
+    }
+
+    pub fn detectors_removed_near(self, position: P2, radius: N, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_detectors_removed_near(position, radius));
+    }
+
+    pub fn place_on_lane(self, lane: LaneID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TrafficMeasurements_place_on_lane(lane));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_spawn(pub TrafficMeasurementsID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_record(pub DetectorMeasurement);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_reset();
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_get_latest(pub TrafficMeasurementsListenerID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_export_csv(pub TrafficMeasurementsListenerID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_detector_placed(pub P2);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_detectors_removed_near(pub P2, pub N);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TrafficMeasurements_place_on_lane(pub LaneID);
+
+impl LaneID {
+    pub fn add_detector_near(self, position: P2, radius: N, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_add_detector_near(position, radius));
+    }
+
+    pub fn remove_detectors_near(self, position: P2, radius: N, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_remove_detectors_near(position, radius));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_add_detector_near(pub P2, pub N);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_remove_detectors_near(pub P2, pub N);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    TrafficMeasurementsListenerID::register_trait(system);
+    system.add_spawner::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_spawn(id, ), world| {
+            TrafficMeasurements::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_record(measurement), instance, world| {
+            instance.record(measurement, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_reset(), instance, world| {
+            instance.reset(world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_get_latest(requester), instance, world| {
+            instance.get_latest(requester, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_export_csv(requester), instance, world| {
+            instance.export_csv(requester, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_detector_placed(location), instance, world| {
+            instance.detector_placed(location, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_detectors_removed_near(position, radius), instance, world| {
+            instance.detectors_removed_near(position, radius, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TrafficMeasurements, _, _>(
+        |&MSG_TrafficMeasurements_place_on_lane(lane), instance, world| {
+            instance.place_on_lane(lane, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_add_detector_near(position, radius), instance, world| {
+            instance.add_detector_near(position, radius, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_remove_detectors_near(position, radius), instance, world| {
+            instance.remove_detectors_near(position, radius, world); Fate::Live
+        }, false
+    );
+}
@@ -0,0 +1,325 @@
+//! Virtual loop detectors at positions on lanes. Each one counts the cars passing it,
+//! measures their speeds and how much of the time it is covered by a vehicle, and reports
+//! that as one measurement per interval into a time series, to tune signals and road designs.
+
+use kay::{ActorSystem, World, TypedID};
+use compact::{CVec, CString};
+use descartes::{N, P2};
+use ordered_float::OrderedFloat;
+use std::collections::HashMap;
+use std::fmt::Write;
+use cb_time::units::Instant;
+
+use super::lane::{Lane, LaneID};
+use super::microtraffic::{LaneCar, VehicleType};
+
+use cb_util::log::info;
+const LOG_T: &str = "Detectors";
+
+/// Length of one measurement interval, in s of traffic time
+pub const MEASUREMENT_INTERVAL: f32 = 60.0;
+/// Once this many measurements are recorded, the oldest quarter of them is dropped
+const MAX_RECORDS: usize = 100_000;
+/// Detectors closer to each other than this are the same one, in m
+const PLACEMENT_TOLERANCE: N = 1.0;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Detector {
+    /// Distance along the lane, in m
+    pub position: f32,
+    pub location: P2,
+    /// Traffic time measured in the current interval, in s
+    measured_time: f32,
+    n_cars: u32,
+    speed_sum: f32,
+    /// Traffic time during which a vehicle was over the detector, in s
+    occupied_time: f32,
+}
+
+impl Detector {
+    pub fn new(position: f32, location: P2) -> Detector {
+        Detector {
+            position,
+            location,
+            measured_time: 0.0,
+            n_cars: 0,
+            speed_sum: 0.0,
+            occupied_time: 0.0,
+        }
+    }
+
+    /// Has to be called right before the cars move by `dt`
+    pub fn measure(&mut self, cars: &[LaneCar], dt: f32) {
+        self.measured_time += dt;
+        let mut occupied = false;
+
+        for car in cars {
+            let rear = *car.position;
+            let front = rear + car.vehicle_type.parameters().length;
+
+            // counted when the front passes, since the rear might already be on the next lane
+            if front < self.position && front + dt * car.velocity >= self.position {
+                self.n_cars += 1;
+                self.speed_sum += car.velocity;
+            }
+
+            if rear <= self.position && front >= self.position {
+                occupied = true;
+            }
+        }
+
+        if occupied {
+            self.occupied_time += dt;
+        }
+    }
+
+    /// Once the current interval is over, sums it up and starts the next one
+    pub fn finish_interval(
+        &mut self,
+        lane: LaneID,
+        current_instant: Instant,
+    ) -> Option<DetectorMeasurement> {
+        if self.measured_time < MEASUREMENT_INTERVAL {
+            return None;
+        }
+
+        let measurement = DetectorMeasurement {
+            lane,
+            position: self.position,
+            location: self.location,
+            ended_at: current_instant,
+            duration: self.measured_time,
+            n_cars: self.n_cars,
+            flow: self.n_cars as f32 * 3600.0 / self.measured_time,
+            mean_speed: if self.n_cars > 0 {
+                Some(self.speed_sum / self.n_cars as f32)
+            } else {
+                None
+            },
+            occupancy: self.occupied_time / self.measured_time,
+        };
+
+        *self = Detector::new(self.position, self.location);
+
+        Some(measurement)
+    }
+}
+
+/// What a detector measured during one interval
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DetectorMeasurement {
+    pub lane: LaneID,
+    /// Distance along the lane, in m
+    pub position: f32,
+    pub location: P2,
+    pub ended_at: Instant,
+    /// Length of the interval, in s of traffic time
+    pub duration: f32,
+    pub n_cars: u32,
+    /// In vehicles per hour
+    pub flow: f32,
+    /// Of the cars that passed, in m/s
+    pub mean_speed: Option<f32>,
+    /// Share of the interval during which a vehicle was over the detector
+    pub occupancy: f32,
+}
+
+impl Lane {
+    /// Places a detector where `position` projects onto the lane, if it is close enough
+    /// and there isn't one there already
+    pub fn add_detector_near(&mut self, position: P2, radius: N, world: &mut World) {
+        if let Some((along, projected)) = self.construction.path.project(position) {
+            if (projected - position).norm() >= radius {
+                return;
+            }
+
+            if self
+                .microtraffic
+                .detectors
+                .iter()
+                .any(|detector| (detector.position - along).abs() < PLACEMENT_TOLERANCE)
+            {
+                return;
+            }
+
+            // vehicles enter a lane with their front already a vehicle length along it,
+            // so they would pass a detector before that without being counted
+            if along < VehicleType::max_length() {
+                info(
+                    LOG_T,
+                    format!(
+                        "Can't place a detector {:.1}m along the lane, it has to be at least {}m",
+                        along,
+                        VehicleType::max_length()
+                    ),
+                    self.id,
+                    world,
+                );
+                return;
+            }
+
+            self.microtraffic
+                .detectors
+                .push(Detector::new(along, projected));
+            TrafficMeasurementsID::global_first(world).detector_placed(projected, world);
+            info(
+                LOG_T,
+                format!("Placed a detector {:.1}m along the lane", along),
+                self.id,
+                world,
+            );
+        }
+    }
+
+    pub fn remove_detectors_near(&mut self, position: P2, radius: N, _: &mut World) {
+        self.microtraffic
+            .detectors
+            .retain(|detector| (detector.location - position).norm() >= radius);
+    }
+}
+
+/// Places a detector on every lane within `radius` of `position`
+pub fn add_detectors_near(position: P2, radius: N, world: &mut World) {
+    LaneID::global_broadcast(world).add_detector_near(position, radius, world);
+}
+
+pub fn remove_detectors_near(position: P2, radius: N, world: &mut World) {
+    LaneID::global_broadcast(world).remove_detectors_near(position, radius, world);
+    TrafficMeasurementsID::global_first(world).detectors_removed_near(position, radius, world);
+}
+
+pub trait TrafficMeasurementsListener {
+    fn on_traffic_measurements(&mut self, latest: &CVec<DetectorMeasurement>, world: &mut World);
+    fn on_traffic_measurements_csv(&mut self, measurements: &CString, world: &mut World);
+}
+
+/// Collects the measurements of all detectors into one time series
+#[derive(Compact, Clone)]
+pub struct TrafficMeasurements {
+    id: TrafficMeasurementsID,
+    records: CVec<DetectorMeasurement>,
+    /// Where detectors were placed, to place them again on lanes rebuilt there
+    placements: CVec<P2>,
+}
+
+impl TrafficMeasurements {
+    pub fn spawn(id: TrafficMeasurementsID, _: &mut World) -> TrafficMeasurements {
+        TrafficMeasurements {
+            id,
+            records: CVec::new(),
+            placements: CVec::new(),
+        }
+    }
+
+    pub fn detector_placed(&mut self, location: P2, _: &mut World) {
+        if !self
+            .placements
+            .iter()
+            .any(|placement| (*placement - location).norm() < PLACEMENT_TOLERANCE)
+        {
+            self.placements.push(location);
+        }
+    }
+
+    pub fn detectors_removed_near(&mut self, position: P2, radius: N, _: &mut World) {
+        self.placements.retain(|placement| (*placement - position).norm() >= radius);
+    }
+
+    /// Places the detectors that were on the lanes a new lane replaces
+    pub fn place_on_lane(&mut self, lane: LaneID, world: &mut World) {
+        for placement in self.placements.iter() {
+            lane.add_detector_near(*placement, PLACEMENT_TOLERANCE, world);
+        }
+    }
+
+    pub fn record(&mut self, measurement: DetectorMeasurement, world: &mut World) {
+        if self.records.len() >= MAX_RECORDS {
+            let n_dropped = MAX_RECORDS / 4;
+            self.records = self.records[n_dropped..].to_vec().into();
+            info(
+                LOG_T,
+                format!("Dropped the oldest {} detector measurements", n_dropped),
+                self.id,
+                world,
+            );
+        }
+
+        self.records.push(measurement);
+    }
+
+    /// Forgets all measurements so far, for example right after retiming signals
+    pub fn reset(&mut self, _: &mut World) {
+        self.records = CVec::new();
+    }
+
+    pub fn get_latest(&mut self, requester: TrafficMeasurementsListenerID, world: &mut World) {
+        requester.on_traffic_measurements(self.latest(), world);
+    }
+
+    pub fn export_csv(&mut self, requester: TrafficMeasurementsListenerID, world: &mut World) {
+        requester.on_traffic_measurements_csv(self.csv().into(), world);
+    }
+
+    /// The most recent measurement of each detector, ordered by location
+    fn latest(&self) -> CVec<DetectorMeasurement> {
+        let mut latest: HashMap<(LaneID, OrderedFloat<f32>), DetectorMeasurement> =
+            HashMap::new();
+
+        for measurement in self.records.iter() {
+            latest.insert(
+                (measurement.lane, OrderedFloat(measurement.position)),
+                *measurement,
+            );
+        }
+
+        let mut sorted: Vec<DetectorMeasurement> = latest.values().cloned().collect();
+        sorted.sort_by_key(|measurement| {
+            (
+                OrderedFloat(measurement.location.x),
+                OrderedFloat(measurement.location.y),
+            )
+        });
+        sorted.into()
+    }
+
+    fn csv(&self) -> String {
+        let mut csv = "lane,position_m,x,y,ended_at,duration_s,n_cars,flow_veh_h,\
+                       mean_speed_m_s,occupancy\n"
+            .to_owned();
+
+        for measurement in self.records.iter() {
+            writeln!(
+                csv,
+                "\"{:?}\",{},{},{},{},{},{},{},{},{}",
+                measurement.lane.as_raw(),
+                measurement.position,
+                measurement.location.x,
+                measurement.location.y,
+                measurement.ended_at.ticks(),
+                measurement.duration,
+                measurement.n_cars,
+                measurement.flow,
+                measurement
+                    .mean_speed
+                    .map(|speed| speed.to_string())
+                    .unwrap_or_default(),
+                measurement.occupancy
+            )
+            .expect("writing to a string can't fail");
+        }
+
+        csv
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<TrafficMeasurements>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    TrafficMeasurementsID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
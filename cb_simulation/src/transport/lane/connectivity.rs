@@ -2,11 +2,13 @@ use compact::CVec;
 use descartes::N;
 use super::{LaneID, SwitchLaneID};
 use transport::microtraffic::LaneLikeID;
+use transport::transport_planning::RightOfWay;
 
 #[derive(Compact, Clone)]
 pub struct ConnectivityInfo {
     pub interactions: CVec<Interaction>,
     pub on_intersection: bool,
+    pub right_of_way: RightOfWay,
 }
 
 impl ConnectivityInfo {
@@ -14,6 +16,7 @@ impl ConnectivityInfo {
         ConnectivityInfo {
             interactions: CVec::new(),
             on_intersection,
+            right_of_way: RightOfWay::Priority,
         }
     }
 }
@@ -26,6 +29,15 @@ pub struct SwitchConnectivityInfo {
     pub right_distance_map: CVec<(N, N)>,
 }
 
+/// Which of two conflicting lanes has to give way to the other
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConflictPriority {
+    /// Both lanes give way to each other
+    Shared,
+    Own,
+    Partner,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Interaction {
     Previous {
@@ -35,6 +47,9 @@ pub enum Interaction {
     Next {
         next: LaneID,
         green: bool,
+        must_stop: bool,
+        /// The next lane is closed and no car may enter it
+        closed: bool,
     },
     Conflicting {
         conflicting: LaneID,
@@ -43,6 +58,7 @@ pub enum Interaction {
         end: N,
         conflicting_end: N,
         can_weave: bool,
+        priority: ConflictPriority,
     },
     Switch {
         via: SwitchLaneID,
@@ -50,6 +66,8 @@ pub enum Interaction {
         is_left: bool,
         start: N,
         end: N,
+        /// The switch lane is closed and no car may change lanes through it
+        closed: bool,
     },
 }
 
@@ -55,16 +55,16 @@ impl TypedID for LaneID {
 }
 
 impl LaneID {
-    pub fn spawn(path: LinePath, on_intersection: bool, timings: CVec < bool >, world: &mut World) -> Self {
+    pub fn spawn(path: LinePath, on_intersection: bool, timings: CVec < SignalAspect >, road_class: RoadClass, world: &mut World) -> Self {
         let id = LaneID::from_raw(world.allocate_instance_id::<Lane>());
         let swarm = world.local_broadcast::<Lane>();
-        world.send(swarm, MSG_Lane_spawn(id, path, on_intersection, timings));
+        world.send(swarm, MSG_Lane_spawn(id, path, on_intersection, timings, road_class));
         id
     }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_Lane_spawn(pub LaneID, pub LinePath, pub bool, pub CVec < bool >);
+struct MSG_Lane_spawn(pub LaneID, pub LinePath, pub bool, pub CVec < SignalAspect >, pub RoadClass);
 
 
 impl Actor for SwitchLane {
@@ -115,16 +115,16 @@ impl TypedID for SwitchLaneID {
 }
 
 impl SwitchLaneID {
-    pub fn spawn(path: LinePath, world: &mut World) -> Self {
+    pub fn spawn(path: LinePath, road_class: RoadClass, world: &mut World) -> Self {
         let id = SwitchLaneID::from_raw(world.allocate_instance_id::<SwitchLane>());
         let swarm = world.local_broadcast::<SwitchLane>();
-        world.send(swarm, MSG_SwitchLane_spawn(id, path));
+        world.send(swarm, MSG_SwitchLane_spawn(id, path, road_class));
         id
     }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_SwitchLane_spawn(pub SwitchLaneID, pub LinePath);
+struct MSG_SwitchLane_spawn(pub SwitchLaneID, pub LinePath, pub RoadClass);
 
 
 #[allow(unused_variables)]
@@ -133,14 +133,14 @@ pub fn auto_setup(system: &mut ActorSystem) {
     
     
     system.add_spawner::<Lane, _, _>(
-        |&MSG_Lane_spawn(id, ref path, on_intersection, ref timings), world| {
-            Lane::spawn(id, path, on_intersection, timings, world)
+        |&MSG_Lane_spawn(id, ref path, on_intersection, ref timings, road_class), world| {
+            Lane::spawn(id, path, on_intersection, timings, road_class, world)
         }, false
     );
     
     system.add_spawner::<SwitchLane, _, _>(
-        |&MSG_SwitchLane_spawn(id, ref path), world| {
-            SwitchLane::spawn(id, path, world)
+        |&MSG_SwitchLane_spawn(id, ref path, road_class), world| {
+            SwitchLane::spawn(id, path, road_class, world)
         }, false
     );
 }
\ No newline at end of file
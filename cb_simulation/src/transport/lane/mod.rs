@@ -7,14 +7,19 @@ pub mod connectivity;
 use self::connectivity::{ConnectivityInfo, SwitchConnectivityInfo};
 use super::microtraffic::{Microtraffic, TransferringMicrotraffic};
 use super::pathfinding::PathfindingCore;
+use super::parking::StreetParking;
+use super::transport_planning::RoadClass;
+use super::transport_planning::intersection_control::SignalAspect;
 
 #[derive(Compact, Clone)]
 pub struct Lane {
     pub id: LaneID,
+    pub road_class: RoadClass,
     pub construction: ConstructionInfo,
     pub connectivity: ConnectivityInfo,
     pub microtraffic: Microtraffic,
     pub pathfinding: PathfindingCore,
+    pub parking: StreetParking,
 }
 
 impl Lane {
@@ -22,15 +27,18 @@ impl Lane {
         id: LaneID,
         path: &LinePath,
         on_intersection: bool,
-        timings: &CVec<bool>,
+        timings: &CVec<SignalAspect>,
+        road_class: RoadClass,
         world: &mut World,
     ) -> Self {
         let lane = Lane {
             id,
+            road_class,
             construction: ConstructionInfo::from_path(path.clone()),
             connectivity: ConnectivityInfo::new(on_intersection),
-            microtraffic: Microtraffic::new(timings.clone()),
+            microtraffic: Microtraffic::new(timings.clone(), road_class.speed_limit()),
             pathfinding: PathfindingCore::default(),
+            parking: StreetParking::default(),
         };
 
         super::ui::on_build(&lane, world);
@@ -42,18 +50,25 @@ impl Lane {
 #[derive(Compact, Clone)]
 pub struct SwitchLane {
     pub id: SwitchLaneID,
+    pub road_class: RoadClass,
     pub construction: ConstructionInfo,
     pub connectivity: SwitchConnectivityInfo,
     pub microtraffic: TransferringMicrotraffic,
 }
 
 impl SwitchLane {
-    pub fn spawn(id: SwitchLaneID, path: &LinePath, _: &mut World) -> SwitchLane {
+    pub fn spawn(
+        id: SwitchLaneID,
+        path: &LinePath,
+        road_class: RoadClass,
+        _: &mut World,
+    ) -> SwitchLane {
         SwitchLane {
             id,
+            road_class,
             construction: ConstructionInfo::from_path(path.clone()),
             connectivity: SwitchConnectivityInfo::default(),
-            microtraffic: TransferringMicrotraffic::default(),
+            microtraffic: TransferringMicrotraffic::new(road_class.speed_limit()),
         }
     }
 
@@ -0,0 +1,179 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct DriverDistributionsRequesterID {
+    _raw_id: RawID
+}
+
+impl Copy for DriverDistributionsRequesterID {}
+impl Clone for DriverDistributionsRequesterID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for DriverDistributionsRequesterID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "DriverDistributionsRequesterID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for DriverDistributionsRequesterID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for DriverDistributionsRequesterID {
+    fn eq(&self, other: &DriverDistributionsRequesterID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for DriverDistributionsRequesterID {}
+
+pub struct DriverDistributionsRequesterRepresentative;
+
+impl ActorOrActorTrait for DriverDistributionsRequesterRepresentative {
+    type ID = DriverDistributionsRequesterID;
+}
+
+impl TypedID for DriverDistributionsRequesterID {
+    type Target = DriverDistributionsRequesterRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        DriverDistributionsRequesterID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + DriverDistributionsRequester> TraitIDFrom<Act> for DriverDistributionsRequesterID {}
+
+impl DriverDistributionsRequesterID {
+    pub fn on_driver_distributions(self, distributions: DriverDistributions, world: &mut World) {
+        world.send(self.as_raw(), MSG_DriverDistributionsRequester_on_driver_distributions(distributions));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<DriverDistributionsRequesterRepresentative>();
+        system.register_trait_message::<MSG_DriverDistributionsRequester_on_driver_distributions>();
+    }
+
+    pub fn register_implementor<Act: Actor + DriverDistributionsRequester>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, DriverDistributionsRequesterRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_DriverDistributionsRequester_on_driver_distributions(distributions), instance, world| {
+                instance.on_driver_distributions(distributions, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverDistributionsRequester_on_driver_distributions(pub DriverDistributions);
+
+impl Actor for DriverBehaviourSettings {
+    type ID = DriverBehaviourSettingsID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct DriverBehaviourSettingsID {
+    _raw_id: RawID
+}
+
+impl Copy for DriverBehaviourSettingsID {}
+impl Clone for DriverBehaviourSettingsID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for DriverBehaviourSettingsID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "DriverBehaviourSettingsID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for DriverBehaviourSettingsID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for DriverBehaviourSettingsID {
+    fn eq(&self, other: &DriverBehaviourSettingsID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for DriverBehaviourSettingsID {}
+
+impl TypedID for DriverBehaviourSettingsID {
+    type Target = DriverBehaviourSettings;
+
+    fn from_raw(id: RawID) -> Self {
+        DriverBehaviourSettingsID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl DriverBehaviourSettingsID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = DriverBehaviourSettingsID::from_raw(world.allocate_instance_id::<DriverBehaviourSettings>());
+        let swarm = world.local_broadcast::<DriverBehaviourSettings>();
+        world.send(swarm, MSG_DriverBehaviourSettings_spawn(id, ));
+        id
+    }
+
+    pub fn set_distributions(self, distributions: DriverDistributions, world: &mut World) {
+        world.send(self.as_raw(), MSG_DriverBehaviourSettings_set_distributions(distributions));
+    }
+
+    pub fn get_distributions(self, requester: DriverDistributionsRequesterID, world: &mut World) {
+        world.send(self.as_raw(), MSG_DriverBehaviourSettings_get_distributions(requester));
+    }
+
+    pub fn assign_driver(self, trip: TripID, world: &mut World) {
+        world.send(self.as_raw(), MSG_DriverBehaviourSettings_assign_driver(trip));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverBehaviourSettings_spawn(pub DriverBehaviourSettingsID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverBehaviourSettings_set_distributions(pub DriverDistributions);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverBehaviourSettings_get_distributions(pub DriverDistributionsRequesterID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_DriverBehaviourSettings_assign_driver(pub TripID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    DriverDistributionsRequesterID::register_trait(system);
+    system.add_spawner::<DriverBehaviourSettings, _, _>(
+        |&MSG_DriverBehaviourSettings_spawn(id, ), world| {
+            DriverBehaviourSettings::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<DriverBehaviourSettings, _, _>(
+        |&MSG_DriverBehaviourSettings_set_distributions(distributions), instance, world| {
+            instance.set_distributions(distributions, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<DriverBehaviourSettings, _, _>(
+        |&MSG_DriverBehaviourSettings_get_distributions(requester), instance, world| {
+            instance.get_distributions(requester, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<DriverBehaviourSettings, _, _>(
+        |&MSG_DriverBehaviourSettings_assign_driver(trip), instance, world| {
+            instance.assign_driver(trip, world); Fate::Live
+        }, false
+    );
+}
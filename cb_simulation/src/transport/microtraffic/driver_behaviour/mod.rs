@@ -0,0 +1,219 @@
+//! Drivers differ in how fast they like to drive, how much distance they keep and how
+//! quickly they react. Each trip draws its driver from configurable distributions
+//! when it starts, seeded by the trip, so the same trip always gets the same driver.
+
+use kay::{ActorSystem, World};
+use cb_util::random::{seed, Rng};
+use std::f32::consts::PI;
+
+use transport::pathfinding::trip::TripID;
+
+/// A normal distribution, cut off at `min` and `max` to keep drivers plausible
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct TruncatedNormal {
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// How often a value outside the bounds is drawn again before giving up on the distribution
+const MAX_RESAMPLES: usize = 100;
+
+impl TruncatedNormal {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f32 {
+        for _ in 0..MAX_RESAMPLES {
+            // Box-Muller transform
+            let u1: f32 = rng.gen_range(::std::f32::EPSILON, 1.0);
+            let u2: f32 = rng.gen_range(0.0, 1.0);
+            let standard_normal = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+            let value = self.mean + self.std_dev * standard_normal;
+            if value >= self.min && value <= self.max {
+                return value;
+            }
+        }
+        // the bounds are far off the mean, rather than piling drivers up at a bound
+        // pick the most likely value between them
+        self.mean.max(self.min).min(self.max)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DriverDistributions {
+    pub desired_speed_factor: TruncatedNormal,
+    pub time_headway_factor: TruncatedNormal,
+    /// In s
+    pub reaction_delay: TruncatedNormal,
+}
+
+impl Default for DriverDistributions {
+    fn default() -> DriverDistributions {
+        DriverDistributions {
+            desired_speed_factor: TruncatedNormal {
+                mean: 1.0,
+                std_dev: 0.1,
+                min: 0.7,
+                max: 1.3,
+            },
+            time_headway_factor: TruncatedNormal {
+                mean: 1.0,
+                std_dev: 0.2,
+                min: 0.5,
+                max: 2.0,
+            },
+            reaction_delay: TruncatedNormal {
+                mean: 0.8,
+                std_dev: 0.3,
+                min: 0.3,
+                max: 2.0,
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DriverBehaviour {
+    /// Scales the speed the driver would like to drive at, up to the vehicle's maximum
+    pub desired_speed_factor: f32,
+    /// Scales the time headway the driver keeps to whatever is in front
+    pub time_headway_factor: f32,
+    /// How long it takes until the driver acts on a change in traffic, in s
+    pub reaction_delay: f32,
+}
+
+impl Default for DriverBehaviour {
+    /// An average driver that reacts instantly
+    fn default() -> DriverBehaviour {
+        DriverBehaviour {
+            desired_speed_factor: 1.0,
+            time_headway_factor: 1.0,
+            reaction_delay: 0.0,
+        }
+    }
+}
+
+impl DriverDistributions {
+    pub fn sample_driver(&self, trip: TripID) -> DriverBehaviour {
+        let mut rng = seed(trip);
+        DriverBehaviour {
+            desired_speed_factor: self.desired_speed_factor.sample(&mut rng),
+            time_headway_factor: self.time_headway_factor.sample(&mut rng),
+            reaction_delay: self.reaction_delay.sample(&mut rng),
+        }
+    }
+}
+
+/// How many things a driver saw but didn't react to yet they can keep in mind
+const PERCEPTION_MEMORY: usize = 8;
+
+/// The accelerations a driver saw they should be doing and when, so they can act on each
+/// of them exactly their reaction delay later
+#[derive(Copy, Clone, Default)]
+pub struct Perceptions {
+    /// Time since the driver set off, in s
+    clock: f32,
+    /// Times and accelerations, oldest first
+    seen: [(f32, f32); PERCEPTION_MEMORY],
+    n_seen: usize,
+}
+
+impl Perceptions {
+    /// Forgets everything not acted on yet, leaving only `acceleration` as seen just now
+    pub fn reset_to(&mut self, acceleration: f32) {
+        self.seen[0] = (self.clock, acceleration);
+        self.n_seen = 1;
+    }
+
+    /// Only remembers one acceleration per `PERCEPTION_MEMORY`th of the reaction delay,
+    /// so the memory always reaches back far enough
+    pub fn remember(&mut self, acceleration: f32, reaction_delay: f32) {
+        if self.n_seen > 0 {
+            let (last_seen_at, _) = self.seen[self.n_seen - 1];
+            if self.clock - last_seen_at < reaction_delay / PERCEPTION_MEMORY as f32 {
+                return;
+            }
+        }
+
+        if self.n_seen == PERCEPTION_MEMORY {
+            self.forget_oldest(1);
+        }
+
+        self.seen[self.n_seen] = (self.clock, acceleration);
+        self.n_seen += 1;
+    }
+
+    /// Advances the clock and returns the latest acceleration seen at least
+    /// `reaction_delay` ago, if any is newly due
+    pub fn recall(&mut self, dt: f32, reaction_delay: f32) -> Option<f32> {
+        self.clock += dt;
+        let seen_until = self.clock - reaction_delay;
+        let n_due = self.seen[..self.n_seen]
+            .iter()
+            .take_while(|&&(seen_at, _)| seen_at <= seen_until)
+            .count();
+
+        if n_due == 0 {
+            None
+        } else {
+            let (_, acceleration) = self.seen[n_due - 1];
+            self.forget_oldest(n_due);
+            Some(acceleration)
+        }
+    }
+
+    fn forget_oldest(&mut self, n: usize) {
+        for i in n..self.n_seen {
+            self.seen[i - n] = self.seen[i];
+        }
+        self.n_seen -= n;
+    }
+}
+
+pub trait DriverDistributionsRequester {
+    fn on_driver_distributions(&mut self, distributions: DriverDistributions, world: &mut World);
+}
+
+/// Keeps the distributions that trips draw their drivers from
+#[derive(Compact, Clone)]
+pub struct DriverBehaviourSettings {
+    id: DriverBehaviourSettingsID,
+    distributions: DriverDistributions,
+}
+
+impl DriverBehaviourSettings {
+    pub fn spawn(id: DriverBehaviourSettingsID, _: &mut World) -> DriverBehaviourSettings {
+        DriverBehaviourSettings {
+            id,
+            distributions: DriverDistributions::default(),
+        }
+    }
+
+    /// Only affects trips started from now on
+    pub fn set_distributions(&mut self, distributions: DriverDistributions, _: &mut World) {
+        self.distributions = distributions;
+    }
+
+    pub fn get_distributions(
+        &mut self,
+        requester: DriverDistributionsRequesterID,
+        world: &mut World,
+    ) {
+        requester.on_driver_distributions(self.distributions, world);
+    }
+
+    pub fn assign_driver(&mut self, trip: TripID, world: &mut World) {
+        trip.driver_assigned(self.distributions.sample_driver(trip), world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<DriverBehaviourSettings>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    DriverBehaviourSettingsID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
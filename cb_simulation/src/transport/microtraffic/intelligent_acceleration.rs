@@ -1,33 +1,35 @@
 use super::Obstacle;
-
-pub const COMFORTABLE_BREAKING_DECELERATION: f32 = 0.4;
+use super::VehicleType;
+use super::driver_behaviour::DriverBehaviour;
 
 pub fn intelligent_acceleration(
     car: &Obstacle,
+    vehicle_type: VehicleType,
+    driver: DriverBehaviour,
     obstacle: &Obstacle,
     safe_time_headway: f32,
+    speed_limit: f32,
 ) -> f32 {
     // http://en.wikipedia.org/wiki/Intelligent_driver_model
 
-    let car_length = 4.0;
-    let acceleration = 0.4;
-    let max_deceleration: f32 = 5.0;
-    let desired_velocity = car.max_velocity;
+    let vehicle = vehicle_type.parameters();
+    let desired_velocity =
+        (car.max_velocity.min(speed_limit) * driver.desired_speed_factor).min(car.max_velocity);
+    let safe_time_headway = safe_time_headway * driver.time_headway_factor;
     let acceleration_exponent = 4.0;
-    let minimum_spacing = 4.0;
 
-    let net_distance = *obstacle.position - *car.position - car_length;
+    let net_distance = *obstacle.position - *car.position - car.length;
     let velocity_difference = car.velocity - obstacle.velocity;
 
-    let s_star = minimum_spacing
+    let s_star = vehicle.minimum_spacing
         + 0.0f32.max(
             car.velocity * safe_time_headway
                 + (car.velocity * velocity_difference
-                    / (2.0 * (acceleration * COMFORTABLE_BREAKING_DECELERATION).sqrt())),
+                    / (2.0 * (vehicle.acceleration * vehicle.comfortable_deceleration).sqrt())),
         );
 
-    (-max_deceleration).max(
-        acceleration
+    (-vehicle.max_deceleration).max(
+        vehicle.acceleration
             * (1.0
                 - (car.velocity / desired_velocity).powf(acceleration_exponent)
                 - (s_star / net_distance).powf(2.0)),
@@ -88,13 +88,31 @@ struct MSG_LaneLike_add_obstacles(pub CVec < Obstacle >, pub LaneLikeID);
 
 
 impl LaneID {
-    pub fn on_signal_changed(self, from: LaneID, new_green: bool, world: &mut World) {
-        world.send(self.as_raw(), MSG_Lane_on_signal_changed(from, new_green));
+    pub fn on_signal_changed(self, from: LaneID, new_green: bool, new_must_stop: bool, new_closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_on_signal_changed(from, new_green, new_must_stop, new_closed));
+    }
+    
+    pub fn on_neighbouring_cars(self, via: SwitchLaneID, cars: CVec < Obstacle >, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_on_neighbouring_cars(via, cars));
+    }
+    
+    pub fn on_switch_closed(self, from: SwitchLaneID, new_closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_on_switch_closed(from, new_closed));
+    }
+    
+    pub fn set_closed(self, roadworks: RoadworksID, closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_set_closed(roadworks, closed));
     }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_Lane_on_signal_changed(pub LaneID, pub bool);
+struct MSG_Lane_on_signal_changed(pub LaneID, pub bool, pub bool, pub bool);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_on_neighbouring_cars(pub SwitchLaneID, pub CVec < Obstacle >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_on_switch_closed(pub SwitchLaneID, pub bool);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_set_closed(pub RoadworksID, pub bool);
 
 impl Into<LaneLikeID> for LaneID {
     fn into(self) -> LaneLikeID {
@@ -110,9 +128,13 @@ impl Into<TemporalID> for LaneID {
 
 
 impl SwitchLaneID {
-    
+    pub fn set_closed(self, roadworks: RoadworksID, closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_SwitchLane_set_closed(roadworks, closed));
+    }
 }
 
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_SwitchLane_set_closed(pub RoadworksID, pub bool);
 
 
 impl Into<LaneLikeID> for SwitchLaneID {
@@ -134,10 +156,33 @@ pub fn auto_setup(system: &mut ActorSystem) {
     LaneLikeID::register_implementor::<Lane>(system);
     TemporalID::register_implementor::<Lane>(system);
     system.add_handler::<Lane, _, _>(
-        |&MSG_Lane_on_signal_changed(from, new_green), instance, world| {
-            instance.on_signal_changed(from, new_green, world); Fate::Live
+        |&MSG_Lane_on_signal_changed(from, new_green, new_must_stop, new_closed), instance, world| {
+            instance.on_signal_changed(from, new_green, new_must_stop, new_closed, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_on_neighbouring_cars(via, ref cars), instance, world| {
+            instance.on_neighbouring_cars(via, cars, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_on_switch_closed(from, new_closed), instance, world| {
+            instance.on_switch_closed(from, new_closed, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_set_closed(roadworks, closed), instance, world| {
+            instance.set_closed(roadworks, closed, world); Fate::Live
         }, false
     );
     LaneLikeID::register_implementor::<SwitchLane>(system);
     TemporalID::register_implementor::<SwitchLane>(system);
+    system.add_handler::<SwitchLane, _, _>(
+        |&MSG_SwitchLane_set_closed(roadworks, closed), instance, world| {
+            instance.set_closed(roadworks, closed, world); Fate::Live
+        }, false
+    );
 }
\ No newline at end of file
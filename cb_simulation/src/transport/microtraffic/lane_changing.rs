@@ -0,0 +1,79 @@
+use super::{LaneCar, Obstacle, VehicleType};
+use super::intelligent_acceleration::intelligent_acceleration;
+use super::driver_behaviour::DriverBehaviour;
+
+/// How much a car cares about the acceleration its lane change takes away from others
+const POLITENESS: f32 = 0.3;
+/// The new follower may not have to brake harder than this because of a lane change
+const MAX_SAFE_DECELERATION: f32 = 2.0;
+/// Smallest gap a vehicle squeezes into, in front and behind, in m
+const MIN_GAP: f32 = 2.0;
+const SAFE_TIME_HEADWAY: f32 = 2.0;
+/// The total advantage a lane change needs, so cars don't change lanes for nothing
+pub const LANE_CHANGE_THRESHOLD: f32 = 0.05;
+/// Lowers the threshold for changing to the right and raises it for changing to the left
+pub const KEEP_RIGHT_BIAS: f32 = 0.02;
+/// How long a car waits after a lane change it didn't need before considering another one,
+/// so it doesn't weave back and forth between lanes, in s
+pub const LANE_CHANGE_COOLDOWN: f32 = 8.0;
+
+/// How much a lane change would improve the accelerations of the car and the cars
+/// around it, or `None` if it isn't safe. Only the cars around are considered,
+/// not signals or other obstacles. Of the vehicles on the other lane only the lengths
+/// are known, so otherwise they are assumed to be cars with average drivers.
+///
+/// This is MOBIL ("minimizing overall braking induced by lane changes"),
+/// see http://traffic-simulation.de/info/info_MOBIL.html
+pub fn lane_change_advantage(
+    car: &LaneCar,
+    leader: &Obstacle,
+    maybe_follower: Option<&LaneCar>,
+    new_leader: &Obstacle,
+    maybe_new_follower: Option<&Obstacle>,
+    speed_limit: f32,
+) -> Option<f32> {
+    let acceleration = |follower: &Obstacle,
+                        follower_type: VehicleType,
+                        driver: DriverBehaviour,
+                        leader: &Obstacle| {
+        intelligent_acceleration(
+            follower,
+            follower_type,
+            driver,
+            leader,
+            SAFE_TIME_HEADWAY,
+            speed_limit,
+        )
+    };
+    let unknown_driver = DriverBehaviour::default();
+
+    if *new_leader.position - *car.position < car.length + MIN_GAP {
+        return None;
+    }
+
+    let new_follower_gain = if let Some(new_follower) = maybe_new_follower {
+        if *car.position - *new_follower.position < new_follower.length + MIN_GAP {
+            return None;
+        }
+        let new_follower_acceleration =
+            acceleration(new_follower, VehicleType::Car, unknown_driver, car);
+        if new_follower_acceleration < -MAX_SAFE_DECELERATION {
+            return None;
+        }
+        new_follower_acceleration
+            - acceleration(new_follower, VehicleType::Car, unknown_driver, new_leader)
+    } else {
+        0.0
+    };
+
+    let own_gain = acceleration(car, car.vehicle_type, car.driver, new_leader)
+        - acceleration(car, car.vehicle_type, car.driver, leader);
+    let follower_gain = maybe_follower
+        .map(|follower| {
+            acceleration(follower, follower.vehicle_type, follower.driver, leader)
+                - acceleration(follower, follower.vehicle_type, follower.driver, car)
+        })
+        .unwrap_or(0.0);
+
+    Some(own_gain + POLITENESS * (new_follower_gain + follower_gain))
+}
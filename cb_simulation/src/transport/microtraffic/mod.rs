@@ -5,11 +5,22 @@ use std::f32::INFINITY;
 use std::ops::{Deref, DerefMut};
 
 use super::lane::{Lane, LaneID, SwitchLane, SwitchLaneID};
-use super::lane::connectivity::{Interaction};
+use super::lane::connectivity::{Interaction, ConflictPriority};
 use super::pathfinding;
+use super::transport_planning::RightOfWay;
+use super::transport_planning::intersection_control::SignalAspect;
+use super::detectors::{Detector, TrafficMeasurementsID};
+use super::roadworks::RoadworksID;
 
 mod intelligent_acceleration;
 use self::intelligent_acceleration::intelligent_acceleration;
+mod vehicle_type;
+pub use self::vehicle_type::{VehicleType, VehicleParameters};
+mod lane_changing;
+pub mod driver_behaviour;
+use self::driver_behaviour::{DriverBehaviour, Perceptions};
+use self::lane_changing::{lane_change_advantage, LANE_CHANGE_THRESHOLD, KEEP_RIGHT_BIAS,
+LANE_CHANGE_COOLDOWN};
 
 use cb_util::log::debug;
 const LOG_T: &str = "Microtraffic";
@@ -20,34 +31,98 @@ const LOG_T: &str = "Microtraffic";
 pub struct Microtraffic {
     pub obstacles: CVec<(Obstacle, LaneLikeID)>,
     pub cars: CVec<LaneCar>,
-    timings: CVec<bool>,
+    /// Cars on the lanes next to this one, as seen by the switch lanes in between,
+    /// to decide on lane changes
+    pub neighbouring_cars: CVec<(Obstacle, SwitchLaneID)>,
+    timings: CVec<SignalAspect>,
+    pub speed_limit: f32,
     pub green: bool,
     pub yellow_to_green: bool,
     pub yellow_to_red: bool,
+    /// Average speed of the cars on the lane, smoothed over time
+    pub smoothed_speed: f32,
+    /// How many times longer than at free flow it takes to drive along the lane,
+    /// as last published to pathfinding
+    pub congestion: f32,
+    /// Roadworks that temporarily closed the lane. While there are any,
+    /// no more cars enter and pathfinding avoids the lane
+    pub closed_by: CVec<RoadworksID>,
+    pub detectors: CVec<Detector>,
 }
 
 impl Microtraffic {
-    pub fn new(timings: CVec<bool>) -> Self {
+    pub fn new(timings: CVec<SignalAspect>, speed_limit: f32) -> Self {
         Microtraffic {
             obstacles: CVec::new(),
             cars: CVec::new(),
+            neighbouring_cars: CVec::new(),
             timings,
+            speed_limit,
             green: false,
             yellow_to_green: false,
             yellow_to_red: false,
+            smoothed_speed: speed_limit,
+            congestion: 1.0,
+            closed_by: CVec::new(),
+            detectors: CVec::new(),
         }
     }
+
+    pub fn set_timings(&mut self, timings: CVec<SignalAspect>) {
+        self.timings = timings;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        !self.closed_by.is_empty()
+    }
+}
+
+/// Adds or removes one closure, returns whether the lane opened or closed because of it
+fn update_closures(
+    closed_by: &mut CVec<RoadworksID>,
+    roadworks: RoadworksID,
+    closed: bool,
+) -> bool {
+    let was_closed = !closed_by.is_empty();
+    if closed {
+        if !closed_by.contains(&roadworks) {
+            closed_by.push(roadworks);
+        }
+    } else {
+        closed_by.retain(|&other| other != roadworks);
+    }
+    was_closed != !closed_by.is_empty()
 }
 
 // makes "time pass slower" for traffic, so we can still use realistic
 // unit values while traffic happening at a slower pace to be visible
 const MICROTRAFFIC_UNREALISTIC_SLOWDOWN: f32 = 6.0;
 
-#[derive(Compact, Clone, Default)]
+#[derive(Compact, Clone)]
 pub struct TransferringMicrotraffic {
     pub left_obstacles: CVec<Obstacle>,
     pub right_obstacles: CVec<Obstacle>,
     pub cars: CVec<TransferringLaneCar>,
+    pub speed_limit: f32,
+    /// Roadworks that temporarily closed the switch lane,
+    /// while there are any no more cars change lanes through it
+    pub closed_by: CVec<RoadworksID>,
+}
+
+impl TransferringMicrotraffic {
+    pub fn new(speed_limit: f32) -> Self {
+        TransferringMicrotraffic {
+            left_obstacles: CVec::new(),
+            right_obstacles: CVec::new(),
+            cars: CVec::new(),
+            speed_limit,
+            closed_by: CVec::new(),
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        !self.closed_by.is_empty()
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -55,6 +130,9 @@ pub struct Obstacle {
     pub position: OrderedFloat<f32>,
     pub velocity: f32,
     pub max_velocity: f32,
+    /// Length of the vehicle, which extends ahead of `position` (its rear),
+    /// zero for signals and lane ends
+    pub length: f32,
 }
 
 impl Obstacle {
@@ -63,6 +141,7 @@ impl Obstacle {
             position: OrderedFloat(INFINITY),
             velocity: INFINITY,
             max_velocity: INFINITY,
+            length: 0.0,
         }
     }
     fn offset_by(&self, delta: f32) -> Obstacle {
@@ -75,6 +154,7 @@ impl Obstacle {
 
 use super::pathfinding::trip::{TripID, TripResult, TripFate};
 use super::pathfinding::Link;
+use super::transit::DWELL_TIME;
 
 #[derive(Copy, Clone)]
 pub struct LaneCar {
@@ -83,15 +163,74 @@ pub struct LaneCar {
     pub acceleration: f32,
     pub destination: pathfinding::PreciseLocation,
     pub next_hop_interaction: Option<u8>,
+    /// Whether the car already came to a halt at the stop line of its current lane
+    pub has_stopped: bool,
+    /// Distance driven since the car set off, in m
+    pub travelled: f32,
+    pub vehicle_type: VehicleType,
+    /// Set after a lane change that the route required,
+    /// so the car stays in its lane until it reaches the next one
+    pub keeps_lane: bool,
+    /// Time left until the car considers another lane change it doesn't need, in s
+    pub lane_change_cooldown: f32,
+    pub driver: DriverBehaviour,
+    /// What the driver saw but didn't act on yet
+    pub perceptions: Perceptions,
+    /// Set once a bus stopped at its destination, to stay there while passengers board
+    pub dwelling_until: Option<Instant>,
 }
 
 impl LaneCar {
+    /// The driver sees what they should be doing now, but only acts on it
+    /// after their reaction delay. Emergency braking is a reflex and happens at once
+    fn perceive(&mut self, acceleration: f32) {
+        let emergency = acceleration <= -self.vehicle_type.parameters().max_deceleration;
+        if self.driver.reaction_delay <= 0.0 || emergency {
+            self.acceleration = acceleration;
+            self.perceptions.reset_to(acceleration);
+        } else {
+            self.perceptions.remember(acceleration, self.driver.reaction_delay);
+        }
+    }
+
+    fn react(&mut self, dt: f32) {
+        if let Some(acceleration) = self.perceptions.recall(dt, self.driver.reaction_delay) {
+            self.acceleration = acceleration;
+        }
+    }
+
     fn offset_by(&self, delta: f32) -> LaneCar {
         LaneCar {
             as_obstacle: self.as_obstacle.offset_by(delta),
             ..*self
         }
     }
+
+    fn reached_destination(&self, lane_location: pathfinding::Location) -> bool {
+        self.destination.location == lane_location && *self.position >= self.destination.offset
+    }
+
+    /// Tells the trip that the car arrived. Buses stay on the lane as a stopped car
+    /// while passengers get on and off, all other cars leave it
+    fn arrive(&self, lane: LaneID, instant: Instant, world: &mut World) -> Option<LaneCar> {
+        // the trip only ends once the car found a parking spot
+        self.trip.arrive(lane, *self.position, self.travelled, instant, world);
+
+        if self.vehicle_type == VehicleType::Bus {
+            Some(LaneCar {
+                as_obstacle: Obstacle {
+                    velocity: 0.0,
+                    ..self.as_obstacle
+                },
+                acceleration: 0.0,
+                perceptions: Perceptions::default(),
+                dwelling_until: Some(instant + Duration::from_seconds(DWELL_TIME)),
+                ..*self
+            })
+        } else {
+            None
+        }
+    }
 }
 
 impl Deref for LaneCar {
@@ -131,7 +270,7 @@ impl DerefMut for TransferringLaneCar {
     }
 }
 
-use cb_time::units::Instant;
+use cb_time::units::{Instant, Duration, TICKS_PER_SIM_SECOND};
 
 pub trait LaneLike {
     fn add_car(
@@ -149,7 +288,22 @@ use self::pathfinding::StoredRoutingEntry;
 use cb_time::actors::{Temporal, TemporalID};
 
 const TRAFFIC_LOGIC_THROTTLING: usize = 10;
+/// Cars slower than this count as halted at a stop line
+const STOPPED_VELOCITY: f32 = 0.5;
+/// Cars wait this far before the end of a lane when they have to stop there
+const STOP_LINE_DISTANCE: f32 = 10.0;
 const PATHFINDING_THROTTLING: usize = 10;
+/// How far the smoothed lane speed moves towards the current one per traffic logic update
+const SPEED_SMOOTHING: f32 = 0.05;
+const MAX_CONGESTION: f32 = 5.0;
+/// Congestion is only published again once it changed by this fraction,
+/// so routes don't react to every small fluctuation
+const CONGESTION_PUBLISH_THRESHOLD: f32 = 0.2;
+/// Cars only start a lane change they don't need if the switch lane
+/// continues at least this far ahead of them, in m
+const MIN_LANE_CHANGE_LENGTH: f32 = 20.0;
+/// Near the end of a lane cars stay in the lane their route needs there instead of overtaking
+const LANE_CHOICE_DISTANCE: f32 = 100.0;
 
 impl LaneLike for Lane {
     fn add_car(
@@ -159,46 +313,30 @@ impl LaneLike for Lane {
         instant: Instant,
         world: &mut World,
     ) {
-        if let Some(self_as_location) = self.pathfinding.location {
-            if car.destination.location == self_as_location
-                && *car.position >= car.destination.offset
-            {
-                car.trip.finish(
-                    TripResult {
-                        location_now: None,
-                        fate: TripFate::Success(instant),
-                    },
-                    world,
-                );
-
-                return;
-            }
-        }
-
         let (maybe_next_hop_interaction, almost_there) =
             if Some(car.destination.location) == self.pathfinding.location {
                 (None, true)
             } else {
-                let maybe_hop = self
-                    .pathfinding
-                    .routes
-                    .get(car.destination.location)
-                    .or_else(|| {
-                        self.pathfinding
-                            .routes
-                            .get(car.destination.landmark_destination())
-                    })
-                    .map(|&StoredRoutingEntry { outgoing_idx, .. }| outgoing_idx as usize);
-
-                (maybe_hop, false)
+                (self.next_hop_interaction(car.destination), false)
             };
 
         if maybe_next_hop_interaction.is_some() || almost_there {
             let routed_car = LaneCar {
                 next_hop_interaction: maybe_next_hop_interaction.map(|hop| hop as u8),
+                has_stopped: false,
                 ..car
             };
 
+            let routed_car = match self.pathfinding.location {
+                Some(self_as_location) if car.reached_destination(self_as_location) => {
+                    match routed_car.arrive(self.id, instant, world) {
+                        Some(dwelling_car) => dwelling_car,
+                        None => return,
+                    }
+                }
+                _ => routed_car,
+            };
+
             // TODO: optimize using BinaryHeap?
             let maybe_next_car_position =
                 self.microtraffic.cars.iter().position(|other_car| {
@@ -215,6 +353,7 @@ impl LaneLike for Lane {
                 TripResult {
                     location_now: Some(self.id_as()),
                     fate: TripFate::NoRoute,
+                    last_lane: Some(self.id_as()),
                 },
                 world,
             );
@@ -232,17 +371,265 @@ impl LaneLike for Lane {
 }
 
 impl Lane {
-    pub fn on_signal_changed(&mut self, from: LaneID, new_green: bool, _: &mut World) {
+    pub fn on_signal_changed(
+        &mut self,
+        from: LaneID,
+        new_green: bool,
+        new_must_stop: bool,
+        new_closed: bool,
+        _: &mut World,
+    ) {
         for interaction in self.connectivity.interactions.iter_mut() {
             match *interaction {
                 Interaction::Next {
                     next,
                     ref mut green,
-                } if next == from => *green = new_green,
+                    ref mut must_stop,
+                    ref mut closed,
+                } if next == from => {
+                    *green = new_green;
+                    *must_stop = new_must_stop;
+                    *closed = new_closed;
+                }
                 _ => {}
             }
         }
     }
+
+    pub fn on_neighbouring_cars(
+        &mut self,
+        via: SwitchLaneID,
+        cars: &CVec<Obstacle>,
+        _: &mut World,
+    ) {
+        self.microtraffic
+            .neighbouring_cars
+            .retain(|&(_, seen_via)| seen_via != via);
+        self.microtraffic
+            .neighbouring_cars
+            .extend(cars.iter().map(|car| (*car, via)));
+    }
+
+    pub fn on_switch_closed(&mut self, from: SwitchLaneID, new_closed: bool, _: &mut World) {
+        for interaction in self.connectivity.interactions.iter_mut() {
+            match *interaction {
+                Interaction::Switch {
+                    via,
+                    ref mut closed,
+                    ..
+                } if via == from => {
+                    *closed = new_closed;
+                }
+                _ => {}
+            }
+        }
+
+        // switching lanes got a lot cheaper or more expensive
+        self.pathfinding.routes_changed = true;
+        self.pathfinding.query_routes_next_tick = true;
+    }
+
+    pub fn set_closed(&mut self, roadworks: RoadworksID, closed: bool, world: &mut World) {
+        if !update_closures(&mut self.microtraffic.closed_by, roadworks, closed) {
+            return;
+        }
+
+        // routes through this lane got a lot cheaper or more expensive, for predecessors
+        // and for this lane itself, which needs to learn the new costs of its own routes
+        self.pathfinding.routes_changed = true;
+        self.pathfinding.query_routes_next_tick = true;
+        self.signal_previous_lanes(world);
+    }
+
+    fn signal_previous_lanes(&self, world: &mut World) {
+        let must_stop = self.connectivity.right_of_way == RightOfWay::Stop;
+        for interaction in &self.connectivity.interactions {
+            if let Interaction::Previous { previous, .. } = *interaction {
+                previous.on_signal_changed(
+                    self.id,
+                    self.microtraffic.green,
+                    must_stop,
+                    self.microtraffic.is_closed(),
+                    world,
+                );
+            }
+        }
+    }
+
+    fn next_hop_interaction(&self, destination: pathfinding::PreciseLocation) -> Option<usize> {
+        self.pathfinding
+            .routes
+            .get(destination.location)
+            .or_else(|| {
+                self.pathfinding
+                    .routes
+                    .get(destination.landmark_destination())
+            })
+            .map(|&StoredRoutingEntry { outgoing_idx, .. }| outgoing_idx as usize)
+    }
+
+    fn hop_is_closed(&self, maybe_hop_interaction: Option<u8>) -> bool {
+        match maybe_hop_interaction.map(|hop| self.connectivity.interactions[hop as usize]) {
+            Some(Interaction::Next { closed, .. }) | Some(Interaction::Switch { closed, .. }) => {
+                closed
+            }
+            _ => false,
+        }
+    }
+
+    /// Cars that don't need to be on a particular lane move to a neighbouring one
+    /// if they can drive faster there, without making others brake too much.
+    /// Returns the index of each such car, with the switch lane to use and where it starts
+    fn discretionary_lane_changes(&self) -> Vec<(usize, SwitchLaneID, f32)> {
+        let cars = &self.microtraffic.cars;
+        let speed_limit = self.microtraffic.speed_limit;
+        let mut changes: Vec<(usize, SwitchLaneID, f32)> = Vec::new();
+
+        for (c, car) in cars.iter().enumerate() {
+            let free_to_change = !car.keeps_lane
+                && car.lane_change_cooldown <= 0.0
+                && match car
+                    .next_hop_interaction
+                    .map(|hop| self.connectivity.interactions[hop as usize])
+                {
+                    Some(Interaction::Next { .. }) => true,
+                    _ => false,
+                };
+            // a car and the one right behind it changing at once could crash into each other
+            let follower_changes = changes.last().map(|&(other_c, ..)| other_c + 1 == c);
+            if !free_to_change || follower_changes.unwrap_or(false) {
+                continue;
+            }
+
+            let leader = cars
+                .get(c + 1)
+                .map_or(Obstacle::far_ahead(), |leader| leader.as_obstacle);
+            let maybe_follower = c.checked_sub(1).map(|f| &cars[f]);
+
+            let best_change = self
+                .connectivity
+                .interactions
+                .iter()
+                .filter_map(|interaction| match *interaction {
+                    Interaction::Switch {
+                        via,
+                        start,
+                        end,
+                        is_left,
+                        closed: false,
+                        ..
+                    } if *car.position > start
+                        && *car.position < end - MIN_LANE_CHANGE_LENGTH
+                        && *car.position < self.construction.length - LANE_CHOICE_DISTANCE =>
+                    {
+                        let mut maybe_new_follower = None;
+                        let mut maybe_new_leader = None;
+                        for &(neighbour, seen_via) in self.microtraffic.neighbouring_cars.iter() {
+                            if seen_via == via {
+                                if *neighbour.position < *car.position {
+                                    maybe_new_follower = Some(neighbour);
+                                } else if maybe_new_leader.is_none() {
+                                    maybe_new_leader = Some(neighbour);
+                                }
+                            }
+                        }
+
+                        let bias = if is_left {
+                            -KEEP_RIGHT_BIAS
+                        } else {
+                            KEEP_RIGHT_BIAS
+                        };
+
+                        lane_change_advantage(
+                            car,
+                            &leader,
+                            maybe_follower,
+                            &maybe_new_leader.unwrap_or_else(Obstacle::far_ahead),
+                            maybe_new_follower.as_ref(),
+                            speed_limit,
+                        )
+                        .map(|advantage| (via, start, advantage + bias))
+                    }
+                    _ => None,
+                })
+                .filter(|&(_, _, advantage)| advantage > LANE_CHANGE_THRESHOLD)
+                .max_by_key(|&(_, _, advantage)| OrderedFloat(advantage));
+
+            if let Some((via, start, _)) = best_change {
+                changes.push((c, via, start));
+            }
+        }
+
+        changes
+    }
+
+    /// Cars that were going to stay in their lane head for another one early as soon as
+    /// their route needs it, for example one with the arrow for the turn they take next
+    fn pick_lanes_early(&mut self) {
+        for c in 0..self.microtraffic.cars.len() {
+            let car = self.microtraffic.cars[c];
+            let stays_in_lane = match car
+                .next_hop_interaction
+                .map(|hop| self.connectivity.interactions[hop as usize])
+            {
+                Some(Interaction::Next { .. }) => !car.keeps_lane,
+                _ => false,
+            };
+
+            if stays_in_lane {
+                if let Some(hop) = self.next_hop_interaction(car.destination) {
+                    if let Interaction::Switch { .. } = self.connectivity.interactions[hop] {
+                        self.microtraffic.cars[c].next_hop_interaction = Some(hop as u8);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cars that were about to drive into a closed lane take the best route
+    /// that is known now, which avoids the closure once pathfinding caught up
+    fn reroute_around_closures(&mut self) {
+        for c in 0..self.microtraffic.cars.len() {
+            let car = self.microtraffic.cars[c];
+            if self.hop_is_closed(car.next_hop_interaction) {
+                if let Some(hop) = self.next_hop_interaction(car.destination) {
+                    self.microtraffic.cars[c].next_hop_interaction = Some(hop as u8);
+                }
+            }
+        }
+    }
+}
+
+impl Lane {
+    fn update_congestion(&mut self) {
+        // buses waiting at a stop aren't stuck in traffic
+        let (speed_sum, n_moving) = self
+            .microtraffic
+            .cars
+            .iter()
+            .filter(|car| car.dwelling_until.is_none())
+            .fold((0.0, 0), |(sum, n), car| (sum + car.velocity, n + 1));
+        let current_speed = if n_moving == 0 {
+            self.microtraffic.speed_limit
+        } else {
+            speed_sum / n_moving as f32
+        };
+        self.microtraffic.smoothed_speed +=
+            SPEED_SMOOTHING * (current_speed - self.microtraffic.smoothed_speed);
+
+        let congestion = (self.microtraffic.speed_limit
+            / self.microtraffic.smoothed_speed.max(STOPPED_VELOCITY))
+        .max(1.0)
+        .min(MAX_CONGESTION);
+
+        if (congestion - self.microtraffic.congestion).abs()
+            > CONGESTION_PUBLISH_THRESHOLD * self.microtraffic.congestion
+        {
+            self.microtraffic.congestion = congestion;
+            // routes through this lane got cheaper or more expensive for predecessors
+            self.pathfinding.costs_changed = true;
+        }
+    }
 }
 
 impl Temporal for Lane {
@@ -255,32 +642,24 @@ impl Temporal for Lane {
             == self.id.as_raw().instance_id as usize % TRAFFIC_LOGIC_THROTTLING;
 
         let old_green = self.microtraffic.green;
-        self.microtraffic.yellow_to_red = if self.microtraffic.timings.is_empty() {
-            true
+        let (aspect, next_aspect) = if self.microtraffic.timings.is_empty() {
+            (SignalAspect::Green, SignalAspect::Green)
         } else {
-            !self.microtraffic.timings
-                [((current_instant.ticks() + 100) / 30) % self.microtraffic.timings.len()]
-        };
-        self.microtraffic.yellow_to_green = if self.microtraffic.timings.is_empty() {
-            true
-        } else {
-            self.microtraffic.timings
-                [((current_instant.ticks() + 100) / 30) % self.microtraffic.timings.len()]
-        };
-        self.microtraffic.green = if self.microtraffic.timings.is_empty() {
-            true
-        } else {
-            self.microtraffic.timings
-                [(current_instant.ticks() / 30) % self.microtraffic.timings.len()]
+            let second = current_instant.ticks() / TICKS_PER_SIM_SECOND as usize;
+            let n_seconds = self.microtraffic.timings.len();
+            (
+                self.microtraffic.timings[second % n_seconds],
+                self.microtraffic.timings[(second + 1) % n_seconds],
+            )
         };
+        self.microtraffic.yellow_to_red = aspect == SignalAspect::Yellow;
+        self.microtraffic.yellow_to_green =
+            aspect == SignalAspect::Red && next_aspect == SignalAspect::Green;
+        self.microtraffic.green = aspect != SignalAspect::Red;
 
         // TODO: this is just a hacky way to update new lanes about existing lane's green
         if old_green != self.microtraffic.green || do_traffic {
-            for interaction in &self.connectivity.interactions {
-                if let Interaction::Previous { previous, .. } = *interaction {
-                    previous.on_signal_changed(self.id, self.microtraffic.green, world);
-                }
-            }
+            self.signal_previous_lanes(world);
         }
 
         if current_instant.ticks() % PATHFINDING_THROTTLING
@@ -290,11 +669,18 @@ impl Temporal for Lane {
         }
 
         if do_traffic {
+            self.reroute_around_closures();
+            self.pick_lanes_early();
+
             // TODO: optimize using BinaryHeap?
             self.microtraffic
                 .obstacles
                 .sort_by_key(|&(ref obstacle, _id)| obstacle.position);
 
+            self.microtraffic
+                .neighbouring_cars
+                .sort_by_key(|&(ref neighbour, _via)| neighbour.position);
+
             let mut obstacles = self
                 .microtraffic
                 .obstacles
@@ -308,8 +694,21 @@ impl Temporal for Lane {
                     .cars
                     .get(c + 1)
                     .map_or(Obstacle::far_ahead(), |car| car.as_obstacle);
+                let speed_limit = self.microtraffic.speed_limit;
                 let car = &mut self.microtraffic.cars[c];
-                let next_car_acceleration = intelligent_acceleration(car, &next_obstacle, 2.0);
+                if car.dwelling_until.is_some() {
+                    continue;
+                }
+                let vehicle_type = car.vehicle_type;
+                let driver = car.driver;
+                let next_car_acceleration = intelligent_acceleration(
+                    car,
+                    vehicle_type,
+                    driver,
+                    &next_obstacle,
+                    2.0,
+                    speed_limit,
+                );
 
                 maybe_next_obstacle = maybe_next_obstacle.and_then(|obstacle| {
                     let mut following_obstacle = Some(obstacle);
@@ -322,35 +721,105 @@ impl Temporal for Lane {
                 });
 
                 let next_obstacle_acceleration = if let Some(next_obstacle) = maybe_next_obstacle {
-                    intelligent_acceleration(car, next_obstacle, 3.0)
+                    intelligent_acceleration(
+                        car,
+                        vehicle_type,
+                        driver,
+                        next_obstacle,
+                        3.0,
+                        speed_limit,
+                    )
                 } else {
                     INFINITY
                 };
 
-                car.acceleration = next_car_acceleration.min(next_obstacle_acceleration);
+                let mut acceleration = next_car_acceleration.min(next_obstacle_acceleration);
 
                 if let Some(next_hop_interaction) = car.next_hop_interaction {
-                    if let Interaction::Next { green, .. } =
-                        self.connectivity.interactions[next_hop_interaction as usize]
-                    {
-                        if !green {
-                            car.acceleration = car.acceleration.min(intelligent_acceleration(
+                    match self.connectivity.interactions[next_hop_interaction as usize] {
+                        Interaction::Next {
+                            green,
+                            must_stop,
+                            closed,
+                            ..
+                        } => {
+                            if must_stop
+                                && car.velocity < STOPPED_VELOCITY
+                                && *car.position > self.construction.length - STOP_LINE_DISTANCE
+                            {
+                                car.has_stopped = true;
+                            }
+
+                            if !green || closed || (must_stop && !car.has_stopped) {
+                                acceleration = acceleration.min(intelligent_acceleration(
+                                    car,
+                                    vehicle_type,
+                                    driver,
+                                    &Obstacle {
+                                        position: OrderedFloat(self.construction.length + 2.0),
+                                        velocity: 0.0,
+                                        max_velocity: 0.0,
+                                        length: 0.0,
+                                    },
+                                    2.0,
+                                    speed_limit,
+                                ))
+                            }
+                        }
+                        Interaction::Switch {
+                            end, closed: true, ..
+                        } => {
+                            // wait where the lane change would have to happen at the latest
+                            acceleration = acceleration.min(intelligent_acceleration(
                                 car,
+                                vehicle_type,
+                                driver,
                                 &Obstacle {
-                                    position: OrderedFloat(self.construction.length + 2.0),
+                                    position: OrderedFloat(end),
                                     velocity: 0.0,
                                     max_velocity: 0.0,
+                                    length: 0.0,
                                 },
                                 2.0,
+                                speed_limit,
                             ))
                         }
+                        _ => {}
                     }
                 }
+
+                car.perceive(acceleration);
+            }
+
+            self.update_congestion();
+
+            for &(c, via, start) in self.discretionary_lane_changes().iter().rev() {
+                let car = self.microtraffic.cars.remove(c);
+                let via_as_lane: LaneLikeID = via.into();
+                via_as_lane.add_car(
+                    LaneCar {
+                        lane_change_cooldown: LANE_CHANGE_COOLDOWN,
+                        ..car.offset_by(-start)
+                    },
+                    Some(self.id_as()),
+                    current_instant,
+                    world,
+                );
+            }
+        }
+
+        for detector in self.microtraffic.detectors.iter_mut() {
+            detector.measure(&self.microtraffic.cars, dt);
+            if let Some(measurement) = detector.finish_interval(self.id, current_instant) {
+                TrafficMeasurementsID::global_first(world).record(measurement, world);
             }
         }
 
         for car in &mut self.microtraffic.cars {
+            car.react(dt);
+            car.lane_change_cooldown = (car.lane_change_cooldown - dt).max(0.0);
             *car.position += dt * car.velocity;
+            car.travelled += dt * car.velocity;
             car.velocity = (car.velocity + dt * car.acceleration)
                 .min(car.max_velocity)
                 .max(0.0);
@@ -360,6 +829,10 @@ impl Temporal for Lane {
             *obstacle.position += dt * obstacle.velocity;
         }
 
+        for &mut (ref mut neighbour, _via) in &mut self.microtraffic.neighbouring_cars {
+            *neighbour.position += dt * neighbour.velocity;
+        }
+
         if self.microtraffic.cars.len() > 1 {
             for i in (0..self.microtraffic.cars.len() - 1).rev() {
                 self.microtraffic.cars[i].position = OrderedFloat(
@@ -372,27 +845,32 @@ impl Temporal for Lane {
         // TODO: move all iteration, updates, etc into one huge retain loop
 
         if let Some(self_as_location) = self.pathfinding.location {
-            self.microtraffic.cars.retain(|car| {
-                if car.destination.location == self_as_location
-                    && *car.position >= car.destination.offset
-                {
-                    car.trip.finish(
-                        TripResult {
-                            location_now: None,
-                            fate: TripFate::Success(current_instant),
-                        },
-                        world,
-                    );
-
-                    false
-                } else {
-                    true
-                }
-            });
+            let self_id = self.id;
+            self.microtraffic.cars = self
+                .microtraffic
+                .cars
+                .iter()
+                .filter_map(|car| match car.dwelling_until {
+                    Some(dwelling_until) => {
+                        if current_instant < dwelling_until {
+                            Some(*car)
+                        } else {
+                            None
+                        }
+                    }
+                    None => {
+                        if car.reached_destination(self_as_location) {
+                            car.arrive(self_id, current_instant, world)
+                        } else {
+                            Some(*car)
+                        }
+                    }
+                })
+                .collect();
         }
 
         loop {
-            let maybe_switch_car: Option<(usize, LaneLikeID, f32)> = self
+            let maybe_switch_car: Option<(usize, LaneLikeID, f32, bool)> = self
                 .microtraffic
                 .cars
                 .iter()
@@ -405,17 +883,21 @@ impl Temporal for Lane {
 
                     match interaction {
                         Some(Interaction::Switch {
-                            start, end, via, ..
+                            start,
+                            end,
+                            via,
+                            closed,
+                            ..
                         }) => {
-                            if *car.position > start && *car.position > end - 300.0 {
-                                Some((i, via.into(), start))
+                            if !closed && *car.position > start && *car.position > end - 300.0 {
+                                Some((i, via.into(), start, true))
                             } else {
                                 None
                             }
                         }
                         Some(Interaction::Next { next, .. }) => {
                             if *car.position > self.construction.length {
-                                Some((i, next.into(), self.construction.length))
+                                Some((i, next.into(), self.construction.length, false))
                             } else {
                                 None
                             }
@@ -428,10 +910,13 @@ impl Temporal for Lane {
                 })
                 .next();
 
-            if let Some((idx_to_remove, next_lane, start)) = maybe_switch_car {
+            if let Some((idx_to_remove, next_lane, start, is_lane_change)) = maybe_switch_car {
                 let car = self.microtraffic.cars.remove(idx_to_remove);
                 next_lane.add_car(
-                    car.offset_by(-start),
+                    LaneCar {
+                        keeps_lane: is_lane_change,
+                        ..car.offset_by(-start)
+                    },
                     Some(self.id_as()),
                     current_instant,
                     world,
@@ -465,6 +950,18 @@ impl Temporal for Lane {
     }
 }
 
+impl SwitchLane {
+    pub fn set_closed(&mut self, roadworks: RoadworksID, closed: bool, world: &mut World) {
+        if !update_closures(&mut self.microtraffic.closed_by, roadworks, closed) {
+            return;
+        }
+
+        for &(lane, _, _) in self.connectivity.left.iter().chain(self.connectivity.right.iter()) {
+            lane.on_switch_closed(self.id, self.microtraffic.is_closed(), world);
+        }
+    }
+}
+
 impl LaneLike for SwitchLane {
     fn add_car(
         &mut self,
@@ -587,7 +1084,14 @@ impl Temporal for SwitchLane {
                                 dangerous = true;
                                 None
                             } else {
-                                Some(OrderedFloat(intelligent_acceleration(car, obstacle, 1.0)))
+                                Some(OrderedFloat(intelligent_acceleration(
+                                    car,
+                                    car.vehicle_type,
+                                    car.driver,
+                                    obstacle,
+                                    1.0,
+                                    self.microtraffic.speed_limit,
+                                )))
                             }
                         })
                         .min()
@@ -604,7 +1108,7 @@ impl Temporal for SwitchLane {
                 };
 
                 let car = &mut self.microtraffic.cars[c];
-                car.acceleration = acceleration;
+                car.perceive(acceleration);
 
                 if dangerous && !car.cancelling {
                     car.switch_acceleration = -car.switch_acceleration;
@@ -614,7 +1118,10 @@ impl Temporal for SwitchLane {
         }
 
         for car in &mut self.microtraffic.cars {
+            car.react(dt);
+            car.lane_change_cooldown = (car.lane_change_cooldown - dt).max(0.0);
             *car.position += dt * car.velocity;
+            car.travelled += dt * car.velocity;
             car.velocity = (car.velocity + dt * car.acceleration)
                 .min(car.max_velocity)
                 .max(0.0);
@@ -710,6 +1217,19 @@ impl Temporal for SwitchLane {
                     .collect();
                 let left_as_lane: LaneLikeID = left.into();
                 left_as_lane.add_obstacles(obstacles, self.id_as(), world);
+
+                let cars_right_of_left = self
+                    .microtraffic
+                    .right_obstacles
+                    .iter()
+                    .chain(self.microtraffic.cars.iter().map(|car| &car.as_obstacle))
+                    .map(|obstacle| {
+                        obstacle.offset_by(
+                            left_start + self.self_to_interaction_offset(*obstacle.position, true),
+                        )
+                    })
+                    .collect();
+                left.on_neighbouring_cars(self.id, cars_right_of_left, world);
             }
 
             if (current_instant.ticks() + 1) % TRAFFIC_LOGIC_THROTTLING
@@ -731,6 +1251,20 @@ impl Temporal for SwitchLane {
                     .collect();
                 let right_as_lane: LaneLikeID = right.into();
                 right_as_lane.add_obstacles(obstacles, self.id_as(), world);
+
+                let cars_left_of_right = self
+                    .microtraffic
+                    .left_obstacles
+                    .iter()
+                    .chain(self.microtraffic.cars.iter().map(|car| &car.as_obstacle))
+                    .map(|obstacle| {
+                        obstacle.offset_by(
+                            right_start
+                                + self.self_to_interaction_offset(*obstacle.position, false),
+                        )
+                    })
+                    .collect();
+                right.on_neighbouring_cars(self.id, cars_left_of_right, world);
             }
         }
     }
@@ -738,6 +1272,11 @@ impl Temporal for SwitchLane {
 
 pub fn setup(system: &mut ActorSystem) {
     auto_setup(system);
+    driver_behaviour::setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    driver_behaviour::spawn(world);
 }
 
 fn obstacles_for_interaction(
@@ -746,14 +1285,20 @@ fn obstacles_for_interaction(
     self_obstacles_iter: ::std::slice::Iter<(Obstacle, LaneLikeID)>,
 ) -> Option<CVec<Obstacle>> {
     match *interaction {
+        Interaction::Conflicting {
+            priority: ConflictPriority::Partner,
+            ..
+        } => Some(CVec::new()),
         Interaction::Conflicting {
             start,
             conflicting_start,
             end,
             can_weave,
+            priority,
             ..
         } => {
-            if can_weave {
+            // a yielding partner waits in front of the overlap instead of weaving in
+            if can_weave && priority == ConflictPriority::Shared {
                 Some(
                     cars.skip_while(|car: &&LaneCar| *car.position + 2.0 * car.velocity < start)
                         .take_while(|car: &&LaneCar| *car.position < end)
@@ -770,6 +1315,7 @@ fn obstacles_for_interaction(
                             position: OrderedFloat(conflicting_start),
                             velocity: 0.0,
                             max_velocity: 0.0,
+                            length: 0.0,
                         }]
                         .into(),
                     )
@@ -0,0 +1,84 @@
+/// What kind of vehicle drives on a lane, which determines how it accelerates and brakes
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum VehicleType {
+    Car,
+    Truck,
+    Bus,
+    Bicycle,
+}
+
+impl Default for VehicleType {
+    fn default() -> VehicleType {
+        VehicleType::Car
+    }
+}
+
+/// Parameters of the intelligent driver model, see
+/// http://en.wikipedia.org/wiki/Intelligent_driver_model
+#[derive(Copy, Clone, Debug)]
+pub struct VehicleParameters {
+    /// In m
+    pub length: f32,
+    /// In m/s²
+    pub acceleration: f32,
+    /// In m/s²
+    pub comfortable_deceleration: f32,
+    /// In m/s²
+    pub max_deceleration: f32,
+    /// Smallest gap kept to the vehicle in front, even when standing, in m
+    pub minimum_spacing: f32,
+    /// In m/s
+    pub max_velocity: f32,
+}
+
+impl VehicleType {
+    pub fn parameters(self) -> VehicleParameters {
+        match self {
+            VehicleType::Car => VehicleParameters {
+                length: 4.0,
+                acceleration: 0.4,
+                comfortable_deceleration: 0.4,
+                max_deceleration: 5.0,
+                minimum_spacing: 4.0,
+                max_velocity: 30.0,
+            },
+            VehicleType::Truck => VehicleParameters {
+                length: 10.0,
+                acceleration: 0.2,
+                comfortable_deceleration: 0.3,
+                max_deceleration: 3.5,
+                minimum_spacing: 5.0,
+                max_velocity: 22.0,
+            },
+            VehicleType::Bus => VehicleParameters {
+                length: 12.0,
+                acceleration: 0.25,
+                comfortable_deceleration: 0.3,
+                max_deceleration: 4.0,
+                minimum_spacing: 5.0,
+                max_velocity: 22.0,
+            },
+            VehicleType::Bicycle => VehicleParameters {
+                length: 1.8,
+                acceleration: 0.3,
+                comfortable_deceleration: 0.5,
+                max_deceleration: 3.0,
+                minimum_spacing: 2.0,
+                max_velocity: 6.0,
+            },
+        }
+    }
+
+    /// Of all kinds of vehicles, in m
+    pub fn max_length() -> f32 {
+        [
+            VehicleType::Car,
+            VehicleType::Truck,
+            VehicleType::Bus,
+            VehicleType::Bicycle,
+        ]
+            .iter()
+            .map(|vehicle_type| vehicle_type.parameters().length)
+            .fold(0.0, f32::max)
+    }
+}
@@ -1,6 +1,12 @@
 pub mod lane;
 pub mod construction;
 pub mod microtraffic;
+pub mod signal_control;
+pub mod parking;
+pub mod pedestrians;
+pub mod transit;
+pub mod roadworks;
+pub mod detectors;
 pub mod ui;
 
 pub mod transport_planning;
@@ -13,10 +19,20 @@ pub fn setup(system: &mut ActorSystem) {
     self::lane::setup(system);
     self::construction::setup(system);
     self::microtraffic::setup(system);
+    self::signal_control::setup(system);
+    self::parking::setup(system);
+    self::pedestrians::setup(system);
+    self::transit::setup(system);
+    self::roadworks::setup(system);
+    self::detectors::setup(system);
     self::pathfinding::setup(system);
     self::ui::setup(system);
 }
 
 pub fn spawn(world: &mut World, time: TimeID) {
     self::pathfinding::spawn(world, time);
+    self::microtraffic::spawn(world);
+    self::detectors::spawn(world);
+    self::pedestrians::spawn(world);
+    self::transit::spawn(world);
 }
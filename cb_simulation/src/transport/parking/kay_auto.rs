@@ -0,0 +1,54 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+
+
+impl LaneID {
+    pub fn search_street_parking(self, building: BuildingID, trip: TripID, hops_left: u8, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_search_street_parking(building, trip, hops_left, instant));
+    }
+
+    pub fn unpark_car(self, building: BuildingID, trip: TripID, rough_source: RoughLocationID, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_unpark_car(building, trip, rough_source, instant));
+    }
+    
+    pub fn park_waiting_cars(self, building: BuildingID, n_cars: u16, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_park_waiting_cars(building, n_cars));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_search_street_parking(pub BuildingID, pub TripID, pub u8, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_unpark_car(pub BuildingID, pub TripID, pub RoughLocationID, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_park_waiting_cars(pub BuildingID, pub u16);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_search_street_parking(building, trip, hops_left, instant), instance, world| {
+            instance.search_street_parking(building, trip, hops_left, instant, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_unpark_car(building, trip, rough_source, instant), instance, world| {
+            instance.unpark_car(building, trip, rough_source, instant, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_park_waiting_cars(building, n_cars), instance, world| {
+            instance.park_waiting_cars(building, n_cars, world); Fate::Live
+        }, false
+    );
+}
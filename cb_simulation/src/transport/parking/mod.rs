@@ -0,0 +1,189 @@
+//! Where cars stay between trips: on-street parking along lanes and parking
+//! on the lots of buildings. Trips end by occupying a spot and the next trip
+//! starting from the same building takes one of the cars parked for it.
+
+use kay::{ActorSystem, World};
+use compact::CVec;
+use cb_time::units::Instant;
+use cb_util::random::{seed, Rng};
+
+use super::lane::{Lane, LaneID};
+use super::lane::connectivity::Interaction;
+use super::pathfinding::{PreciseLocation, RoughLocationID, LocationRequesterID};
+use super::pathfinding::trip::TripID;
+use land_use::buildings::BuildingID;
+
+/// How many lanes a car drives on at most looking for a free on-street spot
+pub const MAX_PARKING_SEARCH_HOPS: u8 = 8;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParkingSpot {
+    /// On the lot of the building the trip went to
+    Lot,
+    OnStreet(LaneID),
+    /// No free spot was found nearby, the car was left where its trip ended
+    Unparked,
+}
+
+#[derive(Compact, Clone, Default)]
+pub struct StreetParking {
+    pub capacity: u16,
+    /// The building each parked car belongs to, one entry per occupied spot
+    pub parked_for: CVec<BuildingID>,
+}
+
+impl StreetParking {
+    pub fn new(capacity: u16) -> Self {
+        StreetParking {
+            capacity,
+            parked_for: CVec::new(),
+        }
+    }
+
+    pub fn n_free(&self) -> u16 {
+        self.capacity.saturating_sub(self.parked_for.len() as u16)
+    }
+
+    fn unpark(&mut self, building: BuildingID) {
+        if let Some(idx) = self.parked_for.iter().position(|&other| other == building) {
+            self.parked_for.remove(idx);
+        }
+    }
+}
+
+#[derive(Compact, Clone, Default)]
+pub struct LotParking {
+    pub capacity: u16,
+    pub occupied: u16,
+    /// Lanes where cars of trips to this building parked because the lot was full
+    pub cars_on_street: CVec<LaneID>,
+    /// Cars that found no spot or whose street spot went away, left right at the building
+    pub cars_waiting: u16,
+}
+
+impl LotParking {
+    /// Buildings start out with their lot full of the cars of the people there
+    pub fn new(capacity: u16) -> Self {
+        LotParking {
+            capacity,
+            occupied: capacity,
+            cars_on_street: CVec::new(),
+            cars_waiting: 0,
+        }
+    }
+
+    pub fn try_occupy(&mut self) -> bool {
+        if self.occupied < self.capacity {
+            self.occupied += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes a car parked on the lot, one waiting at the building or else one parked
+    /// on the street. If no car is parked for the building at all, there is none to take
+    pub fn take_car(&mut self) -> Option<ParkingSpot> {
+        if self.occupied > 0 {
+            self.occupied -= 1;
+            Some(ParkingSpot::Lot)
+        } else if self.cars_waiting > 0 {
+            self.cars_waiting -= 1;
+            Some(ParkingSpot::Unparked)
+        } else {
+            self.cars_on_street.pop().map(ParkingSpot::OnStreet)
+        }
+    }
+
+    /// The car parked on the given lane has to leave it and waits at the building instead
+    pub fn displace_car(&mut self, lane: LaneID) {
+        if let Some(idx) = self.cars_on_street.iter().position(|&other| other == lane) {
+            self.cars_on_street.remove(idx);
+            self.cars_waiting += 1;
+        }
+    }
+}
+
+impl Lane {
+    /// Cruises along the following lanes until a free on-street spot is found
+    pub fn search_street_parking(
+        &mut self,
+        building: BuildingID,
+        trip: TripID,
+        hops_left: u8,
+        instant: Instant,
+        world: &mut World,
+    ) {
+        if self.parking.n_free() > 0 {
+            if let Some(location) = self.pathfinding.location {
+                self.parking.parked_for.push(building);
+                let spot = PreciseLocation {
+                    location,
+                    offset: self.construction.length / 2.0,
+                };
+                building.on_street_parking_found(trip, Some((self.id, spot)), instant, world);
+                return;
+            }
+        }
+
+        let next_lanes = self
+            .connectivity
+            .interactions
+            .iter()
+            .filter_map(|interaction| match *interaction {
+                Interaction::Next { next, .. } => Some(next),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        match seed((trip, hops_left)).choose(&next_lanes) {
+            Some(next) if hops_left > 0 => {
+                next.search_street_parking(building, trip, hops_left - 1, instant, world)
+            }
+            _ => building.on_street_parking_found(trip, None, instant, world),
+        }
+    }
+
+    pub fn unpark_car(
+        &mut self,
+        building: BuildingID,
+        trip: TripID,
+        rough_source: RoughLocationID,
+        instant: Instant,
+        world: &mut World,
+    ) {
+        self.parking.unpark(building);
+        let maybe_spot = self.pathfinding.location.map(|location| PreciseLocation {
+            location,
+            offset: self.construction.length / 2.0,
+        });
+        let requester: LocationRequesterID = trip.into();
+        requester.location_resolved(rough_source, maybe_spot, instant, world);
+    }
+
+    /// Parks cars that are waiting at a nearby building, as far as there are free spots
+    pub fn park_waiting_cars(&mut self, building: BuildingID, n_cars: u16, world: &mut World) {
+        let n_parked = n_cars.min(self.parking.n_free());
+        if n_parked > 0 {
+            for _ in 0..n_parked {
+                self.parking.parked_for.push(building);
+            }
+            building.on_waiting_cars_parked(self.id, n_parked, world);
+        }
+    }
+}
+
+/// Cars parked on a lane that is unbuilt go back to wait at their buildings,
+/// from where they can be parked again once a new lane is built
+pub fn on_unbuild(lane: &Lane, world: &mut World) {
+    for &building in lane.parking.parked_for.iter() {
+        building.street_parking_closed(lane.id, world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    auto_setup(system);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
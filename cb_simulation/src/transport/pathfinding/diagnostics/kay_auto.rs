@@ -0,0 +1,186 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct RoutingDiagnosticsListenerID {
+    _raw_id: RawID
+}
+
+impl Copy for RoutingDiagnosticsListenerID {}
+impl Clone for RoutingDiagnosticsListenerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for RoutingDiagnosticsListenerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RoutingDiagnosticsListenerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for RoutingDiagnosticsListenerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for RoutingDiagnosticsListenerID {
+    fn eq(&self, other: &RoutingDiagnosticsListenerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for RoutingDiagnosticsListenerID {}
+
+pub struct RoutingDiagnosticsListenerRepresentative;
+
+impl ActorOrActorTrait for RoutingDiagnosticsListenerRepresentative {
+    type ID = RoutingDiagnosticsListenerID;
+}
+
+impl TypedID for RoutingDiagnosticsListenerID {
+    type Target = RoutingDiagnosticsListenerRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        RoutingDiagnosticsListenerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + RoutingDiagnosticsListener> TraitIDFrom<Act> for RoutingDiagnosticsListenerID {}
+
+impl RoutingDiagnosticsListenerID {
+    pub fn on_routing_diagnostics(self, summary: RoutingSummary, links: CVec < LinkRoutingInfo >, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoutingDiagnosticsListener_on_routing_diagnostics(summary, links));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<RoutingDiagnosticsListenerRepresentative>();
+        system.register_trait_message::<MSG_RoutingDiagnosticsListener_on_routing_diagnostics>();
+    }
+
+    pub fn register_implementor<Act: Actor + RoutingDiagnosticsListener>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, RoutingDiagnosticsListenerRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_RoutingDiagnosticsListener_on_routing_diagnostics(summary, ref links), instance, world| {
+                instance.on_routing_diagnostics(summary, links, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingDiagnosticsListener_on_routing_diagnostics(pub RoutingSummary, pub CVec < LinkRoutingInfo >);
+
+impl Actor for RoutingDiagnostics {
+    type ID = RoutingDiagnosticsID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct RoutingDiagnosticsID {
+    _raw_id: RawID
+}
+
+impl Copy for RoutingDiagnosticsID {}
+impl Clone for RoutingDiagnosticsID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for RoutingDiagnosticsID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RoutingDiagnosticsID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for RoutingDiagnosticsID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for RoutingDiagnosticsID {
+    fn eq(&self, other: &RoutingDiagnosticsID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for RoutingDiagnosticsID {}
+
+impl TypedID for RoutingDiagnosticsID {
+    type Target = RoutingDiagnostics;
+
+    fn from_raw(id: RawID) -> Self {
+        RoutingDiagnosticsID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl RoutingDiagnosticsID {
+    pub fn spawn(time: TimeID, world: &mut World) -> Self {
+        let id = RoutingDiagnosticsID::from_raw(world.allocate_instance_id::<RoutingDiagnostics>());
+        let swarm = world.local_broadcast::<RoutingDiagnostics>();
+        world.send(swarm, MSG_RoutingDiagnostics_spawn(id, time));
+        id
+    }
+
+    pub fn report_link(self, info: LinkRoutingInfo, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoutingDiagnostics_report_link(info));
+    }
+
+    pub fn get_diagnostics(self, requester: RoutingDiagnosticsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoutingDiagnostics_get_diagnostics(requester));
+    }
+
+    pub fn wait_for_convergence(self, requester: RoutingDiagnosticsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoutingDiagnostics_wait_for_convergence(requester));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingDiagnostics_spawn(pub RoutingDiagnosticsID, pub TimeID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingDiagnostics_report_link(pub LinkRoutingInfo);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingDiagnostics_get_diagnostics(pub RoutingDiagnosticsListenerID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoutingDiagnostics_wait_for_convergence(pub RoutingDiagnosticsListenerID);
+
+impl Into<SleeperID> for RoutingDiagnosticsID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    RoutingDiagnosticsListenerID::register_trait(system);
+    SleeperID::register_implementor::<RoutingDiagnostics>(system);
+    system.add_spawner::<RoutingDiagnostics, _, _>(
+        |&MSG_RoutingDiagnostics_spawn(id, time), world| {
+            RoutingDiagnostics::spawn(id, time, world)
+        }, false
+    );
+
+    system.add_handler::<RoutingDiagnostics, _, _>(
+        |&MSG_RoutingDiagnostics_report_link(info), instance, world| {
+            instance.report_link(info, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<RoutingDiagnostics, _, _>(
+        |&MSG_RoutingDiagnostics_get_diagnostics(requester), instance, world| {
+            instance.get_diagnostics(requester, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<RoutingDiagnostics, _, _>(
+        |&MSG_RoutingDiagnostics_wait_for_convergence(requester), instance, world| {
+            instance.wait_for_convergence(requester, world); Fate::Live
+        }, false
+    );
+}
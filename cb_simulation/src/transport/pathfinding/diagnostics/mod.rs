@@ -0,0 +1,142 @@
+//! Diagnostics for the landmark-based route propagation: which landmark each link
+//! belongs to, how many routes it knows and whether routing has settled down
+//! everywhere after a change to the road network.
+
+use kay::{ActorSystem, World, Actor, TypedID};
+use compact::CVec;
+use cb_time::actors::{TimeID, Sleeper, SleeperID};
+use cb_time::units::{Instant, Duration, Ticks};
+
+use super::LinkID;
+
+/// How often all links are asked for their routing state, in seconds
+const ROUND_INTERVAL: usize = 5;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct LinkRoutingInfo {
+    pub link: LinkID,
+    pub landmark: Option<LinkID>,
+    pub hops_from_landmark: u8,
+    pub n_routes: u32,
+    /// Whether the landmark and routes of the link stayed the same for a while
+    pub stable: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoutingSummary {
+    pub n_links: u32,
+    pub n_stable: u32,
+    pub n_without_landmark: u32,
+    pub n_landmarks: u32,
+    /// All links reported stable routing in the last round
+    pub converged: bool,
+}
+
+impl RoutingSummary {
+    fn from_infos(infos: &CVec<LinkRoutingInfo>) -> RoutingSummary {
+        let n_links = infos.len() as u32;
+        let n_stable = infos.iter().filter(|info| info.stable).count() as u32;
+
+        RoutingSummary {
+            n_links,
+            n_stable,
+            n_without_landmark: infos.iter().filter(|info| info.landmark.is_none()).count()
+                as u32,
+            n_landmarks: infos
+                .iter()
+                .filter(|info| info.landmark == Some(info.link))
+                .count() as u32,
+            converged: n_links > 0 && n_stable == n_links,
+        }
+    }
+}
+
+pub trait RoutingDiagnosticsListener {
+    fn on_routing_diagnostics(
+        &mut self,
+        summary: RoutingSummary,
+        links: &CVec<LinkRoutingInfo>,
+        world: &mut World,
+    );
+}
+
+/// Collects the routing state of all links in rounds. A round ends when the next
+/// one starts, so results are always one round old
+#[derive(Compact, Clone)]
+pub struct RoutingDiagnostics {
+    id: RoutingDiagnosticsID,
+    time: TimeID,
+    collecting: CVec<LinkRoutingInfo>,
+    last_round: CVec<LinkRoutingInfo>,
+    last_summary: RoutingSummary,
+    waiting_for_convergence: CVec<RoutingDiagnosticsListenerID>,
+}
+
+impl RoutingDiagnostics {
+    pub fn spawn(id: RoutingDiagnosticsID, time: TimeID, world: &mut World) -> Self {
+        time.wake_up_in(Ticks(0), id.into(), world);
+
+        RoutingDiagnostics {
+            id,
+            time,
+            collecting: CVec::new(),
+            last_round: CVec::new(),
+            last_summary: RoutingSummary::default(),
+            waiting_for_convergence: CVec::new(),
+        }
+    }
+
+    pub fn report_link(&mut self, info: LinkRoutingInfo, _: &mut World) {
+        self.collecting.push(info);
+    }
+
+    pub fn get_diagnostics(&mut self, requester: RoutingDiagnosticsListenerID, world: &mut World) {
+        requester.on_routing_diagnostics(self.last_summary, self.last_round.clone(), world);
+    }
+
+    /// Answers as soon as a round finds all links stable, right away if the last one did
+    pub fn wait_for_convergence(
+        &mut self,
+        requester: RoutingDiagnosticsListenerID,
+        world: &mut World,
+    ) {
+        if self.last_summary.converged {
+            self.get_diagnostics(requester, world);
+        } else {
+            self.waiting_for_convergence.push(requester);
+        }
+    }
+}
+
+impl Sleeper for RoutingDiagnostics {
+    fn wake(&mut self, _current_instant: Instant, world: &mut World) {
+        self.last_round = ::std::mem::replace(&mut self.collecting, CVec::new());
+        self.last_summary = RoutingSummary::from_infos(&self.last_round);
+
+        if self.last_summary.converged {
+            for listener in self.waiting_for_convergence.iter() {
+                listener.on_routing_diagnostics(self.last_summary, self.last_round.clone(), world);
+            }
+            self.waiting_for_convergence = CVec::new();
+        }
+
+        LinkID::global_broadcast(world).report_routing(self.id, world);
+        self.time.wake_up_in(
+            Ticks::from(Duration::from_seconds(ROUND_INTERVAL)),
+            self.id_as(),
+            world,
+        );
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<RoutingDiagnostics>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World, time: TimeID) {
+    RoutingDiagnosticsID::spawn(time, world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
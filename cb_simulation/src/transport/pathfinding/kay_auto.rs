@@ -78,6 +78,14 @@ impl LinkID {
         world.send(self.as_raw(), MSG_Link_get_distance_to(destination, requester));
     }
     
+    pub fn report_routing(self, diagnostics: RoutingDiagnosticsID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Link_report_routing(diagnostics));
+    }
+    
+    pub fn report_to_oracle(self, oracle: PathfindingOracleID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Link_report_to_oracle(oracle));
+    }
+    
     pub fn add_attachee(self, attachee: AttacheeID, world: &mut World) {
         world.send(self.as_raw(), MSG_Link_add_attachee(attachee));
     }
@@ -95,6 +103,8 @@ impl LinkID {
         system.register_trait_message::<MSG_Link_forget_routes>();
         system.register_trait_message::<MSG_Link_join_landmark>();
         system.register_trait_message::<MSG_Link_get_distance_to>();
+        system.register_trait_message::<MSG_Link_report_routing>();
+        system.register_trait_message::<MSG_Link_report_to_oracle>();
         system.register_trait_message::<MSG_Link_add_attachee>();
         system.register_trait_message::<MSG_Link_remove_attachee>();
     }
@@ -143,6 +153,18 @@ impl LinkID {
             }, false
         );
         
+        system.add_handler::<Act, _, _>(
+            |&MSG_Link_report_routing(diagnostics), instance, world| {
+                instance.report_routing(diagnostics, world); Fate::Live
+            }, false
+        );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_Link_report_to_oracle(oracle), instance, world| {
+                instance.report_to_oracle(oracle, world); Fate::Live
+            }, false
+        );
+        
         system.add_handler::<Act, _, _>(
             |&MSG_Link_add_attachee(attachee), instance, world| {
                 instance.add_attachee(attachee, world); Fate::Live
@@ -172,6 +194,10 @@ struct MSG_Link_join_landmark(pub LinkID, pub Location, pub u8);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Link_get_distance_to(pub Location, pub DistanceRequesterID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Link_report_routing(pub RoutingDiagnosticsID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Link_report_to_oracle(pub PathfindingOracleID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Link_add_attachee(pub AttacheeID);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Link_remove_attachee(pub AttacheeID);
@@ -293,11 +319,21 @@ impl RoughLocationID {
     pub fn resolve_as_position(self, requester: PositionRequesterID, rough_location: RoughLocationID, world: &mut World) {
         world.send(self.as_raw(), MSG_RoughLocation_resolve_as_position(requester, rough_location));
     }
+    
+    pub fn take_car(self, trip: TripID, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoughLocation_take_car(trip, instant));
+    }
+    
+    pub fn find_parking(self, trip: TripID, arrival_lane: LaneID, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_RoughLocation_find_parking(trip, arrival_lane, instant));
+    }
 
     pub fn register_trait(system: &mut ActorSystem) {
         system.register_trait::<RoughLocationRepresentative>();
         system.register_trait_message::<MSG_RoughLocation_resolve_as_location>();
         system.register_trait_message::<MSG_RoughLocation_resolve_as_position>();
+        system.register_trait_message::<MSG_RoughLocation_take_car>();
+        system.register_trait_message::<MSG_RoughLocation_find_parking>();
     }
 
     pub fn register_implementor<Act: Actor + RoughLocation>(system: &mut ActorSystem) {
@@ -313,6 +349,18 @@ impl RoughLocationID {
                 instance.resolve_as_position(requester, rough_location, world); Fate::Live
             }, false
         );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_RoughLocation_take_car(trip, instant), instance, world| {
+                instance.take_car(trip, instant, world); Fate::Live
+            }, false
+        );
+        
+        system.add_handler::<Act, _, _>(
+            |&MSG_RoughLocation_find_parking(trip, arrival_lane, instant), instance, world| {
+                instance.find_parking(trip, arrival_lane, instant, world); Fate::Live
+            }, false
+        );
     }
 }
 
@@ -320,6 +368,10 @@ impl RoughLocationID {
 struct MSG_RoughLocation_resolve_as_location(pub LocationRequesterID, pub RoughLocationID, pub Instant);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_RoughLocation_resolve_as_position(pub PositionRequesterID, pub RoughLocationID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoughLocation_take_car(pub TripID, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RoughLocation_find_parking(pub TripID, pub LaneID, pub Instant);
 #[derive(Serialize, Deserialize)] #[serde(transparent)]
 pub struct LocationRequesterID {
     _raw_id: RawID
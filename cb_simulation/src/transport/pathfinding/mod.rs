@@ -5,6 +5,10 @@ use cb_time::units::Instant;
 
 pub mod trip;
 pub mod road_pathfinding;
+pub mod diagnostics;
+pub mod oracle;
+use self::diagnostics::{RoutingDiagnosticsID, LinkRoutingInfo};
+use self::oracle::PathfindingOracleID;
 
 const LOG_T: &str = "Pathfinding";
 
@@ -35,6 +39,14 @@ pub trait Link: Actor {
     }
 
     fn pathfinding_tick(&mut self, world: &mut World) {
+        let was_changing = {
+            let core = self.core();
+            core.routes_changed
+                || core.query_routes_next_tick
+                || !core.tell_to_forget_next_tick.is_empty()
+                || core.routing_timeout > 0
+        };
+
         if let Some(location) = self.core().location {
             for LinkConnection {
                 link: successor, ..
@@ -57,9 +69,11 @@ pub trait Link: Actor {
                 learned_landmark_from: Some(self.id_as()),
                 routes: CHashMap::new(),
                 routes_changed: true,
+                costs_changed: false,
                 query_routes_next_tick: false,
                 tell_to_forget_next_tick: CVec::new(),
                 routing_timeout: ROUTING_TIMEOUT_AFTER_CHANGE,
+                ticks_since_change: 0,
                 attachees: self.core().attachees.clone(),
             }
         }
@@ -87,13 +101,20 @@ pub trait Link: Actor {
                 self.core_mut().tell_to_forget_next_tick.clear();
             }
 
-            if self.core().routes_changed {
+            if self.core().routes_changed || self.core().costs_changed {
                 for predecessor in self.predecessors() {
                     self.query_routes(predecessor.link, predecessor.connection_cost, world);
                 }
                 self.core_mut().routes_changed = false;
+                self.core_mut().costs_changed = false;
             }
         }
+
+        self.core_mut().ticks_since_change = if was_changing {
+            0
+        } else {
+            self.core().ticks_since_change.saturating_add(1)
+        };
     }
 
     fn query_routes(&mut self, requester: LinkID, connection_cost: f32, world: &mut World) {
@@ -106,7 +127,7 @@ pub trait Link: Actor {
                         destination,
                         CommunicatedRoutingEntry {
                             distance: stored_entry.distance + connection_cost,
-                            distance_hops: stored_entry.distance_hops + 1,
+                            distance_hops: stored_entry.distance_hops.saturating_add(1),
                         },
                     )
                 })
@@ -140,12 +161,34 @@ pub trait Link: Actor {
                         .map(|self_dest| self_dest.landmark == destination.landmark)
                         .unwrap_or(false)
                 {
-                    let insert = self
+                    // cost changes along the current route are taken over once they are
+                    // noticeable, but switching to another route needs a clear advantage
+                    // to avoid flapping
+                    let (insert, is_new_route, got_clearly_worse) = self
                         .core()
                         .routes
                         .get(destination)
-                        .map(|&StoredRoutingEntry { distance, .. }| new_distance < distance)
-                        .unwrap_or(true);
+                        .map(|known| {
+                            if known.learned_from == from {
+                                let hops_changed = new_distance_hops != known.distance_hops;
+                                let cost_changed = (new_distance - known.distance).abs()
+                                    > known.distance * COST_CHANGE_THRESHOLD;
+                                (
+                                    hops_changed || cost_changed,
+                                    hops_changed,
+                                    new_distance > known.distance * (1.0 + ROUTE_SWITCH_MARGIN),
+                                )
+                            } else {
+                                let switch =
+                                    new_distance < known.distance * (1.0 - ROUTE_SWITCH_MARGIN);
+                                (switch, switch, false)
+                            }
+                        })
+                        .unwrap_or((true, true, false));
+                    if got_clearly_worse {
+                        // another route might be better now, for example around a jam
+                        self.core_mut().query_routes_next_tick = true;
+                    }
                     if insert {
                         self.core_mut().routes.insert(
                             destination,
@@ -156,7 +199,11 @@ pub trait Link: Actor {
                                 learned_from: from,
                             },
                         );
-                        self.core_mut().routes_changed = true;
+                        if is_new_route {
+                            self.core_mut().routes_changed = true;
+                        } else {
+                            self.core_mut().costs_changed = true;
+                        }
                     }
                 }
             }
@@ -232,9 +279,11 @@ pub trait Link: Actor {
                 hops_from_landmark,
                 routes: CHashMap::new(),
                 routes_changed: true,
+                costs_changed: false,
                 query_routes_next_tick: true,
                 tell_to_forget_next_tick,
                 routing_timeout: ROUTING_TIMEOUT_AFTER_CHANGE,
+                ticks_since_change: 0,
                 attachees: self.core().attachees.clone(),
             };
         }
@@ -255,6 +304,37 @@ pub trait Link: Actor {
         requester.on_distance(maybe_distance, world);
     }
 
+    fn report_routing(&mut self, diagnostics: RoutingDiagnosticsID, world: &mut World) {
+        let core = self.core();
+        diagnostics.report_link(
+            LinkRoutingInfo {
+                link: self.id_as(),
+                landmark: core.location.map(|location| location.landmark),
+                hops_from_landmark: core.hops_from_landmark,
+                n_routes: core.routes.keys().count() as u32,
+                stable: core.ticks_since_change >= STABLE_AFTER_TICKS,
+            },
+            world,
+        );
+    }
+
+    fn report_to_oracle(&mut self, oracle: PathfindingOracleID, world: &mut World) {
+        let as_pairs = |connections: Vec<LinkConnection>| -> CVec<(LinkID, f32)> {
+            connections
+                .into_iter()
+                .map(|connection| (connection.link, connection.connection_cost))
+                .collect()
+        };
+        oracle.add_link(
+            self.id_as(),
+            self.core().location,
+            self.self_as_route().map(|(_, entry)| entry.distance),
+            as_pairs(self.successors()),
+            as_pairs(self.predecessors()),
+            world,
+        );
+    }
+
     fn add_attachee(&mut self, attachee: AttacheeID, _: &mut World) {
         self.core_mut().attachees.push(attachee);
     }
@@ -277,19 +357,24 @@ pub struct PathfindingCore {
     pub learned_landmark_from: Option<LinkID>,
     pub routes: CHashMap<Location, StoredRoutingEntry>,
     pub routes_changed: bool,
+    /// Only the cost of known routes changed, for example with congestion,
+    /// which predecessors hear about but which doesn't make routing unstable
+    pub costs_changed: bool,
     pub tell_to_forget_next_tick: CVec<Location>,
     pub query_routes_next_tick: bool,
     pub routing_timeout: u16,
+    /// Pathfinding ticks since the landmark or routes of this link last changed
+    pub ticks_since_change: u16,
     attachees: CVec<AttacheeID>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct Location {
     pub landmark: LinkID,
     pub link: LinkID,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct PreciseLocation {
     pub location: Location,
     pub offset: f32,
@@ -350,12 +435,23 @@ pub struct CommunicatedRoutingEntry {
 const IDEAL_LANDMARK_RADIUS: u8 = 3;
 const MIN_LANDMARK_INCOMING: usize = 3;
 const ROUTING_TIMEOUT_AFTER_CHANGE: u16 = 15;
+/// A link whose routing didn't change for this many pathfinding ticks counts as stable
+const STABLE_AFTER_TICKS: u16 = 30;
+/// How much cheaper another route has to be before it replaces a known one
+const ROUTE_SWITCH_MARGIN: f32 = 0.1;
+/// Cost changes along a known route are only passed on once they exceed this fraction,
+/// so small fluctuations don't ripple through the whole network
+const COST_CHANGE_THRESHOLD: f32 = 0.05;
 
 pub enum RoughLocationResolve {
     Done(Option<PreciseLocation>, P2),
     SameAs(RoughLocationID),
 }
 
+use self::trip::TripID;
+use super::lane::LaneID;
+use super::parking::ParkingSpot;
+
 pub trait RoughLocation {
     fn resolve(&self) -> RoughLocationResolve;
 
@@ -391,6 +487,36 @@ pub trait RoughLocation {
             }
         }
     }
+
+    /// Hands a car to a trip starting here, if there is one
+    fn take_car(&mut self, trip: TripID, instant: Instant, world: &mut World) {
+        match self.resolve() {
+            RoughLocationResolve::Done(..) => {
+                trip.car_taken(true, instant, world);
+            }
+            RoughLocationResolve::SameAs(other_rough_location) => {
+                other_rough_location.take_car(trip, instant, world);
+            }
+        }
+    }
+
+    /// Finds a spot for the car of a trip that arrived here on the given lane
+    fn find_parking(
+        &mut self,
+        trip: TripID,
+        arrival_lane: LaneID,
+        instant: Instant,
+        world: &mut World,
+    ) {
+        match self.resolve() {
+            RoughLocationResolve::Done(..) => {
+                trip.parking_found(ParkingSpot::Unparked, None, instant, world);
+            }
+            RoughLocationResolve::SameAs(other_rough_location) => {
+                other_rough_location.find_parking(trip, arrival_lane, instant, world);
+            }
+        }
+    }
 }
 
 pub trait LocationRequester {
@@ -421,11 +547,15 @@ use cb_time::actors::TimeID;
 pub fn setup(system: &mut ActorSystem) {
     trip::setup(system);
     road_pathfinding::auto_setup(system);
+    diagnostics::setup(system);
+    oracle::setup(system);
     auto_setup(system);
 }
 
 pub fn spawn(world: &mut World, time: TimeID) {
     trip::spawn(world, time);
+    diagnostics::spawn(world, time);
+    oracle::spawn(world);
 }
 
 mod kay_auto;
@@ -0,0 +1,204 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for PathfindingOracle {
+    type ID = PathfindingOracleID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct PathfindingOracleID {
+    _raw_id: RawID
+}
+
+impl Copy for PathfindingOracleID {}
+impl Clone for PathfindingOracleID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for PathfindingOracleID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "PathfindingOracleID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for PathfindingOracleID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for PathfindingOracleID {
+    fn eq(&self, other: &PathfindingOracleID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for PathfindingOracleID {}
+
+impl TypedID for PathfindingOracleID {
+    type Target = PathfindingOracle;
+
+    fn from_raw(id: RawID) -> Self {
+        PathfindingOracleID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl PathfindingOracleID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = PathfindingOracleID::from_raw(world.allocate_instance_id::<PathfindingOracle>());
+        let swarm = world.local_broadcast::<PathfindingOracle>();
+        world.send(swarm, MSG_PathfindingOracle_spawn(id, ));
+        id
+    }
+
+    pub fn check(self, n_pairs: u32, world: &mut World) {
+        world.send(self.as_raw(), MSG_PathfindingOracle_check(n_pairs));
+    }
+
+    pub fn add_link(self, link: LinkID, location: Option < Location >, self_route_distance: Option < f32 >, successors: CVec < (LinkID, f32) >, predecessors: CVec < (LinkID, f32) >, world: &mut World) {
+        world.send(self.as_raw(), MSG_PathfindingOracle_add_link(link, location, self_route_distance, successors, predecessors));
+    }
+
+    pub fn check_result(self, check: RouteCheckID, outcome: RouteCheckOutcome, world: &mut World) {
+        world.send(self.as_raw(), MSG_PathfindingOracle_check_result(check, outcome));
+    }
+}
+
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PathfindingOracle_spawn(pub PathfindingOracleID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PathfindingOracle_check(pub u32);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PathfindingOracle_add_link(pub LinkID, pub Option < Location >, pub Option < f32 >, pub CVec < (LinkID, f32) >, pub CVec < (LinkID, f32) >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PathfindingOracle_check_result(pub RouteCheckID, pub RouteCheckOutcome);
+
+impl Into<SleeperID> for PathfindingOracleID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+impl Actor for RouteCheck {
+    type ID = RouteCheckID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct RouteCheckID {
+    _raw_id: RawID
+}
+
+impl Copy for RouteCheckID {}
+impl Clone for RouteCheckID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for RouteCheckID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RouteCheckID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for RouteCheckID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for RouteCheckID {
+    fn eq(&self, other: &RouteCheckID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for RouteCheckID {}
+
+impl TypedID for RouteCheckID {
+    type Target = RouteCheck;
+
+    fn from_raw(id: RawID) -> Self {
+        RouteCheckID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl RouteCheckID {
+    pub fn spawn(oracle: PathfindingOracleID, source: LinkID, destination: Location, expected: Option < f32 >, expected_to_landmark: Option < f32 >, world: &mut World) -> Self {
+        let id = RouteCheckID::from_raw(world.allocate_instance_id::<RouteCheck>());
+        let swarm = world.local_broadcast::<RouteCheck>();
+        world.send(swarm, MSG_RouteCheck_spawn(id, oracle, source, destination, expected, expected_to_landmark));
+        id
+    }
+
+    pub fn done(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_RouteCheck_done());
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RouteCheck_spawn(pub RouteCheckID, pub PathfindingOracleID, pub LinkID, pub Location, pub Option < f32 >, pub Option < f32 >);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_RouteCheck_done();
+
+impl Into<DistanceRequesterID> for RouteCheckID {
+    fn into(self) -> DistanceRequesterID {
+        DistanceRequesterID::from_raw(self.as_raw())
+    }
+}
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    SleeperID::register_implementor::<PathfindingOracle>(system);
+    system.add_spawner::<PathfindingOracle, _, _>(
+        |&MSG_PathfindingOracle_spawn(id, ), world| {
+            PathfindingOracle::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<PathfindingOracle, _, _>(
+        |&MSG_PathfindingOracle_check(n_pairs), instance, world| {
+            instance.check(n_pairs, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<PathfindingOracle, _, _>(
+        |&MSG_PathfindingOracle_add_link(link, location, self_route_distance, ref successors, ref predecessors), instance, world| {
+            instance.add_link(link, location, self_route_distance, successors, predecessors, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<PathfindingOracle, _, _>(
+        |&MSG_PathfindingOracle_check_result(check, outcome), instance, world| {
+            instance.check_result(check, outcome, world); Fate::Live
+        }, false
+    );
+
+    DistanceRequesterID::register_implementor::<RouteCheck>(system);
+    system.add_spawner::<RouteCheck, _, _>(
+        |&MSG_RouteCheck_spawn(id, oracle, source, destination, expected, expected_to_landmark), world| {
+            RouteCheck::spawn(id, oracle, source, destination, expected, expected_to_landmark, world)
+        }, false
+    );
+
+    system.add_handler::<RouteCheck, _, _>(
+        |&MSG_RouteCheck_done(), instance, world| {
+            instance.done(world)
+        }, false
+    );
+}
@@ -0,0 +1,426 @@
+//! An exact, centralised shortest-path check of the distributed pathfinding.
+//! All links are snapshotted into one graph, which is searched with Dijkstra
+//! from each sampled source link to its sampled destinations. The results are
+//! compared to the distances that the links themselves report, and every
+//! mismatch is logged.
+
+use kay::{ActorSystem, World, Fate, Actor, TypedID};
+use compact::{CVec, CHashMap};
+use ordered_float::OrderedFloat;
+use std::collections::{BinaryHeap, HashMap, BTreeMap};
+use std::cmp::Reverse;
+use cb_time::actors::{TimeID, Sleeper, SleeperID};
+use cb_time::units::{Instant, Ticks};
+use cb_util::random::{seed, Rng};
+
+use super::{LinkID, Location, DistanceRequester, DistanceRequesterID};
+
+use cb_util::log::{info, warn};
+const LOG_T: &str = "Pathfinding Oracle";
+
+/// How long to wait for all links to be snapshotted
+const SNAPSHOT_WAIT: Ticks = Ticks(20);
+/// How much longer than the exact distance a reported one may be and still count
+/// as matching, since routes only switch once another one is clearly shorter
+/// and small cost changes aren't passed on. No route is shorter than the exact one
+const MAX_RELATIVE_EXCESS: f32 = 0.1;
+const ABSOLUTE_TOLERANCE: f32 = 1.0;
+
+#[derive(Compact, Clone)]
+struct OracleLink {
+    location: Option<Location>,
+    self_route_distance: Option<f32>,
+    successors: CVec<(LinkID, f32)>,
+    predecessors: CVec<(LinkID, f32)>,
+}
+
+/// The snapshotted links, indexed for searching
+struct SnapshotGraph {
+    links: Vec<LinkID>,
+    index: HashMap<LinkID, usize>,
+    self_route_distances: Vec<Option<f32>>,
+    /// For each link: the links that lead to it and what that connection costs
+    incoming: Vec<Vec<(usize, f32)>>,
+    /// For each link: the links it leads to and what that connection costs
+    outgoing: Vec<Vec<(usize, f32)>>,
+}
+
+impl SnapshotGraph {
+    fn build(oracle_links: &CHashMap<LinkID, OracleLink>) -> SnapshotGraph {
+        let links: Vec<LinkID> = oracle_links.keys().cloned().collect();
+        let index: HashMap<LinkID, usize> = links
+            .iter()
+            .enumerate()
+            .map(|(idx, &link)| (link, idx))
+            .collect();
+        let mut incoming = vec![Vec::new(); links.len()];
+
+        for (&link, oracle_link) in oracle_links.pairs() {
+            for &(predecessor, predecessor_cost) in oracle_link.predecessors.iter() {
+                if let Some(&predecessor_idx) = index.get(&predecessor) {
+                    // a route can be passed on from either end of the connection
+                    let successor_cost = oracle_links
+                        .get(predecessor)
+                        .and_then(|predecessor_link| {
+                            predecessor_link
+                                .successors
+                                .iter()
+                                .find(|&&(successor, _)| successor == link)
+                                .map(|&(_, cost)| cost)
+                        })
+                        .unwrap_or(predecessor_cost);
+                    incoming[index[&link]]
+                        .push((predecessor_idx, predecessor_cost.min(successor_cost)));
+                }
+            }
+        }
+
+        let mut outgoing = vec![Vec::new(); links.len()];
+        for (idx, link_incoming) in incoming.iter().enumerate() {
+            for &(predecessor_idx, cost) in link_incoming {
+                outgoing[predecessor_idx].push((idx, cost));
+            }
+        }
+
+        SnapshotGraph {
+            self_route_distances: links
+                .iter()
+                .map(|&link| oracle_links.get(link).and_then(|l| l.self_route_distance))
+                .collect(),
+            links,
+            index,
+            incoming,
+            outgoing,
+        }
+    }
+
+    /// Exact distances from `source` to every link, accumulated the same way as routes
+    /// add up their distances while being passed on between links: the connection costs
+    /// along the way, except for the one into the destination, plus the destination's
+    /// own route
+    fn distances_from(&self, source: usize) -> Vec<Option<f32>> {
+        let mut costs_to = vec![None; self.links.len()];
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((OrderedFloat(0.0), source)));
+
+        while let Some(Reverse((OrderedFloat(cost), idx))) = queue.pop() {
+            if costs_to[idx].is_some() {
+                continue;
+            }
+            costs_to[idx] = Some(cost);
+
+            for &(successor, connection_cost) in &self.outgoing[idx] {
+                if costs_to[successor].is_none() {
+                    queue.push(Reverse((OrderedFloat(cost + connection_cost), successor)));
+                }
+            }
+        }
+
+        (0..self.links.len())
+            .map(|destination| {
+                let self_route_distance = self.self_route_distances[destination]?;
+                self.incoming[destination]
+                    .iter()
+                    .filter_map(|&(predecessor, _)| costs_to[predecessor])
+                    .min_by_key(|&cost| OrderedFloat(cost))
+                    .map(|cost| cost + self_route_distance)
+            })
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RouteCheckOutcome {
+    Matching,
+    /// Only the distance to the destination's landmark matches,
+    /// which is all that links far away from the destination know
+    LandmarkMatching,
+    /// Both agree that the destination can't be reached
+    Unreachable,
+    /// The reported distance differs from the exact one
+    Discrepancy,
+    /// A route is reported to a destination that can't be reached anymore
+    Stale,
+    /// The destination can be reached, but no route is known
+    Missing,
+}
+
+#[derive(Compact, Clone)]
+pub struct PathfindingOracle {
+    id: PathfindingOracleID,
+    links: CHashMap<LinkID, OracleLink>,
+    n_pairs: u32,
+    snapshotting: bool,
+    /// Checks still waiting for the distance their source link reports
+    pending: CVec<RouteCheckID>,
+    outcomes: CVec<RouteCheckOutcome>,
+}
+
+impl PathfindingOracle {
+    pub fn spawn(id: PathfindingOracleID, _: &mut World) -> PathfindingOracle {
+        PathfindingOracle {
+            id,
+            links: CHashMap::new(),
+            n_pairs: 0,
+            snapshotting: false,
+            pending: CVec::new(),
+            outcomes: CVec::new(),
+        }
+    }
+
+    /// Snapshots all links, then checks `n_pairs` random pairs of them.
+    /// Checks of the last run that are still unanswered, for example because
+    /// their source link was unbuilt in the meantime, are given up on
+    pub fn check(&mut self, n_pairs: u32, world: &mut World) {
+        if self.snapshotting {
+            warn(LOG_T, "Still snapshotting for the last check", self.id, world);
+            return;
+        }
+        if !self.pending.is_empty() {
+            for check in self.pending.iter() {
+                check.done(world);
+            }
+            self.report(world);
+        }
+        self.snapshotting = true;
+        self.links = CHashMap::new();
+        self.n_pairs = n_pairs;
+        LinkID::global_broadcast(world).report_to_oracle(self.id, world);
+        TimeID::local_first(world).wake_up_in(SNAPSHOT_WAIT, self.id_as(), world);
+    }
+
+    pub fn add_link(
+        &mut self,
+        link: LinkID,
+        location: Option<Location>,
+        self_route_distance: Option<f32>,
+        successors: &CVec<(LinkID, f32)>,
+        predecessors: &CVec<(LinkID, f32)>,
+        _: &mut World,
+    ) {
+        self.links.insert(
+            link,
+            OracleLink {
+                location,
+                self_route_distance,
+                successors: successors.clone(),
+                predecessors: predecessors.clone(),
+            },
+        );
+    }
+
+    pub fn check_result(
+        &mut self,
+        check: RouteCheckID,
+        outcome: RouteCheckOutcome,
+        world: &mut World,
+    ) {
+        // answers to checks that were given up on are ignored
+        if !self.pending.contains(&check) {
+            return;
+        }
+        self.pending.retain(|&pending_check| pending_check != check);
+        self.outcomes.push(outcome);
+
+        if self.pending.is_empty() {
+            self.report(world);
+        }
+    }
+
+    fn report(&mut self, world: &mut World) {
+        let summary = {
+            let count = |wanted: RouteCheckOutcome| {
+                self.outcomes
+                    .iter()
+                    .filter(|&&outcome| outcome == wanted)
+                    .count()
+            };
+            format!(
+                "Checked {} routes: {} matching, {} matching the landmark, {} unreachable, \
+                 {} discrepancies, {} stale, {} missing, {} unanswered",
+                self.outcomes.len() + self.pending.len(),
+                count(RouteCheckOutcome::Matching),
+                count(RouteCheckOutcome::LandmarkMatching),
+                count(RouteCheckOutcome::Unreachable),
+                count(RouteCheckOutcome::Discrepancy),
+                count(RouteCheckOutcome::Stale),
+                count(RouteCheckOutcome::Missing),
+                self.pending.len(),
+            )
+        };
+        info(LOG_T, summary, self.id, world);
+        self.outcomes = CVec::new();
+        self.pending = CVec::new();
+    }
+}
+
+impl Sleeper for PathfindingOracle {
+    fn wake(&mut self, current_instant: Instant, world: &mut World) {
+        self.snapshotting = false;
+        let graph = SnapshotGraph::build(&self.links);
+        let located_links: Vec<(usize, Location)> = graph
+            .links
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, &link)| {
+                self.links
+                    .get(link)
+                    .and_then(|oracle_link| oracle_link.location)
+                    .map(|location| (idx, location))
+            })
+            .collect();
+
+        if located_links.len() < 2 {
+            info(LOG_T, "Not enough routable links to check", self.id, world);
+            return;
+        }
+
+        let mut rng = seed(current_instant);
+        let mut destinations_by_source: BTreeMap<usize, Vec<(usize, Location)>> = BTreeMap::new();
+
+        for _ in 0..self.n_pairs {
+            let (source, _) = located_links[rng.gen_range(0, located_links.len())];
+            let (destination, destination_location) =
+                located_links[rng.gen_range(0, located_links.len())];
+            if source != destination {
+                destinations_by_source
+                    .entry(source)
+                    .or_insert_with(Vec::new)
+                    .push((destination, destination_location));
+            }
+        }
+
+        for (&source, destinations) in &destinations_by_source {
+            let distances = graph.distances_from(source);
+
+            for &(destination, destination_location) in destinations {
+                let expected = distances[destination];
+                let expected_to_landmark = if destination_location.is_landmark() {
+                    expected
+                } else {
+                    graph
+                        .index
+                        .get(&destination_location.landmark)
+                        .and_then(|&landmark| distances[landmark])
+                };
+
+                self.pending.push(RouteCheckID::spawn(
+                    self.id,
+                    graph.links[source],
+                    destination_location,
+                    expected,
+                    expected_to_landmark,
+                    world,
+                ));
+            }
+        }
+
+        if self.pending.is_empty() {
+            info(LOG_T, "No pairs of different links sampled", self.id, world);
+        }
+    }
+}
+
+/// Asks one link for its distance to a destination and compares it to the exact one
+#[derive(Compact, Clone)]
+pub struct RouteCheck {
+    id: RouteCheckID,
+    oracle: PathfindingOracleID,
+    source: LinkID,
+    destination: Location,
+    expected: Option<f32>,
+    expected_to_landmark: Option<f32>,
+}
+
+impl RouteCheck {
+    pub fn spawn(
+        id: RouteCheckID,
+        oracle: PathfindingOracleID,
+        source: LinkID,
+        destination: Location,
+        expected: Option<f32>,
+        expected_to_landmark: Option<f32>,
+        world: &mut World,
+    ) -> RouteCheck {
+        source.get_distance_to(destination, id.into(), world);
+
+        RouteCheck {
+            id,
+            oracle,
+            source,
+            destination,
+            expected,
+            expected_to_landmark,
+        }
+    }
+
+    pub fn done(&mut self, _: &mut World) -> Fate {
+        Fate::Die
+    }
+}
+
+fn roughly_matches(reported: f32, expected: f32) -> bool {
+    reported >= expected - ABSOLUTE_TOLERANCE
+        && reported <= expected * (1.0 + MAX_RELATIVE_EXCESS) + ABSOLUTE_TOLERANCE
+}
+
+impl DistanceRequester for RouteCheck {
+    fn on_distance(&mut self, maybe_distance: Option<f32>, world: &mut World) {
+        // links far from the destination only know the way to its landmark
+        let outcome = match (maybe_distance, self.expected, self.expected_to_landmark) {
+            (None, None, None) => RouteCheckOutcome::Unreachable,
+            (None, ..) => RouteCheckOutcome::Missing,
+            (Some(_), None, None) => RouteCheckOutcome::Stale,
+            (Some(reported), expected, expected_to_landmark) => {
+                let matches = |maybe_expected: Option<f32>| {
+                    maybe_expected
+                        .map(|expected| roughly_matches(reported, expected))
+                        .unwrap_or(false)
+                };
+                if matches(expected) {
+                    RouteCheckOutcome::Matching
+                } else if matches(expected_to_landmark) {
+                    RouteCheckOutcome::LandmarkMatching
+                } else {
+                    RouteCheckOutcome::Discrepancy
+                }
+            }
+        };
+
+        if outcome == RouteCheckOutcome::Discrepancy
+            || outcome == RouteCheckOutcome::Stale
+            || outcome == RouteCheckOutcome::Missing
+        {
+            warn(
+                LOG_T,
+                format!(
+                    "{:?} from {:?} to {:?}: reported {:?}, exact {:?} (to landmark {:?})",
+                    outcome,
+                    self.source,
+                    self.destination,
+                    maybe_distance,
+                    self.expected,
+                    self.expected_to_landmark
+                ),
+                self.id,
+                world,
+            );
+        }
+
+        self.oracle.check_result(self.id, outcome, world);
+        self.id.done(world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<PathfindingOracle>();
+    system.register::<RouteCheck>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    PathfindingOracleID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
@@ -23,7 +23,7 @@ impl Link for Lane {
                 (
                     destination,
                     CommunicatedRoutingEntry {
-                        distance: self.construction.length,
+                        distance: self.travel_cost(),
                         distance_hops: 0,
                     },
                 )
@@ -50,9 +50,16 @@ impl Link for Lane {
             .interactions
             .iter()
             .filter_map(|interaction| match *interaction {
-                Interaction::Switch { to, is_left, .. } => Some(LinkConnection {
+                Interaction::Switch {
+                    to,
+                    is_left,
+                    closed,
+                    ..
+                } => Some(LinkConnection {
                     link: to.into(),
-                    connection_cost: if is_left {
+                    connection_cost: if closed {
+                        CLOSED_LANE_COST
+                    } else if is_left {
                         LANE_CHANGE_COST_LEFT
                     } else {
                         LANE_CHANGE_COST_RIGHT
@@ -60,7 +67,7 @@ impl Link for Lane {
                 }),
                 Interaction::Next { next, .. } => Some(LinkConnection {
                     link: next.into(),
-                    connection_cost: self.construction.length,
+                    connection_cost: self.travel_cost(),
                 }),
                 _ => None,
             })
@@ -72,9 +79,16 @@ impl Link for Lane {
             .interactions
             .iter()
             .filter_map(|interaction| match *interaction {
-                Interaction::Switch { to, is_left, .. } => Some(LinkConnection {
+                Interaction::Switch {
+                    to,
+                    is_left,
+                    closed,
+                    ..
+                } => Some(LinkConnection {
                     link: to.into(),
-                    connection_cost: if is_left {
+                    connection_cost: if closed {
+                        CLOSED_LANE_COST
+                    } else if is_left {
                         LANE_CHANGE_COST_RIGHT
                     } else {
                         LANE_CHANGE_COST_LEFT
@@ -82,7 +96,7 @@ impl Link for Lane {
                 }),
                 Interaction::Previous { previous, .. } => Some(LinkConnection {
                     link: previous.into(),
-                    connection_cost: self.construction.length,
+                    connection_cost: self.travel_cost(),
                 }),
                 _ => None,
             })
@@ -91,6 +105,7 @@ impl Link for Lane {
 
     fn after_route_forgotten(&mut self, forgotten_route: Location, world: &mut World) {
         let self_as_rough_location = self.id_as();
+        let self_as_lane_like = self.id_as();
 
         self.microtraffic.cars.retain(|car| {
             let car_was_going_there = if forgotten_route.is_landmark() {
@@ -104,6 +119,7 @@ impl Link for Lane {
                     TripResult {
                         location_now: Some(self_as_rough_location),
                         fate: TripFate::RouteForgotten,
+                        last_lane: Some(self_as_lane_like),
                     },
                     world,
                 );
@@ -115,6 +131,17 @@ impl Link for Lane {
     }
 }
 
+impl Lane {
+    /// The length of the lane, stretched by how congested it is
+    fn travel_cost(&self) -> f32 {
+        if self.microtraffic.is_closed() {
+            CLOSED_LANE_COST
+        } else {
+            self.construction.length * self.microtraffic.congestion
+        }
+    }
+}
+
 pub fn on_unbuild(lane: &Lane, world: &mut World) {
     for attachee in &lane.pathfinding.attachees {
         attachee.location_changed(lane.pathfinding.location, None, world);
@@ -135,6 +162,9 @@ impl RoughLocation for Lane {
 
 const LANE_CHANGE_COST_LEFT: f32 = 5.0;
 const LANE_CHANGE_COST_RIGHT: f32 = 3.0;
+/// Practically infinite, but routes through closed lanes are still kept,
+/// so cars only get stuck in front of a closure if there is no way around it
+pub const CLOSED_LANE_COST: f32 = 1_000_000.0;
 
 mod kay_auto;
 pub use self::kay_auto::*;
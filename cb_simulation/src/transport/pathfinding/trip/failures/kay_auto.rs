@@ -0,0 +1,191 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct FailedTripListenerID {
+    _raw_id: RawID
+}
+
+impl Copy for FailedTripListenerID {}
+impl Clone for FailedTripListenerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for FailedTripListenerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "FailedTripListenerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for FailedTripListenerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for FailedTripListenerID {
+    fn eq(&self, other: &FailedTripListenerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for FailedTripListenerID {}
+
+pub struct FailedTripListenerRepresentative;
+
+impl ActorOrActorTrait for FailedTripListenerRepresentative {
+    type ID = FailedTripListenerID;
+}
+
+impl TypedID for FailedTripListenerID {
+    type Target = FailedTripListenerRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        FailedTripListenerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + FailedTripListener> TraitIDFrom<Act> for FailedTripListenerID {}
+
+impl FailedTripListenerID {
+    pub fn on_failed_trips(self, enabled: bool, n_failures: u32, groups: CVec < FailureGroup >, world: &mut World) {
+        world.send(self.as_raw(), MSG_FailedTripListener_on_failed_trips(enabled, n_failures, groups));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<FailedTripListenerRepresentative>();
+        system.register_trait_message::<MSG_FailedTripListener_on_failed_trips>();
+    }
+
+    pub fn register_implementor<Act: Actor + FailedTripListener>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, FailedTripListenerRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_FailedTripListener_on_failed_trips(enabled, n_failures, ref groups), instance, world| {
+                instance.on_failed_trips(enabled, n_failures, groups, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripListener_on_failed_trips(pub bool, pub u32, pub CVec < FailureGroup >);
+
+impl Actor for FailedTripReporter {
+    type ID = FailedTripReporterID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct FailedTripReporterID {
+    _raw_id: RawID
+}
+
+impl Copy for FailedTripReporterID {}
+impl Clone for FailedTripReporterID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for FailedTripReporterID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "FailedTripReporterID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for FailedTripReporterID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for FailedTripReporterID {
+    fn eq(&self, other: &FailedTripReporterID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for FailedTripReporterID {}
+
+impl TypedID for FailedTripReporterID {
+    type Target = FailedTripReporter;
+
+    fn from_raw(id: RawID) -> Self {
+        FailedTripReporterID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl FailedTripReporterID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = FailedTripReporterID::from_raw(world.allocate_instance_id::<FailedTripReporter>());
+        let swarm = world.local_broadcast::<FailedTripReporter>();
+        world.send(swarm, MSG_FailedTripReporter_spawn(id, ));
+        id
+    }
+
+    pub fn set_enabled(self, enabled: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_FailedTripReporter_set_enabled(enabled));
+    }
+
+    pub fn clear(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_FailedTripReporter_clear());
+    }
+
+    pub fn report(self, failed_trip: FailedTrip, world: &mut World) {
+        world.send(self.as_raw(), MSG_FailedTripReporter_report(failed_trip));
+    }
+
+    pub fn get_failures(self, requester: FailedTripListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_FailedTripReporter_get_failures(requester));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripReporter_spawn(pub FailedTripReporterID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripReporter_set_enabled(pub bool);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripReporter_clear();
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripReporter_report(pub FailedTrip);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_FailedTripReporter_get_failures(pub FailedTripListenerID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    FailedTripListenerID::register_trait(system);
+    system.add_spawner::<FailedTripReporter, _, _>(
+        |&MSG_FailedTripReporter_spawn(id, ), world| {
+            FailedTripReporter::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<FailedTripReporter, _, _>(
+        |&MSG_FailedTripReporter_set_enabled(enabled), instance, world| {
+            instance.set_enabled(enabled, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<FailedTripReporter, _, _>(
+        |&MSG_FailedTripReporter_clear(), instance, world| {
+            instance.clear(world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<FailedTripReporter, _, _>(
+        |&MSG_FailedTripReporter_report(failed_trip), instance, world| {
+            instance.report(failed_trip, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<FailedTripReporter, _, _>(
+        |&MSG_FailedTripReporter_get_failures(requester), instance, world| {
+            instance.get_failures(requester, world); Fate::Live
+        }, false
+    );
+}
@@ -0,0 +1,127 @@
+//! Reports failed trips, grouped by why and where they failed.
+//! Reporting can be switched on and off while the simulation is running.
+
+use kay::{ActorSystem, World, RawID, TypedID};
+use compact::CVec;
+use descartes::P2;
+
+use transport::pathfinding::{RoughLocationID, PreciseLocation};
+use transport::microtraffic::LaneLikeID;
+use super::{TripID, TripFate};
+
+/// Further failure groups are only counted in total
+const MAX_GROUPS: usize = 500;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct FailedTrip {
+    pub trip: TripID,
+    pub fate: TripFate,
+    pub rough_source: RoughLocationID,
+    pub rough_destination: RoughLocationID,
+    pub source: Option<PreciseLocation>,
+    pub destination: Option<PreciseLocation>,
+    pub source_position: Option<P2>,
+    pub destination_position: Option<P2>,
+    pub last_lane: Option<LaneLikeID>,
+}
+
+impl FailedTrip {
+    /// The last lane reached, or the source for trips that never got onto the road
+    fn location(&self) -> RawID {
+        self.last_lane
+            .map(|lane| lane.as_raw())
+            .unwrap_or_else(|| self.rough_source.as_raw())
+    }
+}
+
+/// All failures with the same fate at the same location
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct FailureGroup {
+    pub fate: TripFate,
+    pub location: RawID,
+    pub n_failures: u32,
+    pub latest: FailedTrip,
+}
+
+pub trait FailedTripListener {
+    fn on_failed_trips(
+        &mut self,
+        enabled: bool,
+        n_failures: u32,
+        groups: &CVec<FailureGroup>,
+        world: &mut World,
+    );
+}
+
+#[derive(Compact, Clone)]
+pub struct FailedTripReporter {
+    id: FailedTripReporterID,
+    enabled: bool,
+    n_failures: u32,
+    groups: CVec<FailureGroup>,
+}
+
+impl FailedTripReporter {
+    pub fn spawn(id: FailedTripReporterID, _: &mut World) -> FailedTripReporter {
+        FailedTripReporter {
+            id,
+            enabled: false,
+            n_failures: 0,
+            groups: CVec::new(),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, _: &mut World) {
+        self.enabled = enabled;
+    }
+
+    pub fn clear(&mut self, _: &mut World) {
+        self.n_failures = 0;
+        self.groups = CVec::new();
+    }
+
+    pub fn report(&mut self, failed_trip: FailedTrip, _: &mut World) {
+        if !self.enabled {
+            return;
+        }
+        self.n_failures += 1;
+
+        let location = failed_trip.location();
+        let maybe_group_idx = self
+            .groups
+            .iter()
+            .position(|group| group.fate == failed_trip.fate && group.location == location);
+
+        if let Some(group_idx) = maybe_group_idx {
+            let group = &mut self.groups[group_idx];
+            group.n_failures += 1;
+            group.latest = failed_trip;
+        } else if self.groups.len() < MAX_GROUPS {
+            self.groups.push(FailureGroup {
+                fate: failed_trip.fate,
+                location,
+                n_failures: 1,
+                latest: failed_trip,
+            });
+        }
+    }
+
+    /// Sorted by the number of failures, most frequent first
+    pub fn get_failures(&mut self, requester: FailedTripListenerID, world: &mut World) {
+        let mut sorted_groups = self.groups.to_vec();
+        sorted_groups.sort_by_key(|group| ::std::cmp::Reverse(group.n_failures));
+        requester.on_failed_trips(self.enabled, self.n_failures, sorted_groups.into(), world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<FailedTripReporter>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    FailedTripReporterID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
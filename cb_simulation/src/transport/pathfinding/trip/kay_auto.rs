@@ -133,23 +133,77 @@ impl TypedID for TripID {
 }
 
 impl TripID {
-    pub fn spawn(rough_source: RoughLocationID, rough_destination: RoughLocationID, listener: Option < TripListenerID >, instant: Instant, world: &mut World) -> Self {
+    pub fn spawn(rough_source: RoughLocationID, rough_destination: RoughLocationID, listener: Option < TripListenerID >, mode: ModeChoice, vehicle_type: VehicleType, instant: Instant, world: &mut World) -> Self {
         let id = TripID::from_raw(world.allocate_instance_id::<Trip>());
         let swarm = world.local_broadcast::<Trip>();
-        world.send(swarm, MSG_Trip_spawn(id, rough_source, rough_destination, listener, instant));
+        world.send(swarm, MSG_Trip_spawn(id, rough_source, rough_destination, listener, mode, vehicle_type, instant));
         id
     }
     
+    pub fn driver_assigned(self, driver: DriverBehaviour, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_driver_assigned(driver));
+    }
+    
+    pub fn walking_route_found(self, maybe_distance: Option < f32 >, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_walking_route_found(maybe_distance));
+    }
+    
+    pub fn transit_connection_found(self, maybe_connection: Option < (TransitStopID, TransitStopID) >, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_transit_connection_found(maybe_connection));
+    }
+    
+    pub fn alighted(self, stop_position: P2, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_alighted(stop_position));
+    }
+    
+    pub fn car_taken(self, has_car: bool, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_car_taken(has_car, instant));
+    }
+    
+    pub fn arrive(self, lane: LaneID, position: f32, travelled: f32, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_arrive(lane, position, travelled, instant));
+    }
+    
+    pub fn parking_found(self, spot: ParkingSpot, maybe_cruise_to: Option < PreciseLocation >, instant: Instant, world: &mut World) {
+        world.send(self.as_raw(), MSG_Trip_parking_found(spot, maybe_cruise_to, instant));
+    }
+    
     pub fn finish(self, result: TripResult, world: &mut World) {
         world.send(self.as_raw(), MSG_Trip_finish(result));
     }
 }
 
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_Trip_spawn(pub TripID, pub RoughLocationID, pub RoughLocationID, pub Option < TripListenerID >, pub Instant);
+struct MSG_Trip_spawn(pub TripID, pub RoughLocationID, pub RoughLocationID, pub Option < TripListenerID >, pub ModeChoice, pub VehicleType, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_driver_assigned(pub DriverBehaviour);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_walking_route_found(pub Option < f32 >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_transit_connection_found(pub Option < (TransitStopID, TransitStopID) >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_alighted(pub P2);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_car_taken(pub bool, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_arrive(pub LaneID, pub f32, pub f32, pub Instant);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Trip_parking_found(pub ParkingSpot, pub Option < PreciseLocation >, pub Instant);
 #[derive(Compact, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Trip_finish(pub TripResult);
 
+impl Into<PositionRequesterID> for TripID {
+    fn into(self) -> PositionRequesterID {
+        PositionRequesterID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<SleeperID> for TripID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
 impl Into<LocationRequesterID> for TripID {
     fn into(self) -> LocationRequesterID {
         LocationRequesterID::from_raw(self.as_raw())
@@ -236,86 +290,58 @@ impl LaneID {
 #[derive(Copy, Clone)] #[allow(non_camel_case_types)]
 struct MSG_Lane_manually_spawn_car_add_lane();
 
-
-impl Actor for FailedTripDebugger {
-    type ID = FailedTripDebuggerID;
-
-    fn id(&self) -> Self::ID {
-        self.id
-    }
-    unsafe fn set_id(&mut self, id: RawID) {
-        self.id = Self::ID::from_raw(id);
-    }
-}
-
-#[derive(Serialize, Deserialize)] #[serde(transparent)]
-pub struct FailedTripDebuggerID {
-    _raw_id: RawID
-}
-
-impl Copy for FailedTripDebuggerID {}
-impl Clone for FailedTripDebuggerID { fn clone(&self) -> Self { *self } }
-impl ::std::fmt::Debug for FailedTripDebuggerID {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-        write!(f, "FailedTripDebuggerID({:?})", self._raw_id)
-    }
-}
-impl ::std::hash::Hash for FailedTripDebuggerID {
-    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
-        self._raw_id.hash(state);
-    }
-}
-impl PartialEq for FailedTripDebuggerID {
-    fn eq(&self, other: &FailedTripDebuggerID) -> bool {
-        self._raw_id == other._raw_id
-    }
-}
-impl Eq for FailedTripDebuggerID {}
-
-impl TypedID for FailedTripDebuggerID {
-    type Target = FailedTripDebugger;
-
-    fn from_raw(id: RawID) -> Self {
-        FailedTripDebuggerID { _raw_id: id }
-    }
-
-    fn as_raw(&self) -> RawID {
-        self._raw_id
-    }
-}
-
-impl FailedTripDebuggerID {
-    pub fn spawn(rough_source: RoughLocationID, rough_destination: RoughLocationID, world: &mut World) -> Self {
-        let id = FailedTripDebuggerID::from_raw(world.allocate_instance_id::<FailedTripDebugger>());
-        let swarm = world.local_broadcast::<FailedTripDebugger>();
-        world.send(swarm, MSG_FailedTripDebugger_spawn(id, rough_source, rough_destination));
-        id
-    }
-    
-    pub fn done(self, world: &mut World) {
-        world.send(self.as_raw(), MSG_FailedTripDebugger_done());
-    }
-}
-
-#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
-struct MSG_FailedTripDebugger_spawn(pub FailedTripDebuggerID, pub RoughLocationID, pub RoughLocationID);
-#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
-struct MSG_FailedTripDebugger_done();
-
-impl Into<PositionRequesterID> for FailedTripDebuggerID {
-    fn into(self) -> PositionRequesterID {
-        PositionRequesterID::from_raw(self.as_raw())
-    }
-}
-
 #[allow(unused_variables)]
 #[allow(unused_mut)]
 pub fn auto_setup(system: &mut ActorSystem) {
     TripListenerID::register_trait(system);
+    PositionRequesterID::register_implementor::<Trip>(system);
+    SleeperID::register_implementor::<Trip>(system);
     LocationRequesterID::register_implementor::<Trip>(system);
     system.add_spawner::<Trip, _, _>(
-        |&MSG_Trip_spawn(id, rough_source, rough_destination, listener, instant), world| {
-            Trip::spawn(id, rough_source, rough_destination, listener, instant, world)
+        |&MSG_Trip_spawn(id, rough_source, rough_destination, listener, mode, vehicle_type, instant), world| {
+            Trip::spawn(id, rough_source, rough_destination, listener, mode, vehicle_type, instant, world)
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_driver_assigned(driver), instance, world| {
+            instance.driver_assigned(driver, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_walking_route_found(maybe_distance), instance, world| {
+            instance.walking_route_found(maybe_distance, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_transit_connection_found(maybe_connection), instance, world| {
+            instance.transit_connection_found(maybe_connection, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_alighted(stop_position), instance, world| {
+            instance.alighted(stop_position, world); Fate::Live
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_car_taken(has_car, instant), instance, world| {
+            instance.car_taken(has_car, instant, world)
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_arrive(lane, position, travelled, instant), instance, world| {
+            instance.arrive(lane, position, travelled, instant, world)
+        }, false
+    );
+    
+    system.add_handler::<Trip, _, _>(
+        |&MSG_Trip_parking_found(spot, maybe_cruise_to, instant), instance, world| {
+            instance.parking_found(spot, maybe_cruise_to, instant, world)
         }, false
     );
     
@@ -342,16 +368,4 @@ pub fn auto_setup(system: &mut ActorSystem) {
             instance.manually_spawn_car_add_lane(world); Fate::Live
         }, false
     );
-    PositionRequesterID::register_implementor::<FailedTripDebugger>(system);
-    system.add_spawner::<FailedTripDebugger, _, _>(
-        |&MSG_FailedTripDebugger_spawn(id, rough_source, rough_destination), world| {
-            FailedTripDebugger::spawn(id, rough_source, rough_destination, world)
-        }, false
-    );
-    
-    system.add_handler::<FailedTripDebugger, _, _>(
-        |&MSG_FailedTripDebugger_done(), instance, world| {
-            instance.done(world)
-        }, false
-    );
 }
\ No newline at end of file
@@ -4,11 +4,19 @@ use ordered_float::OrderedFloat;
 use cb_time::units::Instant;
 
 use transport::lane::LaneID;
+use transport::parking::ParkingSpot;
+use transport::pedestrians::PedestrianNetworkID;
+use transport::transit::{TransitNetworkID, TransitStopID};
 use super::{PreciseLocation, RoughLocationID, LocationRequester, LocationRequesterID};
 
 use itertools::Itertools;
 use super::super::lane::Lane;
 
+pub mod statistics;
+use self::statistics::{TripStatisticsID, TripRecord};
+pub mod failures;
+use self::failures::{FailedTripReporterID, FailedTrip};
+
 use cb_util::log::{debug, warn};
 const LOG_T: &str = "Trips";
 
@@ -20,6 +28,40 @@ pub struct Trip {
     source: Option<PreciseLocation>,
     destination: Option<PreciseLocation>,
     listener: Option<TripListenerID>,
+    parking: TripParking,
+    mode: ModeChoice,
+    /// What drives, if the trip is driven
+    vehicle_type: VehicleType,
+    driver: DriverBehaviour,
+    started_at: Instant,
+    source_position: Option<P2>,
+    destination_position: Option<P2>,
+    /// Along roads and sidewalks so far, in m
+    distance_travelled: f32,
+}
+
+/// How a trip gets from its source to its destination
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ModeChoice {
+    Drive,
+    Walk,
+    /// Walks if the destination is close enough, otherwise rides
+    /// transit if a line connects both places, and drives if not
+    ByDistance,
+}
+
+/// In m/s
+const WALKING_SPEED: f32 = 1.4;
+/// Straight-line distance up to which trips choosing by distance walk
+const MAX_WALKING_DISTANCE: f32 = 800.0;
+
+#[derive(Copy, Clone, Debug)]
+enum TripParking {
+    Driving,
+    /// Arrived at the destination and waiting to hear where to park
+    Searching(LaneID, f32),
+    /// Looking for parking led elsewhere, the car is driving there
+    CruisingTo(ParkingSpot),
 }
 
 #[derive(Copy, Clone)]
@@ -27,9 +69,11 @@ pub struct TripResult {
     pub location_now: Option<RoughLocationID>,
     //pub instant: Instant,
     pub fate: TripFate,
+    /// The lane the car was on when the trip ended, if it was driving
+    pub last_lane: Option<LaneLikeID>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum TripFate {
     Success(Instant),
     SourceOrDestinationNotResolvable,
@@ -38,20 +82,29 @@ pub enum TripFate {
     HopDisconnected,
     LaneUnbuilt,
     ForceStopped,
+    /// No car was parked for the building the trip started from
+    NoCar,
 }
 
-const DEBUG_FAILED_TRIPS_VISUALLY: bool = false;
-
 impl Trip {
     pub fn spawn(
         id: TripID,
         rough_source: RoughLocationID,
         rough_destination: RoughLocationID,
         listener: Option<TripListenerID>,
+        mode: ModeChoice,
+        vehicle_type: VehicleType,
         instant: Instant,
         world: &mut World,
     ) -> Self {
-        rough_source.resolve_as_location(id.into(), rough_source, instant, world);
+        // also for walking trips, which may end up driven
+        DriverBehaviourSettingsID::global_first(world).assign_driver(id, world);
+        if mode == ModeChoice::Drive {
+            rough_source.take_car(id, instant, world);
+        }
+        // driving trips only need the positions for their statistics
+        rough_source.resolve_as_position(id.into(), rough_source, world);
+        rough_destination.resolve_as_position(id.into(), rough_destination, world);
 
         if let Some(listener) = listener {
             listener.trip_created(id, world);
@@ -64,9 +117,173 @@ impl Trip {
             listener,
             source: None,
             destination: None,
+            parking: TripParking::Driving,
+            mode,
+            vehicle_type,
+            // until the settings assign the actual driver
+            driver: DriverBehaviour::default(),
+            started_at: instant,
+            source_position: None,
+            destination_position: None,
+            distance_travelled: 0.0,
+        }
+    }
+
+    pub fn driver_assigned(&mut self, driver: DriverBehaviour, _: &mut World) {
+        self.driver = driver;
+    }
+
+    /// Walking trips without a route along sidewalks are driven instead
+    pub fn walking_route_found(&mut self, maybe_distance: Option<f32>, world: &mut World) {
+        if let Some(distance) = maybe_distance {
+            self.distance_travelled += distance;
+            let duration = Duration::from_seconds((distance / WALKING_SPEED) as usize);
+            TimeID::local_first(world).wake_up_in(Ticks::from(duration), self.id_as(), world);
+        } else {
+            debug(
+                LOG_T,
+                format!("Trip {:?} found no walking route, driving", self.id),
+                self.id(),
+                world,
+            );
+            self.mode = ModeChoice::Drive;
+            self.rough_source.take_car(self.id, self.started_at, world);
+        }
+    }
+
+    pub fn transit_connection_found(
+        &mut self,
+        maybe_connection: Option<(TransitStopID, TransitStopID)>,
+        world: &mut World,
+    ) {
+        if let Some((boarding_stop, alighting_stop)) = maybe_connection {
+            boarding_stop.wait_for_bus(self.id, alighting_stop, world);
+        } else {
+            self.mode = ModeChoice::Drive;
+            self.rough_source.take_car(self.id, self.started_at, world);
+        }
+    }
+
+    /// Walks the rest of the way from the stop
+    pub fn alighted(&mut self, stop_position: P2, world: &mut World) {
+        let distance = self
+            .destination_position
+            .map(|destination_position| (destination_position - stop_position).norm())
+            .unwrap_or(0.0);
+        // the ride itself is counted as the straight line from the source to the stop
+        let ride_distance = self
+            .source_position
+            .map(|source_position| (stop_position - source_position).norm())
+            .unwrap_or(0.0);
+        self.distance_travelled += ride_distance + distance;
+        let duration = Duration::from_seconds((distance / WALKING_SPEED) as usize);
+        TimeID::local_first(world).wake_up_in(Ticks::from(duration), self.id_as(), world);
+    }
+
+    pub fn car_taken(&mut self, has_car: bool, instant: Instant, world: &mut World) -> Fate {
+        if has_car {
+            self.rough_source.resolve_as_location(
+                self.id_as(),
+                self.rough_source,
+                instant,
+                world,
+            );
+            Fate::Live
+        } else {
+            self.finish(
+                TripResult {
+                    location_now: Some(self.rough_source),
+                    fate: TripFate::NoCar,
+                    last_lane: None,
+                },
+                world,
+            )
+        }
+    }
+
+    pub fn arrive(
+        &mut self,
+        lane: LaneID,
+        position: f32,
+        travelled: f32,
+        instant: Instant,
+        world: &mut World,
+    ) -> Fate {
+        self.distance_travelled += travelled;
+
+        match self.parking {
+            TripParking::Driving => {
+                self.parking = TripParking::Searching(lane, position);
+                self.rough_destination.find_parking(self.id, lane, instant, world);
+                Fate::Live
+            }
+            TripParking::CruisingTo(spot) => self.finish_parked(spot, instant, world),
+            TripParking::Searching(..) => unreachable!("Car arrived while searching for parking"),
         }
     }
 
+    pub fn parking_found(
+        &mut self,
+        spot: ParkingSpot,
+        maybe_cruise_to: Option<PreciseLocation>,
+        instant: Instant,
+        world: &mut World,
+    ) -> Fate {
+        if let (TripParking::Searching(lane, position), Some(cruise_to)) =
+            (self.parking, maybe_cruise_to)
+        {
+            if spot != ParkingSpot::OnStreet(lane) {
+                self.parking = TripParking::CruisingTo(spot);
+                let lane_as_lane_like: LaneLikeID = lane.into();
+                lane_as_lane_like.add_car(
+                    LaneCar {
+                        trip: self.id,
+                        as_obstacle: Obstacle {
+                            position: OrderedFloat(position),
+                            velocity: 0.0,
+                            max_velocity: self.vehicle_type.parameters().max_velocity,
+                            length: self.vehicle_type.parameters().length,
+                        },
+                        acceleration: 0.0,
+                        destination: cruise_to,
+                        next_hop_interaction: None,
+                        has_stopped: false,
+                        travelled: 0.0,
+                        vehicle_type: self.vehicle_type,
+                        keeps_lane: false,
+                        lane_change_cooldown: 0.0,
+                        driver: self.driver,
+                        perceptions: Perceptions::default(),
+                        dwelling_until: None,
+                    },
+                    None,
+                    instant,
+                    world,
+                );
+                return Fate::Live;
+            }
+        }
+
+        self.finish_parked(spot, instant, world)
+    }
+
+    fn finish_parked(&mut self, spot: ParkingSpot, instant: Instant, world: &mut World) -> Fate {
+        debug(
+            LOG_T,
+            format!("Trip {:?} parked: {:?}", self.id, spot),
+            self.id(),
+            world,
+        );
+        self.finish(
+            TripResult {
+                location_now: None,
+                fate: TripFate::Success(instant),
+                last_lane: None,
+            },
+            world,
+        )
+    }
+
     pub fn finish(&mut self, result: TripResult, world: &mut World) -> Fate {
         match result.fate {
             TripFate::Success(_) | TripFate::ForceStopped => {}
@@ -85,12 +302,43 @@ impl Trip {
                     self.id(),
                     world,
                 );
-                if DEBUG_FAILED_TRIPS_VISUALLY {
-                    FailedTripDebuggerID::spawn(self.rough_source, self.rough_destination, world);
-                }
+                FailedTripReporterID::global_first(world).report(
+                    FailedTrip {
+                        trip: self.id,
+                        fate: reason,
+                        rough_source: self.rough_source,
+                        rough_destination: self.rough_destination,
+                        source: self.source,
+                        destination: self.destination,
+                        source_position: self.source_position,
+                        destination_position: self.destination_position,
+                        last_lane: result.last_lane,
+                    },
+                    world,
+                );
             }
         }
 
+        TripStatisticsID::global_first(world).record(
+            TripRecord {
+                trip: self.id,
+                source: self.rough_source,
+                destination: self.rough_destination,
+                source_position: self.source_position,
+                destination_position: self.destination_position,
+                mode: self.mode,
+                vehicle_type: self.vehicle_type,
+                started_at: self.started_at,
+                ended_at: match result.fate {
+                    TripFate::Success(instant) => Some(instant),
+                    _ => None,
+                },
+                fate: result.fate,
+                distance: self.distance_travelled,
+            },
+            world,
+        );
+
         if let Some(listener) = self.listener {
             listener.trip_result(
                 self.id,
@@ -142,11 +390,20 @@ impl LocationRequester for Trip {
                         as_obstacle: Obstacle {
                             position: OrderedFloat(source.offset),
                             velocity: 0.0,
-                            max_velocity: 8.0,
+                            max_velocity: self.vehicle_type.parameters().max_velocity,
+                            length: self.vehicle_type.parameters().length,
                         },
                         acceleration: 0.0,
                         destination,
                         next_hop_interaction: None,
+                        has_stopped: false,
+                        travelled: 0.0,
+                        vehicle_type: self.vehicle_type,
+                        keeps_lane: false,
+                        lane_change_cooldown: 0.0,
+                        driver: self.driver,
+                        perceptions: Perceptions::default(),
+                        dwelling_until: None,
                     },
                     None,
                     instant,
@@ -167,6 +424,7 @@ impl LocationRequester for Trip {
                 TripResult {
                     location_now: Some(self.rough_source),
                     fate: TripFate::SourceOrDestinationNotResolvable,
+                    last_lane: None,
                 },
                 world,
             );
@@ -175,8 +433,70 @@ impl LocationRequester for Trip {
 }
 
 use cb_time::actors::{TimeID, Sleeper, SleeperID};
-use cb_time::units::Ticks;
-use super::super::microtraffic::{LaneLikeID, LaneCar, Obstacle};
+use cb_time::units::{Ticks, Duration};
+use super::super::microtraffic::{LaneLikeID, LaneCar, Obstacle, VehicleType};
+use super::super::microtraffic::driver_behaviour::{DriverBehaviour, Perceptions,
+DriverBehaviourSettingsID};
+
+impl PositionRequester for Trip {
+    fn position_resolved(
+        &mut self,
+        rough_location: RoughLocationID,
+        position: P2,
+        world: &mut World,
+    ) {
+        let already_resolved =
+            self.source_position.is_some() && self.destination_position.is_some();
+
+        if rough_location == self.rough_source {
+            self.source_position = Some(position);
+        }
+        if rough_location == self.rough_destination {
+            self.destination_position = Some(position);
+        }
+
+        if self.mode == ModeChoice::Drive {
+            return;
+        }
+
+        if let (false, Some(source_position), Some(destination_position)) = (
+            already_resolved,
+            self.source_position,
+            self.destination_position,
+        ) {
+            let distance = (destination_position - source_position).norm();
+            if self.mode == ModeChoice::Walk || distance < MAX_WALKING_DISTANCE {
+                PedestrianNetworkID::global_first(world).find_walking_route(
+                    source_position,
+                    destination_position,
+                    self.id,
+                    world,
+                );
+            } else {
+                TransitNetworkID::global_first(world).find_connection(
+                    source_position,
+                    destination_position,
+                    self.id,
+                    world,
+                );
+            }
+        }
+    }
+}
+
+/// Walking trips, and transit trips after getting off, sleep for as long as the walk takes
+impl Sleeper for Trip {
+    fn wake(&mut self, current_instant: Instant, world: &mut World) {
+        self.id.finish(
+            TripResult {
+                location_now: None,
+                fate: TripFate::Success(current_instant),
+                last_lane: None,
+            },
+            world,
+        );
+    }
+}
 
 pub trait TripListener {
     fn trip_created(&mut self, trip: TripID, world: &mut World);
@@ -221,12 +541,24 @@ impl Sleeper for TripCreator {
     fn wake(&mut self, current_instant: Instant, world: &mut World) {
         ::rand::thread_rng().shuffle(&mut self.lanes);
 
+        // a mix of vehicles, to see all of them on the road
+        let vehicle_types = [
+            VehicleType::Car,
+            VehicleType::Car,
+            VehicleType::Car,
+            VehicleType::Truck,
+            VehicleType::Bus,
+            VehicleType::Bicycle,
+        ];
+
         for mut pair in &self.lanes.iter().chunks(2) {
             if let (Some(source), Some(dest)) = (pair.next(), pair.next()) {
                 TripID::spawn(
                     (*source).into(),
                     (*dest).into(),
                     None,
+                    ModeChoice::Drive,
+                    *::rand::thread_rng().choose(&vehicle_types).expect("not empty"),
                     current_instant,
                     world,
                 );
@@ -251,69 +583,18 @@ impl Lane {
 use super::{PositionRequester, PositionRequesterID};
 use descartes::{P2};
 
-#[derive(Compact, Clone)]
-pub struct FailedTripDebugger {
-    id: FailedTripDebuggerID,
-    rough_source: RoughLocationID,
-    source_position: Option<P2>,
-    rough_destination: RoughLocationID,
-    destination_position: Option<P2>,
-}
-
-impl FailedTripDebugger {
-    pub fn spawn(
-        id: FailedTripDebuggerID,
-        rough_source: RoughLocationID,
-        rough_destination: RoughLocationID,
-        world: &mut World,
-    ) -> Self {
-        rough_source.resolve_as_position(id.into(), rough_source, world);
-        rough_destination.resolve_as_position(id.into(), rough_destination, world);
-        FailedTripDebugger {
-            id,
-            rough_source,
-            source_position: None,
-            rough_destination,
-            destination_position: None,
-        }
-    }
-
-    pub fn done(&mut self, _: &mut World) -> ::kay::Fate {
-        ::kay::Fate::Die
-    }
-}
-
-impl PositionRequester for FailedTripDebugger {
-    fn position_resolved(
-        &mut self,
-        rough_location: RoughLocationID,
-        position: P2,
-        world: &mut World,
-    ) {
-        if rough_location == self.rough_source {
-            self.source_position = Some(position);
-        } else {
-            self.destination_position = Some(position);
-        }
-
-        if let (Some(_source_position), Some(_destination_position)) =
-            (self.source_position, self.destination_position)
-        {
-            // TODO: render this again
-            self.id.done(world);
-        }
-    }
-}
-
 pub fn setup(system: &mut ActorSystem) {
     system.register::<Trip>();
     system.register::<TripCreator>();
-    system.register::<FailedTripDebugger>();
     auto_setup(system);
+    statistics::setup(system);
+    failures::setup(system);
 }
 
 pub fn spawn(world: &mut World, time: TimeID) {
     TripCreatorID::spawn(time, world);
+    statistics::spawn(world);
+    failures::spawn(world);
 }
 
 mod kay_auto;
@@ -0,0 +1,211 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TripStatisticsListenerID {
+    _raw_id: RawID
+}
+
+impl Copy for TripStatisticsListenerID {}
+impl Clone for TripStatisticsListenerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TripStatisticsListenerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TripStatisticsListenerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TripStatisticsListenerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TripStatisticsListenerID {
+    fn eq(&self, other: &TripStatisticsListenerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TripStatisticsListenerID {}
+
+pub struct TripStatisticsListenerRepresentative;
+
+impl ActorOrActorTrait for TripStatisticsListenerRepresentative {
+    type ID = TripStatisticsListenerID;
+}
+
+impl TypedID for TripStatisticsListenerID {
+    type Target = TripStatisticsListenerRepresentative;
+
+    fn from_raw(id: RawID) -> Self {
+        TripStatisticsListenerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl<Act: Actor + TripStatisticsListener> TraitIDFrom<Act> for TripStatisticsListenerID {}
+
+impl TripStatisticsListenerID {
+    pub fn on_trip_statistics(self, summary: TripSummary, od_matrix: CVec < ODEntry >, duration_histogram: CVec < u32 >, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatisticsListener_on_trip_statistics(summary, od_matrix, duration_histogram));
+    }
+
+    pub fn on_trip_statistics_csv(self, trips: CString, od_matrix: CString, duration_histogram: CString, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatisticsListener_on_trip_statistics_csv(trips, od_matrix, duration_histogram));
+    }
+
+    pub fn register_trait(system: &mut ActorSystem) {
+        system.register_trait::<TripStatisticsListenerRepresentative>();
+        system.register_trait_message::<MSG_TripStatisticsListener_on_trip_statistics>();
+        system.register_trait_message::<MSG_TripStatisticsListener_on_trip_statistics_csv>();
+    }
+
+    pub fn register_implementor<Act: Actor + TripStatisticsListener>(system: &mut ActorSystem) {
+        system.register_implementor::<Act, TripStatisticsListenerRepresentative>();
+        system.add_handler::<Act, _, _>(
+            |&MSG_TripStatisticsListener_on_trip_statistics(summary, ref od_matrix, ref duration_histogram), instance, world| {
+                instance.on_trip_statistics(summary, od_matrix, duration_histogram, world); Fate::Live
+            }, false
+        );
+
+        system.add_handler::<Act, _, _>(
+            |&MSG_TripStatisticsListener_on_trip_statistics_csv(ref trips, ref od_matrix, ref duration_histogram), instance, world| {
+                instance.on_trip_statistics_csv(trips, od_matrix, duration_histogram, world); Fate::Live
+            }, false
+        );
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatisticsListener_on_trip_statistics(pub TripSummary, pub CVec < ODEntry >, pub CVec < u32 >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatisticsListener_on_trip_statistics_csv(pub CString, pub CString, pub CString);
+
+impl Actor for TripStatistics {
+    type ID = TripStatisticsID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TripStatisticsID {
+    _raw_id: RawID
+}
+
+impl Copy for TripStatisticsID {}
+impl Clone for TripStatisticsID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TripStatisticsID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TripStatisticsID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TripStatisticsID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TripStatisticsID {
+    fn eq(&self, other: &TripStatisticsID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TripStatisticsID {}
+
+impl TypedID for TripStatisticsID {
+    type Target = TripStatistics;
+
+    fn from_raw(id: RawID) -> Self {
+        TripStatisticsID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TripStatisticsID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = TripStatisticsID::from_raw(world.allocate_instance_id::<TripStatistics>());
+        let swarm = world.local_broadcast::<TripStatistics>();
+        world.send(swarm, MSG_TripStatistics_spawn(id, ));
+        id
+    }
+
+    pub fn record(self, record: TripRecord, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatistics_record(record));
+    }
+
+    pub fn reset(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatistics_reset());
+    }
+
+    pub fn get_statistics(self, requester: TripStatisticsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatistics_get_statistics(requester));
+    }
+
+    pub fn export_csv(self, requester: TripStatisticsListenerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TripStatistics_export_csv(requester));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatistics_spawn(pub TripStatisticsID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatistics_record(pub TripRecord);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatistics_reset();
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatistics_get_statistics(pub TripStatisticsListenerID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TripStatistics_export_csv(pub TripStatisticsListenerID);
+
+impl Into<TemporalID> for TripStatisticsID {
+    fn into(self) -> TemporalID {
+        TemporalID::from_raw(self.as_raw())
+    }
+}
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    TripStatisticsListenerID::register_trait(system);
+    TemporalID::register_implementor::<TripStatistics>(system);
+    system.add_spawner::<TripStatistics, _, _>(
+        |&MSG_TripStatistics_spawn(id, ), world| {
+            TripStatistics::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<TripStatistics, _, _>(
+        |&MSG_TripStatistics_record(record), instance, world| {
+            instance.record(record, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TripStatistics, _, _>(
+        |&MSG_TripStatistics_reset(), instance, world| {
+            instance.reset(world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TripStatistics, _, _>(
+        |&MSG_TripStatistics_get_statistics(requester), instance, world| {
+            instance.get_statistics(requester, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TripStatistics, _, _>(
+        |&MSG_TripStatistics_export_csv(requester), instance, world| {
+            instance.export_csv(requester, world); Fate::Live
+        }, false
+    );
+}
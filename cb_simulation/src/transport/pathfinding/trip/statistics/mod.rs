@@ -0,0 +1,364 @@
+//! Records every finished trip and aggregates them into origin–destination matrices
+//! between square zones and into a histogram of trip durations, to compare how well
+//! the road network works before and after changing it. All of it can be exported as CSV.
+
+use kay::{ActorSystem, World, TypedID};
+use compact::{CVec, CString};
+use descartes::P2;
+use std::collections::HashMap;
+use std::fmt::Write;
+use cb_time::actors::{Temporal, TemporalID};
+use cb_time::units::{Instant, TICKS_PER_SIM_SECOND};
+
+use transport::pathfinding::RoughLocationID;
+use super::{TripID, TripFate, ModeChoice};
+use transport::microtraffic::VehicleType;
+
+use cb_util::log::info;
+const LOG_T: &str = "Trip Statistics";
+
+/// Side length of the square zones that trips are aggregated between, in m
+pub const ZONE_SIZE: f32 = 500.0;
+/// Width of one bin of the duration histogram, in minutes
+pub const HISTOGRAM_BIN_MINUTES: usize = 5;
+/// The last bin also counts all trips that took even longer
+pub const HISTOGRAM_N_BINS: usize = 24;
+/// Once this many trips are recorded, the oldest quarter of them is dropped
+const MAX_RECORDS: usize = 100_000;
+
+/// Index of a zone along x and y
+pub type Zone = (i32, i32);
+
+fn zone_of(position: P2) -> Zone {
+    (
+        (position.x / ZONE_SIZE).floor() as i32,
+        (position.y / ZONE_SIZE).floor() as i32,
+    )
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TripRecord {
+    pub trip: TripID,
+    pub source: RoughLocationID,
+    pub destination: RoughLocationID,
+    pub source_position: Option<P2>,
+    pub destination_position: Option<P2>,
+    pub mode: ModeChoice,
+    /// What drove, if the trip was driven
+    pub vehicle_type: VehicleType,
+    pub started_at: Instant,
+    /// Only successful trips know when they ended, the others end when they are recorded
+    pub ended_at: Option<Instant>,
+    pub fate: TripFate,
+    /// Distance covered along roads and sidewalks, in m
+    pub distance: f32,
+}
+
+impl TripRecord {
+    /// Buses driving from stop to stop and trucks delivering freight
+    /// are trips too, but nobody makes them
+    fn is_person_trip(&self) -> bool {
+        self.vehicle_type != VehicleType::Bus && self.vehicle_type != VehicleType::Truck
+    }
+
+    fn succeeded(&self) -> bool {
+        if let TripFate::Success(_) = self.fate {
+            true
+        } else {
+            false
+        }
+    }
+
+    /// In s
+    fn duration(&self) -> f32 {
+        let ended_at = self.ended_at.unwrap_or(self.started_at);
+        ended_at.ticks().saturating_sub(self.started_at.ticks()) as f32
+            / TICKS_PER_SIM_SECOND as f32
+    }
+
+    fn zones(&self) -> Option<(Zone, Zone)> {
+        match (self.source_position, self.destination_position) {
+            (Some(source_position), Some(destination_position)) => {
+                Some((zone_of(source_position), zone_of(destination_position)))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn fate_name(fate: TripFate) -> String {
+    match fate {
+        TripFate::Success(_) => "Success".to_owned(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// All trips between two zones
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct ODEntry {
+    pub origin: Zone,
+    pub destination: Zone,
+    pub n_trips: u32,
+    pub n_failed: u32,
+    /// Of successful trips, in s
+    pub mean_duration: f32,
+    /// Of successful trips, in m
+    pub mean_distance: f32,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TripSummary {
+    pub n_trips: u32,
+    pub n_successful: u32,
+    pub n_failed: u32,
+    /// Trips that ended before their source and destination were located,
+    /// which are missing from the origin–destination matrix
+    pub n_unlocated: u32,
+    /// Of successful trips, in s
+    pub mean_duration: f32,
+    /// Of successful trips, in m
+    pub mean_distance: f32,
+}
+
+pub trait TripStatisticsListener {
+    fn on_trip_statistics(
+        &mut self,
+        summary: TripSummary,
+        od_matrix: &CVec<ODEntry>,
+        duration_histogram: &CVec<u32>,
+        world: &mut World,
+    );
+    fn on_trip_statistics_csv(
+        &mut self,
+        trips: &CString,
+        od_matrix: &CString,
+        duration_histogram: &CString,
+        world: &mut World,
+    );
+}
+
+#[derive(Compact, Clone)]
+pub struct TripStatistics {
+    id: TripStatisticsID,
+    current_instant: Instant,
+    records: CVec<TripRecord>,
+}
+
+impl TripStatistics {
+    pub fn spawn(id: TripStatisticsID, _: &mut World) -> TripStatistics {
+        TripStatistics {
+            id,
+            current_instant: Instant::new(0),
+            records: CVec::new(),
+        }
+    }
+
+    pub fn record(&mut self, record: TripRecord, world: &mut World) {
+        if !record.is_person_trip() {
+            return;
+        }
+
+        if self.records.len() >= MAX_RECORDS {
+            let n_dropped = MAX_RECORDS / 4;
+            self.records = self.records[n_dropped..].to_vec().into();
+            info(
+                LOG_T,
+                format!("Dropped the oldest {} recorded trips", n_dropped),
+                self.id,
+                world,
+            );
+        }
+
+        self.records.push(TripRecord {
+            ended_at: record.ended_at.or(Some(self.current_instant)),
+            ..record
+        });
+    }
+
+    /// Forgets all trips recorded so far, for example right after changing the road network
+    pub fn reset(&mut self, _: &mut World) {
+        self.records = CVec::new();
+    }
+
+    pub fn get_statistics(&mut self, requester: TripStatisticsListenerID, world: &mut World) {
+        requester.on_trip_statistics(
+            self.summary(),
+            self.od_matrix(),
+            self.duration_histogram(),
+            world,
+        );
+    }
+
+    pub fn export_csv(&mut self, requester: TripStatisticsListenerID, world: &mut World) {
+        requester.on_trip_statistics_csv(
+            self.trips_csv().into(),
+            self.od_matrix_csv().into(),
+            self.duration_histogram_csv().into(),
+            world,
+        );
+    }
+
+    fn summary(&self) -> TripSummary {
+        let mut summary = TripSummary::default();
+
+        for record in self.records.iter() {
+            summary.n_trips += 1;
+            if record.zones().is_none() {
+                summary.n_unlocated += 1;
+            }
+            if record.succeeded() {
+                summary.n_successful += 1;
+                let n = summary.n_successful as f32;
+                summary.mean_duration += (record.duration() - summary.mean_duration) / n;
+                summary.mean_distance += (record.distance - summary.mean_distance) / n;
+            } else {
+                summary.n_failed += 1;
+            }
+        }
+
+        summary
+    }
+
+    /// Sorted by the number of trips, busiest pairs of zones first,
+    /// and by origin and destination among equally busy ones
+    fn od_matrix(&self) -> CVec<ODEntry> {
+        let mut entries: HashMap<(Zone, Zone), ODEntry> = HashMap::new();
+
+        for record in self.records.iter() {
+            if let Some((origin, destination)) = record.zones() {
+                let entry = entries
+                    .entry((origin, destination))
+                    .or_insert(ODEntry {
+                        origin,
+                        destination,
+                        n_trips: 0,
+                        n_failed: 0,
+                        mean_duration: 0.0,
+                        mean_distance: 0.0,
+                    });
+                entry.n_trips += 1;
+                if record.succeeded() {
+                    let n = (entry.n_trips - entry.n_failed) as f32;
+                    entry.mean_duration += (record.duration() - entry.mean_duration) / n;
+                    entry.mean_distance += (record.distance - entry.mean_distance) / n;
+                } else {
+                    entry.n_failed += 1;
+                }
+            }
+        }
+
+        let mut sorted_entries: Vec<ODEntry> = entries.values().cloned().collect();
+        sorted_entries.sort_by_key(|entry| {
+            (
+                ::std::cmp::Reverse(entry.n_trips),
+                entry.origin,
+                entry.destination,
+            )
+        });
+        sorted_entries.into()
+    }
+
+    /// Durations of successful trips
+    fn duration_histogram(&self) -> CVec<u32> {
+        let mut bins = vec![0; HISTOGRAM_N_BINS];
+
+        for record in self.records.iter().filter(|record| record.succeeded()) {
+            let bin = (record.duration() / 60.0) as usize / HISTOGRAM_BIN_MINUTES;
+            bins[bin.min(HISTOGRAM_N_BINS - 1)] += 1;
+        }
+
+        bins.into()
+    }
+
+    fn trips_csv(&self) -> String {
+        let mut csv = "trip,source,destination,source_x,source_y,destination_x,destination_y,\
+                       mode,started_at,ended_at,duration_s,fate,distance_m\n"
+            .to_owned();
+
+        let coordinates = |maybe_position: Option<P2>| {
+            maybe_position
+                .map(|position| format!("{},{}", position.x, position.y))
+                .unwrap_or_else(|| ",".to_owned())
+        };
+
+        for record in self.records.iter() {
+            writeln!(
+                csv,
+                "\"{:?}\",\"{:?}\",\"{:?}\",{},{},{:?},{},{},{},{},{}",
+                record.trip.as_raw(),
+                record.source.as_raw(),
+                record.destination.as_raw(),
+                coordinates(record.source_position),
+                coordinates(record.destination_position),
+                record.mode,
+                record.started_at.ticks(),
+                record.ended_at.unwrap_or(record.started_at).ticks(),
+                record.duration(),
+                fate_name(record.fate),
+                record.distance
+            )
+            .expect("writing to a string can't fail");
+        }
+
+        csv
+    }
+
+    fn od_matrix_csv(&self) -> String {
+        let mut csv = "origin_zone_x,origin_zone_y,destination_zone_x,destination_zone_y,\
+                       n_trips,n_failed,mean_duration_s,mean_distance_m\n"
+            .to_owned();
+
+        for entry in self.od_matrix().iter() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                entry.origin.0,
+                entry.origin.1,
+                entry.destination.0,
+                entry.destination.1,
+                entry.n_trips,
+                entry.n_failed,
+                entry.mean_duration,
+                entry.mean_distance
+            )
+            .expect("writing to a string can't fail");
+        }
+
+        csv
+    }
+
+    fn duration_histogram_csv(&self) -> String {
+        let mut csv = "from_min,to_min,n_trips\n".to_owned();
+
+        for (bin, count) in self.duration_histogram().iter().enumerate() {
+            let to = if bin == HISTOGRAM_N_BINS - 1 {
+                "".to_owned()
+            } else {
+                ((bin + 1) * HISTOGRAM_BIN_MINUTES).to_string()
+            };
+            writeln!(csv, "{},{},{}", bin * HISTOGRAM_BIN_MINUTES, to, count)
+                .expect("writing to a string can't fail");
+        }
+
+        csv
+    }
+}
+
+/// Keeps track of the time, to know when failed trips ended
+impl Temporal for TripStatistics {
+    fn tick(&mut self, _dt: f32, current_instant: Instant, _: &mut World) {
+        self.current_instant = current_instant;
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<TripStatistics>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    TripStatisticsID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
@@ -0,0 +1,184 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for Sidewalk {
+    type ID = SidewalkID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct SidewalkID {
+    _raw_id: RawID
+}
+
+impl Copy for SidewalkID {}
+impl Clone for SidewalkID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for SidewalkID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "SidewalkID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for SidewalkID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for SidewalkID {
+    fn eq(&self, other: &SidewalkID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for SidewalkID {}
+
+impl TypedID for SidewalkID {
+    type Target = Sidewalk;
+
+    fn from_raw(id: RawID) -> Self {
+        SidewalkID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl SidewalkID {
+    pub fn spawn(prototype: SidewalkPrototype, report_to: CBConstructionID, world: &mut World) -> Self {
+        let id = SidewalkID::from_raw(world.allocate_instance_id::<Sidewalk>());
+        let swarm = world.local_broadcast::<Sidewalk>();
+        world.send(swarm, MSG_Sidewalk_spawn(id, prototype, report_to));
+        id
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Sidewalk_spawn(pub SidewalkID, pub SidewalkPrototype, pub CBConstructionID);
+
+impl Into<ConstructableID<CBPrototypeKind>> for SidewalkID {
+    fn into(self) -> ConstructableID<CBPrototypeKind> {
+        ConstructableID::from_raw(self.as_raw())
+    }
+}
+impl Actor for PedestrianNetwork {
+    type ID = PedestrianNetworkID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct PedestrianNetworkID {
+    _raw_id: RawID
+}
+
+impl Copy for PedestrianNetworkID {}
+impl Clone for PedestrianNetworkID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for PedestrianNetworkID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "PedestrianNetworkID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for PedestrianNetworkID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for PedestrianNetworkID {
+    fn eq(&self, other: &PedestrianNetworkID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for PedestrianNetworkID {}
+
+impl TypedID for PedestrianNetworkID {
+    type Target = PedestrianNetwork;
+
+    fn from_raw(id: RawID) -> Self {
+        PedestrianNetworkID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl PedestrianNetworkID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = PedestrianNetworkID::from_raw(world.allocate_instance_id::<PedestrianNetwork>());
+        let swarm = world.local_broadcast::<PedestrianNetwork>();
+        world.send(swarm, MSG_PedestrianNetwork_spawn(id, ));
+        id
+    }
+
+    pub fn add_sidewalk(self, sidewalk: SidewalkID, prototype: SidewalkPrototype, world: &mut World) {
+        world.send(self.as_raw(), MSG_PedestrianNetwork_add_sidewalk(sidewalk, prototype));
+    }
+
+    pub fn remove_sidewalk(self, sidewalk: SidewalkID, world: &mut World) {
+        world.send(self.as_raw(), MSG_PedestrianNetwork_remove_sidewalk(sidewalk));
+    }
+
+    pub fn find_walking_route(self, from: P2, to: P2, trip: TripID, world: &mut World) {
+        world.send(self.as_raw(), MSG_PedestrianNetwork_find_walking_route(from, to, trip));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PedestrianNetwork_spawn(pub PedestrianNetworkID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PedestrianNetwork_add_sidewalk(pub SidewalkID, pub SidewalkPrototype);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PedestrianNetwork_remove_sidewalk(pub SidewalkID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_PedestrianNetwork_find_walking_route(pub P2, pub P2, pub TripID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    ConstructableID::<CBPrototypeKind>::register_implementor::<Sidewalk>(system);
+    system.add_spawner::<Sidewalk, _, _>(
+        |&MSG_Sidewalk_spawn(id, ref prototype, report_to), world| {
+            Sidewalk::spawn(id, prototype, report_to, world)
+        }, false
+    );
+
+    system.add_spawner::<PedestrianNetwork, _, _>(
+        |&MSG_PedestrianNetwork_spawn(id, ), world| {
+            PedestrianNetwork::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<PedestrianNetwork, _, _>(
+        |&MSG_PedestrianNetwork_add_sidewalk(sidewalk, ref prototype), instance, world| {
+            instance.add_sidewalk(sidewalk, prototype, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<PedestrianNetwork, _, _>(
+        |&MSG_PedestrianNetwork_remove_sidewalk(sidewalk), instance, world| {
+            instance.remove_sidewalk(sidewalk, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<PedestrianNetwork, _, _>(
+        |&MSG_PedestrianNetwork_find_walking_route(from, to, trip), instance, world| {
+            instance.find_walking_route(from, to, trip, world); Fate::Live
+        }, false
+    );
+}
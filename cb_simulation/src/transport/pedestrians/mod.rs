@@ -0,0 +1,340 @@
+//! Walking: sidewalks built along roads form a pedestrian network that is
+//! separate from the lanes, so walking trips never take up room on the road.
+
+use kay::{ActorSystem, World, Fate, Actor};
+use compact::{CVec, CHashMap};
+use descartes::{N, P2};
+use ordered_float::OrderedFloat;
+use std::collections::{BinaryHeap, HashMap};
+use std::cmp::Reverse;
+
+use cb_planning::Prototype;
+use cb_planning::construction::{Constructable, ConstructableID};
+use planning::{CBConstructionID, CBPrototypeKind};
+use super::pathfinding::trip::TripID;
+use super::transport_planning::{SidewalkPrototype, SidewalkEnd};
+
+/// Sidewalk ends closer than this are joined
+const JOIN_DISTANCE: N = 0.5;
+/// Sidewalk ends closer than this are connected by a crossing, e.g. across a road
+const CROSSING_DISTANCE: N = 30.0;
+/// How far a trip may walk to reach the nearest sidewalk
+const MAX_ACCESS_DISTANCE: N = 60.0;
+/// Where trips start and end, buildings are all on the ground
+const ACCESS_LEVEL: i8 = 0;
+
+#[derive(Compact, Clone)]
+pub struct Sidewalk {
+    id: SidewalkID,
+}
+
+impl Sidewalk {
+    pub fn spawn(
+        id: SidewalkID,
+        prototype: &SidewalkPrototype,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) -> Sidewalk {
+        PedestrianNetworkID::global_first(world).add_sidewalk(id, prototype.clone(), world);
+        report_to.action_done(id.into(), world);
+        Sidewalk { id }
+    }
+}
+
+impl Constructable<CBPrototypeKind> for Sidewalk {
+    fn morph(
+        &mut self,
+        _new_prototype: &Prototype<CBPrototypeKind>,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) {
+        report_to.action_done(self.id_as(), world);
+    }
+
+    fn destruct(&mut self, report_to: CBConstructionID, world: &mut World) -> Fate {
+        PedestrianNetworkID::global_first(world).remove_sidewalk(self.id, world);
+        report_to.action_done(self.id_as(), world);
+        Fate::Die
+    }
+}
+
+type Cell = (i32, i32);
+
+fn cell_of(position: P2, cell_size: N) -> Cell {
+    (
+        (position.x / cell_size).floor() as i32,
+        (position.y / cell_size).floor() as i32,
+    )
+}
+
+/// The cell of a position and the cells around it
+fn cells_around(position: P2, cell_size: N) -> Vec<Cell> {
+    let (x, y) = cell_of(position, cell_size);
+    (x - 1..x + 2)
+        .flat_map(|cell_x| (y - 1..y + 2).map(move |cell_y| (cell_x, cell_y)))
+        .collect()
+}
+
+#[derive(Copy, Clone)]
+struct WalkingNode {
+    position: P2,
+    level: i8,
+    /// Crossings may start here
+    crossable: bool,
+}
+
+/// Sidewalk ends as nodes, connected by the sidewalks themselves and by crossings
+#[derive(Compact, Clone)]
+struct WalkingGraph {
+    nodes: CVec<WalkingNode>,
+    /// For each node: neighboring nodes and the walking distance to them
+    edges: CVec<CVec<(u32, N)>>,
+    /// Start and end node of each sidewalk
+    sidewalk_nodes: CHashMap<SidewalkID, (u32, u32)>,
+    /// The sidewalks passing through each cell of `MAX_ACCESS_DISTANCE` size,
+    /// from which the nearest sidewalk to a position is found
+    sidewalk_cells: CHashMap<Cell, CVec<SidewalkID>>,
+}
+
+impl WalkingGraph {
+    fn new() -> WalkingGraph {
+        WalkingGraph {
+            nodes: CVec::new(),
+            edges: CVec::new(),
+            sidewalk_nodes: CHashMap::new(),
+            sidewalk_cells: CHashMap::new(),
+        }
+    }
+
+    fn build(sidewalks: &CHashMap<SidewalkID, SidewalkPrototype>) -> WalkingGraph {
+        let mut graph = WalkingGraph::new();
+        // nodes by cells of `CROSSING_DISTANCE` size
+        let mut node_cells = HashMap::<Cell, Vec<u32>>::new();
+
+        for (&sidewalk, &SidewalkPrototype(ref path, start, end)) in sidewalks.pairs() {
+            let start_node = graph.node_at(path.start(), start, &mut node_cells);
+            let end_node = graph.node_at(path.end(), end, &mut node_cells);
+            graph.connect(start_node, end_node, path.length());
+            graph.sidewalk_nodes.insert(sidewalk, (start_node, end_node));
+
+            let (min_x, min_y) = cell_of(path.points[0], MAX_ACCESS_DISTANCE);
+            let (min_x, min_y, max_x, max_y) = path.points.iter().fold(
+                (min_x, min_y, min_x, min_y),
+                |(min_x, min_y, max_x, max_y), &point| {
+                    let (x, y) = cell_of(point, MAX_ACCESS_DISTANCE);
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                },
+            );
+            for x in min_x..max_x + 1 {
+                for y in min_y..max_y + 1 {
+                    graph.sidewalk_cells.push_at((x, y), sidewalk);
+                }
+            }
+        }
+
+        for node_a in 0..graph.nodes.len() as u32 {
+            let a = graph.nodes[node_a as usize];
+            if !a.crossable {
+                continue;
+            }
+
+            for cell in cells_around(a.position, CROSSING_DISTANCE) {
+                for &node_b in node_cells.get(&cell).into_iter().flat_map(|nodes| nodes.iter()) {
+                    let b = graph.nodes[node_b as usize];
+                    if node_b <= node_a || !b.crossable || b.level != a.level {
+                        continue;
+                    }
+
+                    let distance = (a.position - b.position).norm();
+                    let already_connected = graph.edges[node_a as usize]
+                        .iter()
+                        .any(|&(neighbor, _)| neighbor == node_b);
+                    if distance < CROSSING_DISTANCE && !already_connected {
+                        graph.connect(node_a, node_b, distance);
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn node_at(
+        &mut self,
+        position: P2,
+        end: SidewalkEnd,
+        node_cells: &mut HashMap<Cell, Vec<u32>>,
+    ) -> u32 {
+        let maybe_existing = cells_around(position, CROSSING_DISTANCE)
+            .into_iter()
+            .flat_map(|cell| node_cells.get(&cell).cloned().unwrap_or_default())
+            .find(|&node| {
+                let node = self.nodes[node as usize];
+                node.level == end.level && (node.position - position).norm() < JOIN_DISTANCE
+            });
+
+        if let Some(existing) = maybe_existing {
+            // a joined end next to a limited-access road keeps the whole node from crossing
+            self.nodes[existing as usize].crossable &= end.crossable;
+            existing
+        } else {
+            self.nodes.push(WalkingNode {
+                position,
+                level: end.level,
+                crossable: end.crossable,
+            });
+            self.edges.push(CVec::new());
+            let node = (self.nodes.len() - 1) as u32;
+            node_cells
+                .entry(cell_of(position, CROSSING_DISTANCE))
+                .or_insert_with(Vec::new)
+                .push(node);
+            node
+        }
+    }
+
+    fn connect(&mut self, node_a: u32, node_b: u32, distance: N) {
+        self.edges[node_a as usize].push((node_b, distance));
+        self.edges[node_b as usize].push((node_a, distance));
+    }
+}
+
+/// Where a position reaches the nearest sidewalk
+#[derive(Copy, Clone)]
+struct Access {
+    sidewalk: SidewalkID,
+    along: N,
+    /// Distance from the position to the sidewalk
+    distance: N,
+}
+
+#[derive(Compact, Clone)]
+pub struct PedestrianNetwork {
+    id: PedestrianNetworkID,
+    sidewalks: CHashMap<SidewalkID, SidewalkPrototype>,
+    graph: WalkingGraph,
+    graph_outdated: bool,
+}
+
+impl PedestrianNetwork {
+    pub fn spawn(id: PedestrianNetworkID, _: &mut World) -> PedestrianNetwork {
+        PedestrianNetwork {
+            id,
+            sidewalks: CHashMap::new(),
+            graph: WalkingGraph::new(),
+            graph_outdated: false,
+        }
+    }
+
+    pub fn add_sidewalk(
+        &mut self,
+        sidewalk: SidewalkID,
+        prototype: &SidewalkPrototype,
+        _: &mut World,
+    ) {
+        self.sidewalks.insert(sidewalk, prototype.clone());
+        self.graph_outdated = true;
+    }
+
+    pub fn remove_sidewalk(&mut self, sidewalk: SidewalkID, _: &mut World) {
+        self.sidewalks.remove(sidewalk);
+        self.graph_outdated = true;
+    }
+
+    fn access(&self, position: P2) -> Option<Access> {
+        cells_around(position, MAX_ACCESS_DISTANCE)
+            .into_iter()
+            .filter_map(|cell| self.graph.sidewalk_cells.get(cell))
+            .flat_map(|sidewalks| sidewalks.iter())
+            .filter_map(|&sidewalk| {
+                let &SidewalkPrototype(ref path, start, end) = self.sidewalks.get(sidewalk)?;
+                if start.level != ACCESS_LEVEL && end.level != ACCESS_LEVEL {
+                    return None;
+                }
+                path.project(position).map(|(along, projected)| Access {
+                    sidewalk,
+                    along,
+                    distance: (projected - position).norm(),
+                })
+            })
+            .filter(|access| access.distance < MAX_ACCESS_DISTANCE)
+            .min_by_key(|access| OrderedFloat(access.distance))
+    }
+
+    /// Walking distances from an access point to the ends of its sidewalk
+    fn access_costs(&self, access: Access) -> Vec<(u32, N)> {
+        let length = self
+            .sidewalks
+            .get(access.sidewalk)
+            .map(|sidewalk| sidewalk.0.length())
+            .unwrap_or(0.0);
+        match self.graph.sidewalk_nodes.get(access.sidewalk) {
+            Some(&(start_node, end_node)) => vec![
+                (start_node, access.distance + access.along),
+                (end_node, access.distance + length - access.along),
+            ],
+            None => vec![],
+        }
+    }
+
+    fn walking_distance(&mut self, from: P2, to: P2) -> Option<N> {
+        if self.graph_outdated {
+            self.graph = WalkingGraph::build(&self.sidewalks);
+            self.graph_outdated = false;
+        }
+
+        let (from_access, to_access) = (self.access(from)?, self.access(to)?);
+
+        if from_access.sidewalk == to_access.sidewalk {
+            return Some(
+                from_access.distance
+                    + (from_access.along - to_access.along).abs()
+                    + to_access.distance,
+            );
+        }
+
+        let target_costs = self.access_costs(to_access);
+        let mut distances = vec![None; self.graph.nodes.len()];
+        let mut queue = BinaryHeap::new();
+
+        for (node, cost) in self.access_costs(from_access) {
+            queue.push(Reverse((OrderedFloat(cost), node)));
+        }
+
+        while let Some(Reverse((OrderedFloat(distance), node))) = queue.pop() {
+            if distances[node as usize].is_some() {
+                continue;
+            }
+            distances[node as usize] = Some(distance);
+
+            for &(neighbor, edge_distance) in self.graph.edges[node as usize].iter() {
+                if distances[neighbor as usize].is_none() {
+                    queue.push(Reverse((OrderedFloat(distance + edge_distance), neighbor)));
+                }
+            }
+        }
+
+        target_costs
+            .into_iter()
+            .filter_map(|(node, cost)| distances[node as usize].map(|distance| distance + cost))
+            .min_by_key(|&distance| OrderedFloat(distance))
+    }
+
+    pub fn find_walking_route(&mut self, from: P2, to: P2, trip: TripID, world: &mut World) {
+        let maybe_distance = self.walking_distance(from, to);
+        trip.walking_route_found(maybe_distance, world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<Sidewalk>();
+    system.register::<PedestrianNetwork>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    PedestrianNetworkID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
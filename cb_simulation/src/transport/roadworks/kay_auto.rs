@@ -0,0 +1,162 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for Roadworks {
+    type ID = RoadworksID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct RoadworksID {
+    _raw_id: RawID
+}
+
+impl Copy for RoadworksID {}
+impl Clone for RoadworksID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for RoadworksID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RoadworksID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for RoadworksID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for RoadworksID {
+    fn eq(&self, other: &RoadworksID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for RoadworksID {}
+
+impl TypedID for RoadworksID {
+    type Target = Roadworks;
+
+    fn from_raw(id: RawID) -> Self {
+        RoadworksID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl RoadworksID {
+    pub fn spawn(position: P2, radius: N, starts_in: Duration, lasts: Option < Duration >, world: &mut World) -> Self {
+        let id = RoadworksID::from_raw(world.allocate_instance_id::<Roadworks>());
+        let swarm = world.local_broadcast::<Roadworks>();
+        world.send(swarm, MSG_Roadworks_spawn(id, position, radius, starts_in, lasts));
+        id
+    }
+
+    pub fn end(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_Roadworks_end());
+    }
+    
+    pub fn end_if_near(self, position: P2, radius: N, world: &mut World) {
+        world.send(self.as_raw(), MSG_Roadworks_end_if_near(position, radius));
+    }
+    
+    pub fn apply_to_lane(self, lane: LaneID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Roadworks_apply_to_lane(lane));
+    }
+    
+    pub fn apply_to_switch_lane(self, lane: SwitchLaneID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Roadworks_apply_to_switch_lane(lane));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Roadworks_spawn(pub RoadworksID, pub P2, pub N, pub Duration, pub Option < Duration >);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Roadworks_end();
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Roadworks_end_if_near(pub P2, pub N);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Roadworks_apply_to_lane(pub LaneID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Roadworks_apply_to_switch_lane(pub SwitchLaneID);
+
+impl Into<SleeperID> for RoadworksID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+impl LaneID {
+    pub fn set_closed_near(self, roadworks: RoadworksID, position: P2, radius: N, closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_set_closed_near(roadworks, position, radius, closed));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_set_closed_near(pub RoadworksID, pub P2, pub N, pub bool);
+
+impl SwitchLaneID {
+    pub fn set_closed_near(self, roadworks: RoadworksID, position: P2, radius: N, closed: bool, world: &mut World) {
+        world.send(self.as_raw(), MSG_SwitchLane_set_closed_near(roadworks, position, radius, closed));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_SwitchLane_set_closed_near(pub RoadworksID, pub P2, pub N, pub bool);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    SleeperID::register_implementor::<Roadworks>(system);
+    system.add_spawner::<Roadworks, _, _>(
+        |&MSG_Roadworks_spawn(id, position, radius, starts_in, lasts), world| {
+            Roadworks::spawn(id, position, radius, starts_in, lasts, world)
+        }, false
+    );
+
+    system.add_handler::<Roadworks, _, _>(
+        |&MSG_Roadworks_end(), instance, world| {
+            instance.end(world)
+        }, false
+    );
+
+    system.add_handler::<Roadworks, _, _>(
+        |&MSG_Roadworks_end_if_near(position, radius), instance, world| {
+            instance.end_if_near(position, radius, world)
+        }, false
+    );
+
+    system.add_handler::<Roadworks, _, _>(
+        |&MSG_Roadworks_apply_to_lane(lane), instance, world| {
+            instance.apply_to_lane(lane, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Roadworks, _, _>(
+        |&MSG_Roadworks_apply_to_switch_lane(lane), instance, world| {
+            instance.apply_to_switch_lane(lane, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_set_closed_near(roadworks, position, radius, closed), instance, world| {
+            instance.set_closed_near(roadworks, position, radius, closed, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<SwitchLane, _, _>(
+        |&MSG_SwitchLane_set_closed_near(roadworks, position, radius, closed), instance, world| {
+            instance.set_closed_near(roadworks, position, radius, closed, world); Fate::Live
+        }, false
+    );
+}
@@ -0,0 +1,157 @@
+//! Temporary closures of lanes, for roadworks or incidents, without unbuilding any roads.
+//! Closed lanes are avoided by pathfinding and cars stop before entering them.
+//! Each lane remembers which roadworks closed it, so it only reopens once all of them ended.
+
+use kay::{ActorSystem, World, Fate, Actor};
+use descartes::{N, P2};
+use cb_time::actors::{TimeID, Sleeper, SleeperID};
+use cb_time::units::{Instant, Duration, Ticks};
+
+use super::lane::{Lane, LaneID, SwitchLane, SwitchLaneID};
+
+use cb_util::log::info;
+const LOG_T: &str = "Roadworks";
+
+impl Lane {
+    pub fn set_closed_near(
+        &mut self,
+        roadworks: RoadworksID,
+        position: P2,
+        radius: N,
+        closed: bool,
+        world: &mut World,
+    ) {
+        if self.construction.path.distance_to(position) < radius {
+            self.set_closed(roadworks, closed, world);
+        }
+    }
+}
+
+impl SwitchLane {
+    pub fn set_closed_near(
+        &mut self,
+        roadworks: RoadworksID,
+        position: P2,
+        radius: N,
+        closed: bool,
+        world: &mut World,
+    ) {
+        if self.construction.path.distance_to(position) < radius {
+            self.set_closed(roadworks, closed, world);
+        }
+    }
+}
+
+fn set_closed_near(
+    roadworks: RoadworksID,
+    position: P2,
+    radius: N,
+    closed: bool,
+    world: &mut World,
+) {
+    LaneID::global_broadcast(world).set_closed_near(roadworks, position, radius, closed, world);
+    SwitchLaneID::global_broadcast(world)
+        .set_closed_near(roadworks, position, radius, closed, world);
+}
+
+/// Ends or cancels all roadworks that overlap the area within `radius` of `position`
+pub fn end_near(position: P2, radius: N, world: &mut World) {
+    RoadworksID::global_broadcast(world).end_if_near(position, radius, world);
+}
+
+/// Closes all lanes around a position for a scheduled time window
+#[derive(Compact, Clone)]
+pub struct Roadworks {
+    id: RoadworksID,
+    position: P2,
+    radius: N,
+    /// Without a duration, the lanes stay closed until the roadworks are ended by hand
+    lasts: Option<Duration>,
+    started: bool,
+}
+
+impl Roadworks {
+    pub fn spawn(
+        id: RoadworksID,
+        position: P2,
+        radius: N,
+        starts_in: Duration,
+        lasts: Option<Duration>,
+        world: &mut World,
+    ) -> Roadworks {
+        TimeID::local_first(world).wake_up_in(Ticks::from(starts_in), id.into(), world);
+
+        Roadworks {
+            id,
+            position,
+            radius,
+            lasts,
+            started: false,
+        }
+    }
+
+    /// Reopens the lanes, unless other roadworks still keep them closed.
+    /// Roadworks that didn't start yet are cancelled
+    pub fn end(&mut self, world: &mut World) -> Fate {
+        if self.started {
+            set_closed_near(self.id, self.position, self.radius, false, world);
+            info(LOG_T, "Roadworks ended, lanes reopened", self.id, world);
+        } else {
+            info(LOG_T, "Scheduled roadworks cancelled", self.id, world);
+        }
+        Fate::Die
+    }
+
+    pub fn end_if_near(&mut self, position: P2, radius: N, world: &mut World) -> Fate {
+        if (self.position - position).norm() < self.radius + radius {
+            self.end(world)
+        } else {
+            Fate::Live
+        }
+    }
+
+    /// Lanes built while the roadworks are going on are closed as well
+    pub fn apply_to_lane(&mut self, lane: LaneID, world: &mut World) {
+        if self.started {
+            lane.set_closed_near(self.id, self.position, self.radius, true, world);
+        }
+    }
+
+    pub fn apply_to_switch_lane(&mut self, lane: SwitchLaneID, world: &mut World) {
+        if self.started {
+            lane.set_closed_near(self.id, self.position, self.radius, true, world);
+        }
+    }
+}
+
+impl Sleeper for Roadworks {
+    fn wake(&mut self, _current_instant: Instant, world: &mut World) {
+        if self.started {
+            self.id.end(world);
+        } else {
+            set_closed_near(self.id, self.position, self.radius, true, world);
+            info(
+                LOG_T,
+                format!(
+                    "Roadworks started, lanes within {}m of {:?} closed",
+                    self.radius, self.position
+                ),
+                self.id,
+                world,
+            );
+            self.started = true;
+
+            if let Some(lasts) = self.lasts {
+                TimeID::local_first(world).wake_up_in(Ticks::from(lasts), self.id_as(), world);
+            }
+        }
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<Roadworks>();
+    auto_setup(system);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
@@ -0,0 +1,143 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for SignalController {
+    type ID = SignalControllerID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct SignalControllerID {
+    _raw_id: RawID
+}
+
+impl Copy for SignalControllerID {}
+impl Clone for SignalControllerID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for SignalControllerID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "SignalControllerID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for SignalControllerID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for SignalControllerID {
+    fn eq(&self, other: &SignalControllerID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for SignalControllerID {}
+
+impl TypedID for SignalControllerID {
+    type Target = SignalController;
+
+    fn from_raw(id: RawID) -> Self {
+        SignalControllerID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl SignalControllerID {
+    pub fn spawn(program: SignalProgram, phases: CVec < CVec < LaneID > >, report_to: CBConstructionID, world: &mut World) -> Self {
+        let id = SignalControllerID::from_raw(world.allocate_instance_id::<SignalController>());
+        let swarm = world.local_broadcast::<SignalController>();
+        world.send(swarm, MSG_SignalController_spawn(id, program, phases, report_to));
+        id
+    }
+
+    pub fn on_queue_report(self, lane: LaneID, report: QueueReport, world: &mut World) {
+        world.send(self.as_raw(), MSG_SignalController_on_queue_report(lane, report));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_SignalController_spawn(pub SignalControllerID, pub SignalProgram, pub CVec < CVec < LaneID > >, pub CBConstructionID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_SignalController_on_queue_report(pub LaneID, pub QueueReport);
+
+impl Into<TemporalID> for SignalControllerID {
+    fn into(self) -> TemporalID {
+        TemporalID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<ConstructableID<CBPrototypeKind>> for SignalControllerID {
+    fn into(self) -> ConstructableID<CBPrototypeKind> {
+        ConstructableID::from_raw(self.as_raw())
+    }
+}
+
+impl LaneID {
+    pub fn set_signal_aspect(self, aspect: SignalAspect, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_set_signal_aspect(aspect));
+    }
+
+    pub fn report_queue(self, controller: SignalControllerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_report_queue(controller));
+    }
+
+    pub fn report_queue_towards(self, next_lane: LaneID, controller: SignalControllerID, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_report_queue_towards(next_lane, controller));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_set_signal_aspect(pub SignalAspect);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_report_queue(pub SignalControllerID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_report_queue_towards(pub LaneID, pub SignalControllerID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+
+    TemporalID::register_implementor::<SignalController>(system);
+    ConstructableID::<CBPrototypeKind>::register_implementor::<SignalController>(system);
+    system.add_spawner::<SignalController, _, _>(
+        |&MSG_SignalController_spawn(id, ref program, ref phases, report_to), world| {
+            SignalController::spawn(id, program, phases, report_to, world)
+        }, false
+    );
+
+    system.add_handler::<SignalController, _, _>(
+        |&MSG_SignalController_on_queue_report(lane, report), instance, world| {
+            instance.on_queue_report(lane, report, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_set_signal_aspect(aspect), instance, world| {
+            instance.set_signal_aspect(aspect, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_report_queue(controller), instance, world| {
+            instance.report_queue(controller, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_report_queue_towards(next_lane, controller), instance, world| {
+            instance.report_queue_towards(next_lane, controller, world); Fate::Live
+        }, false
+    );
+}
@@ -0,0 +1,298 @@
+//! Adaptive traffic signals: instead of cycling through fixed timings, a controller
+//! watches the cars queueing in front of an intersection and extends, cuts short
+//! or skips the phases of its signal program accordingly.
+
+use kay::{ActorSystem, World, Fate, Actor};
+use compact::{CVec, CHashMap};
+use cb_time::actors::{Temporal, TemporalID};
+use cb_time::units::{Instant, TICKS_PER_SIM_SECOND};
+use cb_planning::Prototype;
+use cb_planning::construction::{Constructable, ConstructableID};
+use planning::{CBConstructionID, CBPrototypeKind};
+
+use super::lane::{Lane, LaneID};
+use super::lane::connectivity::Interaction;
+use super::transport_planning::{RoadPrototype, IntersectionPrototype};
+use super::transport_planning::intersection_control::{IntersectionControl, SignalProgram,
+SignalAspect};
+
+/// In seconds
+const MIN_GREEN: u32 = 5;
+/// In seconds, after which a waiting phase is served as soon as the current one had
+/// its minimum green time, even if cars keep arriving for the current one
+const MAX_WAITING: u32 = 90;
+/// Distance in front of the stop line in which approaching cars are detected
+const DETECTION_DISTANCE: f32 = 60.0;
+/// Detected cars slower than this are counted as queued
+const QUEUED_VELOCITY: f32 = 2.0;
+
+/// What the lanes in front of a connecting lane reported about the cars heading for it
+#[derive(Copy, Clone, Default, Debug)]
+pub struct QueueReport {
+    pub n_approaching: u16,
+    pub n_queued: u16,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ControllerState {
+    Green,
+    Yellow,
+    AllRed,
+}
+
+#[derive(Compact, Clone)]
+pub struct SignalController {
+    id: SignalControllerID,
+    program: SignalProgram,
+    /// Connecting lanes of each automatically found phase
+    phases: CVec<CVec<LaneID>>,
+    queues: CHashMap<LaneID, QueueReport>,
+    current_phase: u8,
+    state: ControllerState,
+    ticks_in_state: u32,
+    /// For each phase, since how many ticks cars have been queueing while it was red
+    waiting_ticks: CVec<u32>,
+}
+
+impl SignalController {
+    pub fn spawn(
+        id: SignalControllerID,
+        program: &SignalProgram,
+        phases: &CVec<CVec<LaneID>>,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) -> SignalController {
+        report_to.action_done(id.into(), world);
+        let first_phase = program.order(phases.len()).first().cloned().unwrap_or(0);
+
+        let controller = SignalController {
+            id,
+            program: program.clone(),
+            phases: phases.clone(),
+            queues: CHashMap::new(),
+            current_phase: first_phase as u8,
+            state: ControllerState::Green,
+            ticks_in_state: 0,
+            waiting_ticks: phases.iter().map(|_| 0).collect(),
+        };
+        controller.show_aspects(world);
+        controller
+    }
+
+    pub fn on_queue_report(&mut self, lane: LaneID, report: QueueReport, _: &mut World) {
+        self.queues.insert(lane, report);
+    }
+
+    fn is_current(&self, lane: LaneID) -> bool {
+        self.phases
+            .get(self.current_phase as usize)
+            .map(|lanes| lanes.contains(&lane))
+            .unwrap_or(false)
+    }
+
+    /// Only counts lanes that aren't already served by the current phase
+    fn demand(&self, phase_idx: usize) -> QueueReport {
+        let lanes = &self.phases[phase_idx];
+        lanes
+            .iter()
+            .filter(|&&lane| phase_idx == self.current_phase as usize || !self.is_current(lane))
+            .filter_map(|lane| self.queues.get(*lane))
+            .fold(QueueReport::default(), |sum, report| QueueReport {
+                n_approaching: sum.n_approaching + report.n_approaching,
+                n_queued: sum.n_queued + report.n_queued,
+            })
+    }
+
+    /// The next phase in the program order that has cars waiting for it, if any
+    fn next_phase_with_demand(&self) -> Option<usize> {
+        let order = self.program.order(self.phases.len());
+        let current_position = order
+            .iter()
+            .position(|&phase_idx| phase_idx == self.current_phase as usize)
+            .unwrap_or(0);
+
+        (1..=order.len())
+            .map(|offset| order[(current_position + offset) % order.len()])
+            .find(|&phase_idx| self.demand(phase_idx).n_approaching > 0)
+    }
+
+    fn max_green(&self) -> u32 {
+        let order = self.program.order(self.phases.len());
+        let position = order
+            .iter()
+            .position(|&phase_idx| phase_idx == self.current_phase as usize)
+            .unwrap_or(0);
+        let n_lanes = self.phases[self.current_phase as usize].len();
+        u32::from(self.program.green_duration(position, n_lanes))
+    }
+
+    fn show_aspects(&self, world: &mut World) {
+        for lanes in self.phases.iter() {
+            for &lane in lanes.iter() {
+                let aspect = match self.state {
+                    ControllerState::Green if self.is_current(lane) => SignalAspect::Green,
+                    ControllerState::Yellow if self.is_current(lane) => SignalAspect::Yellow,
+                    _ => SignalAspect::Red,
+                };
+                lane.set_signal_aspect(aspect, world);
+            }
+        }
+    }
+
+    fn change_state(&mut self, state: ControllerState, world: &mut World) {
+        self.state = state;
+        self.ticks_in_state = 0;
+        self.show_aspects(world);
+    }
+}
+
+impl Temporal for SignalController {
+    fn tick(&mut self, _dt: f32, current_instant: Instant, world: &mut World) {
+        if self.phases.is_empty() {
+            return;
+        }
+
+        if current_instant.ticks() % TICKS_PER_SIM_SECOND as usize == 0 {
+            for lanes in self.phases.iter() {
+                for &lane in lanes.iter() {
+                    lane.report_queue(self.id, world);
+                }
+            }
+            // lanes forget their aspect when they are morphed
+            self.show_aspects(world);
+        }
+
+        for phase_idx in 0..self.phases.len() {
+            let served =
+                phase_idx == self.current_phase as usize && self.state == ControllerState::Green;
+            self.waiting_ticks[phase_idx] = if served || self.demand(phase_idx).n_queued == 0 {
+                0
+            } else {
+                self.waiting_ticks[phase_idx] + 1
+            };
+        }
+
+        self.ticks_in_state += 1;
+        let seconds_in_state = self.ticks_in_state / TICKS_PER_SIM_SECOND;
+
+        match self.state {
+            ControllerState::Green => {
+                if let Some(next_phase) = self.next_phase_with_demand() {
+                    let others_waiting = next_phase != self.current_phase as usize;
+                    let longest_waiting = self
+                        .waiting_ticks
+                        .iter()
+                        .max()
+                        .cloned()
+                        .unwrap_or(0)
+                        / TICKS_PER_SIM_SECOND;
+                    let current_demand = self.demand(self.current_phase as usize);
+
+                    if others_waiting
+                        && seconds_in_state >= MIN_GREEN
+                        && (current_demand.n_approaching == 0
+                            || seconds_in_state >= self.max_green()
+                            || longest_waiting >= MAX_WAITING)
+                    {
+                        self.change_state(ControllerState::Yellow, world);
+                    }
+                }
+            }
+            ControllerState::Yellow => {
+                if seconds_in_state >= u32::from(self.program.yellow_duration) {
+                    self.change_state(ControllerState::AllRed, world);
+                }
+            }
+            ControllerState::AllRed => {
+                if seconds_in_state >= u32::from(self.program.all_red_duration) {
+                    if let Some(next_phase) = self.next_phase_with_demand() {
+                        self.current_phase = next_phase as u8;
+                    }
+                    self.change_state(ControllerState::Green, world);
+                }
+            }
+        }
+    }
+}
+
+impl Constructable<CBPrototypeKind> for SignalController {
+    fn morph(
+        &mut self,
+        new_prototype: &Prototype<CBPrototypeKind>,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) {
+        if let CBPrototypeKind::Road(RoadPrototype::Intersection(IntersectionPrototype {
+            control: IntersectionControl::Adaptive(ref program),
+            ..
+        })) = new_prototype.kind
+        {
+            // intersections whose phases changed are rebuilt instead, so `phases`,
+            // `queues` and `waiting_ticks` still fit
+            self.program = program.clone();
+        }
+        report_to.action_done(self.id_as(), world);
+    }
+
+    fn destruct(&mut self, report_to: CBConstructionID, world: &mut World) -> Fate {
+        report_to.action_done(self.id_as(), world);
+        Fate::Die
+    }
+}
+
+impl Lane {
+    pub fn set_signal_aspect(&mut self, aspect: SignalAspect, _: &mut World) {
+        self.microtraffic.set_timings(vec![aspect].into());
+    }
+
+    /// Asks the lanes leading into this connecting lane to report their queues
+    pub fn report_queue(&mut self, controller: SignalControllerID, world: &mut World) {
+        for interaction in self.connectivity.interactions.iter() {
+            if let Interaction::Previous { previous, .. } = *interaction {
+                previous.report_queue_towards(self.id, controller, world);
+            }
+        }
+    }
+
+    pub fn report_queue_towards(
+        &mut self,
+        next_lane: LaneID,
+        controller: SignalControllerID,
+        world: &mut World,
+    ) {
+        let maybe_next_idx = self
+            .connectivity
+            .interactions
+            .iter()
+            .position(|interaction| match *interaction {
+                Interaction::Next { next, .. } => next == next_lane,
+                _ => false,
+            });
+
+        if let Some(next_idx) = maybe_next_idx {
+            let detection_start = self.construction.length - DETECTION_DISTANCE;
+            let report = self
+                .microtraffic
+                .cars
+                .iter()
+                .filter(|car| {
+                    car.next_hop_interaction == Some(next_idx as u8)
+                        && *car.position > detection_start
+                })
+                .fold(QueueReport::default(), |report, car| QueueReport {
+                    n_approaching: report.n_approaching + 1,
+                    n_queued: report.n_queued + if car.velocity < QUEUED_VELOCITY { 1 } else { 0 },
+                });
+
+            controller.on_queue_report(next_lane, report, world);
+        }
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<SignalController>();
+    auto_setup(system);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
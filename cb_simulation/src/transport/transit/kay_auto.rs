@@ -0,0 +1,464 @@
+//! This is all auto-generated. Do not touch.
+#![rustfmt::skip]
+#[allow(unused_imports)]
+use kay::{ActorSystem, TypedID, RawID, Fate, Actor, TraitIDFrom, ActorOrActorTrait};
+#[allow(unused_imports)]
+use super::*;
+
+
+
+impl Actor for TransitLine {
+    type ID = TransitLineID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TransitLineID {
+    _raw_id: RawID
+}
+
+impl Copy for TransitLineID {}
+impl Clone for TransitLineID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TransitLineID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TransitLineID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TransitLineID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TransitLineID {
+    fn eq(&self, other: &TransitLineID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TransitLineID {}
+
+impl TypedID for TransitLineID {
+    type Target = TransitLine;
+
+    fn from_raw(id: RawID) -> Self {
+        TransitLineID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TransitLineID {
+    pub fn spawn(prototype: TransitLinePrototype, report_to: CBConstructionID, world: &mut World) -> Self {
+        let id = TransitLineID::from_raw(world.allocate_instance_id::<TransitLine>());
+        let swarm = world.local_broadcast::<TransitLine>();
+        world.send(swarm, MSG_TransitLine_spawn(id, prototype, report_to));
+        id
+    }
+
+    pub fn bus_retired(self, bus: BusID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitLine_bus_retired(bus));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitLine_spawn(pub TransitLineID, pub TransitLinePrototype, pub CBConstructionID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitLine_bus_retired(pub BusID);
+
+impl Into<SleeperID> for TransitLineID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<ConstructableID<CBPrototypeKind>> for TransitLineID {
+    fn into(self) -> ConstructableID<CBPrototypeKind> {
+        ConstructableID::from_raw(self.as_raw())
+    }
+}
+impl Actor for TransitStop {
+    type ID = TransitStopID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TransitStopID {
+    _raw_id: RawID
+}
+
+impl Copy for TransitStopID {}
+impl Clone for TransitStopID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TransitStopID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TransitStopID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TransitStopID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TransitStopID {
+    fn eq(&self, other: &TransitStopID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TransitStopID {}
+
+impl TypedID for TransitStopID {
+    type Target = TransitStop;
+
+    fn from_raw(id: RawID) -> Self {
+        TransitStopID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TransitStopID {
+    pub fn spawn(line: TransitLineID, position: P2, world: &mut World) -> Self {
+        let id = TransitStopID::from_raw(world.allocate_instance_id::<TransitStop>());
+        let swarm = world.local_broadcast::<TransitStop>();
+        world.send(swarm, MSG_TransitStop_spawn(id, line, position));
+        id
+    }
+
+    pub fn connect(self, location: PreciseLocation, distance: N, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitStop_connect(location, distance));
+    }
+
+    pub fn wait_for_bus(self, trip: TripID, alighting_stop: TransitStopID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitStop_wait_for_bus(trip, alighting_stop));
+    }
+
+    pub fn bus_arrived(self, bus: BusID, alighting: CVec < TripID >, served_stops: CVec < TransitStopID >, free_seats: u16, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitStop_bus_arrived(bus, alighting, served_stops, free_seats));
+    }
+
+    pub fn service_interrupted(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitStop_service_interrupted());
+    }
+
+    pub fn close(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitStop_close());
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_spawn(pub TransitStopID, pub TransitLineID, pub P2);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_connect(pub PreciseLocation, pub N);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_wait_for_bus(pub TripID, pub TransitStopID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_bus_arrived(pub BusID, pub CVec < TripID >, pub CVec < TransitStopID >, pub u16);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_service_interrupted();
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitStop_close();
+
+impl Into<SleeperID> for TransitStopID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<AttacheeID> for TransitStopID {
+    fn into(self) -> AttacheeID {
+        AttacheeID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<RoughLocationID> for TransitStopID {
+    fn into(self) -> RoughLocationID {
+        RoughLocationID::from_raw(self.as_raw())
+    }
+}
+
+impl LaneID {
+    pub fn try_connect_transit_stop(self, stop: TransitStopID, position: P2, world: &mut World) {
+        world.send(self.as_raw(), MSG_Lane_try_connect_transit_stop(stop, position));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Lane_try_connect_transit_stop(pub TransitStopID, pub P2);
+
+impl Actor for Bus {
+    type ID = BusID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct BusID {
+    _raw_id: RawID
+}
+
+impl Copy for BusID {}
+impl Clone for BusID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for BusID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "BusID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for BusID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for BusID {
+    fn eq(&self, other: &BusID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for BusID {}
+
+impl TypedID for BusID {
+    type Target = Bus;
+
+    fn from_raw(id: RawID) -> Self {
+        BusID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl BusID {
+    pub fn spawn(line: TransitLineID, route: CVec < TransitStopID >, world: &mut World) -> Self {
+        let id = BusID::from_raw(world.allocate_instance_id::<Bus>());
+        let swarm = world.local_broadcast::<Bus>();
+        world.send(swarm, MSG_Bus_spawn(id, line, route));
+        id
+    }
+
+    pub fn board(self, boarding: CVec < (TripID, TransitStopID) >, world: &mut World) {
+        world.send(self.as_raw(), MSG_Bus_board(boarding));
+    }
+
+    pub fn end_service(self, world: &mut World) {
+        world.send(self.as_raw(), MSG_Bus_end_service());
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Bus_spawn(pub BusID, pub TransitLineID, pub CVec < TransitStopID >);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Bus_board(pub CVec < (TripID, TransitStopID) >);
+#[derive(Copy, Clone)] #[allow(non_camel_case_types)]
+struct MSG_Bus_end_service();
+
+impl Into<SleeperID> for BusID {
+    fn into(self) -> SleeperID {
+        SleeperID::from_raw(self.as_raw())
+    }
+}
+
+impl Into<TripListenerID> for BusID {
+    fn into(self) -> TripListenerID {
+        TripListenerID::from_raw(self.as_raw())
+    }
+}
+impl Actor for TransitNetwork {
+    type ID = TransitNetworkID;
+
+    fn id(&self) -> Self::ID {
+        self.id
+    }
+    unsafe fn set_id(&mut self, id: RawID) {
+        self.id = Self::ID::from_raw(id);
+    }
+}
+
+#[derive(Serialize, Deserialize)] #[serde(transparent)]
+pub struct TransitNetworkID {
+    _raw_id: RawID
+}
+
+impl Copy for TransitNetworkID {}
+impl Clone for TransitNetworkID { fn clone(&self) -> Self { *self } }
+impl ::std::fmt::Debug for TransitNetworkID {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "TransitNetworkID({:?})", self._raw_id)
+    }
+}
+impl ::std::hash::Hash for TransitNetworkID {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self._raw_id.hash(state);
+    }
+}
+impl PartialEq for TransitNetworkID {
+    fn eq(&self, other: &TransitNetworkID) -> bool {
+        self._raw_id == other._raw_id
+    }
+}
+impl Eq for TransitNetworkID {}
+
+impl TypedID for TransitNetworkID {
+    type Target = TransitNetwork;
+
+    fn from_raw(id: RawID) -> Self {
+        TransitNetworkID { _raw_id: id }
+    }
+
+    fn as_raw(&self) -> RawID {
+        self._raw_id
+    }
+}
+
+impl TransitNetworkID {
+    pub fn spawn(world: &mut World) -> Self {
+        let id = TransitNetworkID::from_raw(world.allocate_instance_id::<TransitNetwork>());
+        let swarm = world.local_broadcast::<TransitNetwork>();
+        world.send(swarm, MSG_TransitNetwork_spawn(id, ));
+        id
+    }
+
+    pub fn add_stop(self, stop: TransitStopID, line: TransitLineID, position: P2, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitNetwork_add_stop(stop, line, position));
+    }
+
+    pub fn remove_stop(self, stop: TransitStopID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitNetwork_remove_stop(stop));
+    }
+
+    pub fn find_connection(self, from: P2, to: P2, trip: TripID, world: &mut World) {
+        world.send(self.as_raw(), MSG_TransitNetwork_find_connection(from, to, trip));
+    }
+}
+
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitNetwork_spawn(pub TransitNetworkID, );
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitNetwork_add_stop(pub TransitStopID, pub TransitLineID, pub P2);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitNetwork_remove_stop(pub TransitStopID);
+#[derive(Compact, Clone)] #[allow(non_camel_case_types)]
+struct MSG_TransitNetwork_find_connection(pub P2, pub P2, pub TripID);
+
+#[allow(unused_variables)]
+#[allow(unused_mut)]
+pub fn auto_setup(system: &mut ActorSystem) {
+    SleeperID::register_implementor::<TransitLine>(system);
+    ConstructableID::<CBPrototypeKind>::register_implementor::<TransitLine>(system);
+    system.add_spawner::<TransitLine, _, _>(
+        |&MSG_TransitLine_spawn(id, ref prototype, report_to), world| {
+            TransitLine::spawn(id, prototype, report_to, world)
+        }, false
+    );
+
+    system.add_handler::<TransitLine, _, _>(
+        |&MSG_TransitLine_bus_retired(bus), instance, world| {
+            instance.bus_retired(bus, world); Fate::Live
+        }, false
+    );
+    SleeperID::register_implementor::<TransitStop>(system);
+    AttacheeID::register_implementor::<TransitStop>(system);
+    RoughLocationID::register_implementor::<TransitStop>(system);
+    system.add_spawner::<TransitStop, _, _>(
+        |&MSG_TransitStop_spawn(id, line, position), world| {
+            TransitStop::spawn(id, line, position, world)
+        }, false
+    );
+
+    system.add_handler::<TransitStop, _, _>(
+        |&MSG_TransitStop_connect(location, distance), instance, world| {
+            instance.connect(location, distance, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitStop, _, _>(
+        |&MSG_TransitStop_wait_for_bus(trip, alighting_stop), instance, world| {
+            instance.wait_for_bus(trip, alighting_stop, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitStop, _, _>(
+        |&MSG_TransitStop_bus_arrived(bus, ref alighting, ref served_stops, free_seats), instance, world| {
+            instance.bus_arrived(bus, alighting, served_stops, free_seats, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitStop, _, _>(
+        |&MSG_TransitStop_service_interrupted(), instance, world| {
+            instance.service_interrupted(world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitStop, _, _>(
+        |&MSG_TransitStop_close(), instance, world| {
+            instance.close(world)
+        }, false
+    );
+
+    system.add_handler::<Lane, _, _>(
+        |&MSG_Lane_try_connect_transit_stop(stop, position), instance, world| {
+            instance.try_connect_transit_stop(stop, position, world); Fate::Live
+        }, false
+    );
+    SleeperID::register_implementor::<Bus>(system);
+    TripListenerID::register_implementor::<Bus>(system);
+    system.add_spawner::<Bus, _, _>(
+        |&MSG_Bus_spawn(id, line, ref route), world| {
+            Bus::spawn(id, line, route, world)
+        }, false
+    );
+
+    system.add_handler::<Bus, _, _>(
+        |&MSG_Bus_board(ref boarding), instance, world| {
+            instance.board(boarding, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<Bus, _, _>(
+        |&MSG_Bus_end_service(), instance, world| {
+            instance.end_service(world)
+        }, false
+    );
+
+    system.add_spawner::<TransitNetwork, _, _>(
+        |&MSG_TransitNetwork_spawn(id, ), world| {
+            TransitNetwork::spawn(id, world)
+        }, false
+    );
+
+    system.add_handler::<TransitNetwork, _, _>(
+        |&MSG_TransitNetwork_add_stop(stop, line, position), instance, world| {
+            instance.add_stop(stop, line, position, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitNetwork, _, _>(
+        |&MSG_TransitNetwork_remove_stop(stop), instance, world| {
+            instance.remove_stop(stop, world); Fate::Live
+        }, false
+    );
+
+    system.add_handler::<TransitNetwork, _, _>(
+        |&MSG_TransitNetwork_find_connection(from, to, trip), instance, world| {
+            instance.find_connection(from, to, trip, world); Fate::Live
+        }, false
+    );
+}
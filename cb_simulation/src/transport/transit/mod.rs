@@ -0,0 +1,515 @@
+//! Public transit: bus lines with stops along existing roads. Buses depart on a
+//! fixed headway and drive from stop to stop as normal cars in traffic, dwelling
+//! at each stop to let passengers get on and off.
+
+use kay::{ActorSystem, World, Fate, Actor, TypedID};
+use compact::{CVec, CHashMap};
+use descartes::{N, P2};
+use ordered_float::OrderedFloat;
+use cb_time::actors::{TimeID, Sleeper, SleeperID};
+use cb_time::units::{Instant, Ticks, Duration};
+use cb_planning::Prototype;
+use cb_planning::construction::{Constructable, ConstructableID};
+use planning::{CBConstructionID, CBPrototypeKind};
+
+use super::lane::{Lane, LaneID};
+use super::microtraffic::VehicleType;
+use super::pathfinding::{PreciseLocation, Location, RoughLocation, RoughLocationID,
+RoughLocationResolve, Attachee, AttacheeID};
+use super::pathfinding::trip::{TripID, TripListener, TripListenerID, TripResult, TripFate,
+ModeChoice};
+use dimensions::LANE_DISTANCE;
+
+pub mod transit_planning;
+use self::transit_planning::TransitLinePrototype;
+
+use cb_util::log::debug;
+const LOG_T: &str = "Transit";
+
+/// How long buses stay stopped at each stop, in seconds
+pub const DWELL_TIME: usize = 20;
+const BUS_CAPACITY: u16 = 60;
+/// How far passengers walk at most between a stop and their source or destination
+const MAX_STOP_DISTANCE: N = 500.0;
+
+impl TransitLinePrototype {
+    pub fn construct(
+        &self,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) -> CVec<ConstructableID<CBPrototypeKind>> {
+        vec![TransitLineID::spawn(self.clone(), report_to, world).into()].into()
+    }
+}
+
+#[derive(Compact, Clone)]
+pub struct TransitLine {
+    id: TransitLineID,
+    stops: CVec<TransitStopID>,
+    headway_minutes: u16,
+    buses: CVec<BusID>,
+}
+
+impl TransitLine {
+    pub fn spawn(
+        id: TransitLineID,
+        prototype: &TransitLinePrototype,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) -> TransitLine {
+        let stops = prototype
+            .stops
+            .iter()
+            .map(|&position| TransitStopID::spawn(id, position, world))
+            .collect();
+
+        // give the stops some time to find their lanes before the first departure
+        TimeID::local_first(world).wake_up_in(
+            Ticks::from(Duration::from_minutes(1)),
+            id.into(),
+            world,
+        );
+        report_to.action_done(id.into(), world);
+
+        TransitLine {
+            id,
+            stops,
+            headway_minutes: prototype.headway_minutes,
+            buses: CVec::new(),
+        }
+    }
+
+    pub fn bus_retired(&mut self, bus: BusID, _: &mut World) {
+        self.buses.retain(|other_bus| *other_bus != bus);
+    }
+}
+
+impl Sleeper for TransitLine {
+    fn wake(&mut self, _: Instant, world: &mut World) {
+        // buses serve all stops in order and then return the same way
+        let route = self
+            .stops
+            .iter()
+            .chain(self.stops.iter().rev().skip(1))
+            .cloned()
+            .collect();
+        let bus = BusID::spawn(self.id, route, world);
+        self.buses.push(bus);
+
+        TimeID::local_first(world).wake_up_in(
+            Ticks::from(Duration::from_minutes(self.headway_minutes as usize)),
+            self.id_as(),
+            world,
+        );
+    }
+}
+
+impl Constructable<CBPrototypeKind> for TransitLine {
+    fn morph(
+        &mut self,
+        new_prototype: &Prototype<CBPrototypeKind>,
+        report_to: CBConstructionID,
+        world: &mut World,
+    ) {
+        if let CBPrototypeKind::TransitLine(ref line_prototype) = new_prototype.kind {
+            self.headway_minutes = line_prototype.headway_minutes;
+        }
+        report_to.action_done(self.id_as(), world);
+    }
+
+    fn destruct(&mut self, report_to: CBConstructionID, world: &mut World) -> Fate {
+        for stop in self.stops.iter() {
+            stop.close(world);
+        }
+        for bus in self.buses.iter() {
+            bus.end_service(world);
+        }
+        report_to.action_done(self.id_as(), world);
+        Fate::Die
+    }
+}
+
+#[derive(Compact, Clone)]
+pub struct TransitStop {
+    id: TransitStopID,
+    line: TransitLineID,
+    position: P2,
+    location: Option<PreciseLocation>,
+    /// Distance between the stop position and its location on a lane
+    location_distance: N,
+    /// Passengers and the stops they want to get off at
+    waiting: CVec<(TripID, TransitStopID)>,
+}
+
+impl TransitStop {
+    pub fn spawn(
+        id: TransitStopID,
+        line: TransitLineID,
+        position: P2,
+        world: &mut World,
+    ) -> TransitStop {
+        let stop = TransitStop {
+            id,
+            line,
+            position,
+            location: None,
+            location_distance: 0.0,
+            waiting: CVec::new(),
+        };
+        stop.try_connect(world);
+        stop
+    }
+
+    /// Looks for the closest lane, again and again until one is found
+    fn try_connect(&self, world: &mut World) {
+        LaneID::global_broadcast(world).try_connect_transit_stop(self.id, self.position, world);
+        TimeID::local_first(world).wake_up_in(
+            Ticks::from(Duration::from_minutes(10)),
+            self.id_as(),
+            world,
+        );
+    }
+
+    pub fn connect(&mut self, location: PreciseLocation, distance: N, world: &mut World) {
+        if self.location.is_none() || distance < self.location_distance {
+            if let Some(old_location) = self.location {
+                old_location.link.remove_attachee(self.id_as(), world);
+            }
+            } else {
+                // only stops on a lane are served, so only those are offered to trips
+                TransitNetworkID::global_first(world).add_stop(
+                    self.id,
+                    self.line,
+                    self.position,
+                    world,
+                );
+            }
+            self.location = Some(location);
+            self.location_distance = distance;
+            location.link.add_attachee(self.id_as(), world);
+        }
+    }
+
+    /// Passengers only wait at stops that buses can reach, the others drive instead
+    pub fn wait_for_bus(&mut self, trip: TripID, alighting_stop: TransitStopID, world: &mut World) {
+        if self.location.is_some() {
+            self.waiting.push((trip, alighting_stop));
+        } else {
+            trip.transit_connection_found(None, world);
+        }
+    }
+
+    /// Lets passengers off the bus, then lets those on who are going
+    /// to one of the stops the bus still serves
+    pub fn bus_arrived(
+        &mut self,
+        bus: BusID,
+        alighting: &CVec<TripID>,
+        served_stops: &CVec<TransitStopID>,
+        free_seats: u16,
+        world: &mut World,
+    ) {
+        for passenger in alighting.iter() {
+            passenger.alighted(self.position, world);
+        }
+
+        let mut boarding = CVec::new();
+        let mut staying = CVec::new();
+        for &(passenger, alighting_stop) in self.waiting.iter() {
+            if boarding.len() < free_seats as usize && served_stops.contains(&alighting_stop) {
+                boarding.push((passenger, alighting_stop));
+            } else {
+                staying.push((passenger, alighting_stop));
+            }
+        }
+        self.waiting = staying;
+
+        bus.board(boarding, world);
+    }
+
+    /// Sends all waiting passengers away to drive instead,
+    /// e.g. because a bus couldn't get through
+    pub fn service_interrupted(&mut self, world: &mut World) {
+        for &(passenger, _) in self.waiting.iter() {
+            passenger.transit_connection_found(None, world);
+        }
+        self.waiting = CVec::new();
+    }
+
+    pub fn close(&mut self, world: &mut World) -> Fate {
+        self.service_interrupted(world);
+        TransitNetworkID::global_first(world).remove_stop(self.id, world);
+        if let Some(location) = self.location {
+            location.link.remove_attachee(self.id_as(), world);
+        }
+        Fate::Die
+    }
+}
+
+impl Sleeper for TransitStop {
+    fn wake(&mut self, _: Instant, world: &mut World) {
+        if self.location.is_none() {
+            self.try_connect(world);
+        }
+    }
+}
+
+impl Attachee for TransitStop {
+    fn location_changed(
+        &mut self,
+        _old: Option<Location>,
+        maybe_new: Option<Location>,
+        world: &mut World,
+    ) {
+        if let Some(new) = maybe_new {
+            self.location
+                .as_mut()
+                .expect("Only an existing location can change")
+                .location = new;
+        } else {
+            self.location = None;
+            TransitNetworkID::global_first(world).remove_stop(self.id, world);
+            self.service_interrupted(world);
+            self.try_connect(world);
+        }
+    }
+}
+
+impl RoughLocation for TransitStop {
+    fn resolve(&self) -> RoughLocationResolve {
+        RoughLocationResolve::Done(self.location, self.position)
+    }
+}
+
+impl Lane {
+    pub fn try_connect_transit_stop(
+        &mut self,
+        stop: TransitStopID,
+        position: P2,
+        world: &mut World,
+    ) {
+        if let Some(location) = self.pathfinding.location {
+            if !self.connectivity.on_intersection {
+                if let Some((offset, projected_point)) = self
+                    .construction
+                    .path
+                    .project_with_max_distance(position, 0.5, 3.0 * LANE_DISTANCE)
+                {
+                    stop.connect(
+                        PreciseLocation { location, offset },
+                        (projected_point - position).norm(),
+                        world,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[derive(Compact, Clone)]
+pub struct Bus {
+    id: BusID,
+    line: TransitLineID,
+    route: CVec<TransitStopID>,
+    /// Index into the route of the stop the bus is at or driving to
+    stop_idx: u8,
+    /// Passengers and the stops they want to get off at
+    passengers: CVec<(TripID, TransitStopID)>,
+}
+
+impl Bus {
+    pub fn spawn(
+        id: BusID,
+        line: TransitLineID,
+        route: &CVec<TransitStopID>,
+        world: &mut World,
+    ) -> Bus {
+        let mut bus = Bus {
+            id,
+            line,
+            route: route.clone(),
+            stop_idx: 0,
+            passengers: CVec::new(),
+        };
+        bus.arrive_at_stop(world);
+        bus
+    }
+
+    fn arrive_at_stop(&mut self, world: &mut World) {
+        let stop = self.route[self.stop_idx as usize];
+        let alighting = self
+            .passengers
+            .iter()
+            .filter(|&&(_, alighting_stop)| alighting_stop == stop)
+            .map(|&(passenger, _)| passenger)
+            .collect::<CVec<_>>();
+        self.passengers
+            .retain(|&(_, alighting_stop)| alighting_stop != stop);
+
+        let served_stops = self.route[(self.stop_idx as usize + 1)..]
+            .iter()
+            .cloned()
+            .collect();
+        let free_seats = BUS_CAPACITY - self.passengers.len() as u16;
+        stop.bus_arrived(self.id, alighting, served_stops, free_seats, world);
+
+        TimeID::local_first(world).wake_up_in(
+            Ticks::from(Duration::from_seconds(DWELL_TIME)),
+            self.id_as(),
+            world,
+        );
+    }
+
+    pub fn board(&mut self, boarding: &CVec<(TripID, TransitStopID)>, _: &mut World) {
+        self.passengers.extend(boarding.iter().cloned());
+    }
+
+    fn fail_passengers(&mut self, fate: TripFate, world: &mut World) {
+        for &(passenger, _) in self.passengers.iter() {
+            passenger.finish(
+                TripResult {
+                    location_now: None,
+                    fate,
+                    last_lane: None,
+                },
+                world,
+            );
+        }
+        self.passengers = CVec::new();
+    }
+
+    pub fn end_service(&mut self, world: &mut World) -> Fate {
+        self.fail_passengers(TripFate::ForceStopped, world);
+        self.line.bus_retired(self.id, world);
+        Fate::Die
+    }
+}
+
+impl Sleeper for Bus {
+    fn wake(&mut self, current_instant: Instant, world: &mut World) {
+        let next_stop_idx = self.stop_idx as usize + 1;
+        if next_stop_idx < self.route.len() {
+            TripID::spawn(
+                self.route[self.stop_idx as usize].into(),
+                self.route[next_stop_idx].into(),
+                Some(self.id_as()),
+                ModeChoice::Drive,
+                VehicleType::Bus,
+                current_instant,
+                world,
+            );
+        } else {
+            self.id.end_service(world);
+        }
+    }
+}
+
+impl TripListener for Bus {
+    fn trip_created(&mut self, _trip: TripID, _: &mut World) {}
+
+    fn trip_result(
+        &mut self,
+        _trip: TripID,
+        result: TripResult,
+        _rough_source: RoughLocationID,
+        _rough_destination: RoughLocationID,
+        world: &mut World,
+    ) {
+        if let TripFate::Success(_) = result.fate {
+            self.stop_idx += 1;
+            self.arrive_at_stop(world);
+        } else {
+            debug(
+                LOG_T,
+                format!("Bus {:?} couldn't reach its next stop: {:?}", self.id, result.fate),
+                self.id(),
+                world,
+            );
+            for stop in self.route[(self.stop_idx as usize + 1)..].iter() {
+                stop.service_interrupted(world);
+            }
+            self.fail_passengers(result.fate, world);
+            self.id.end_service(world);
+        }
+    }
+}
+
+/// Knows all stops, to find out which line connects two places
+#[derive(Compact, Clone)]
+pub struct TransitNetwork {
+    id: TransitNetworkID,
+    stops: CHashMap<TransitStopID, (TransitLineID, P2)>,
+}
+
+impl TransitNetwork {
+    pub fn spawn(id: TransitNetworkID, _: &mut World) -> TransitNetwork {
+        TransitNetwork {
+            id,
+            stops: CHashMap::new(),
+        }
+    }
+
+    pub fn add_stop(
+        &mut self,
+        stop: TransitStopID,
+        line: TransitLineID,
+        position: P2,
+        _: &mut World,
+    ) {
+        self.stops.insert(stop, (line, position));
+    }
+
+    pub fn remove_stop(&mut self, stop: TransitStopID, _: &mut World) {
+        self.stops.remove(stop);
+    }
+
+    /// The pair of stops on the same line that is closest to both places, if any
+    pub fn find_connection(&mut self, from: P2, to: P2, trip: TripID, world: &mut World) {
+        let stops_near = |position: P2| {
+            self.stops
+                .pairs()
+                .map(|(&stop, &(line, stop_position))| {
+                    (stop, line, (stop_position - position).norm())
+                })
+                .filter(|&(_, _, distance)| distance < MAX_STOP_DISTANCE)
+                .collect::<Vec<_>>()
+        };
+        let (boarding_stops, alighting_stops) = (stops_near(from), stops_near(to));
+
+        let maybe_connection = boarding_stops
+            .iter()
+            .flat_map(|&(boarding_stop, boarding_line, boarding_distance)| {
+                alighting_stops
+                    .iter()
+                    .filter(move |&&(alighting_stop, alighting_line, _)| {
+                        alighting_line == boarding_line && alighting_stop != boarding_stop
+                    })
+                    .map(move |&(alighting_stop, _, alighting_distance)| {
+                        (
+                            boarding_stop,
+                            alighting_stop,
+                            boarding_distance + alighting_distance,
+                        )
+                    })
+            })
+            .min_by_key(|&(_, _, walking_distance)| OrderedFloat(walking_distance))
+            .map(|(boarding_stop, alighting_stop, _)| (boarding_stop, alighting_stop));
+
+        trip.transit_connection_found(maybe_connection, world);
+    }
+}
+
+pub fn setup(system: &mut ActorSystem) {
+    system.register::<TransitLine>();
+    system.register::<TransitStop>();
+    system.register::<Bus>();
+    system.register::<TransitNetwork>();
+    auto_setup(system);
+}
+
+pub fn spawn(world: &mut World) {
+    TransitNetworkID::spawn(world);
+}
+
+mod kay_auto;
+pub use self::kay_auto::*;
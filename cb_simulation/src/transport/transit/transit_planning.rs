@@ -0,0 +1,64 @@
+use compact::CVec;
+use descartes::{P2, RoughEq, AreaError};
+
+use cb_planning::{VersionedGesture, PlanHistory, PlanResult, Prototype};
+use planning::{CBPrototypeKind, CBGestureIntent};
+
+/// A bus line serving the gesture points as stops, in order and back again.
+/// Buses find their way between stops over the existing roads
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct TransitLineIntent {
+    /// Minutes between two departures from the first stop
+    pub headway_minutes: u16,
+}
+
+impl Default for TransitLineIntent {
+    fn default() -> Self {
+        TransitLineIntent {
+            headway_minutes: 10,
+        }
+    }
+}
+
+#[derive(Compact, Clone, Serialize, Deserialize, Debug)]
+pub struct TransitLinePrototype {
+    pub stops: CVec<P2>,
+    pub headway_minutes: u16,
+}
+
+impl TransitLinePrototype {
+    /// Only the timetable of a line can change without rebuilding its stops
+    pub fn morphable_from(&self, other: &TransitLinePrototype) -> bool {
+        self.stops.len() == other.stops.len()
+            && self
+                .stops
+                .iter()
+                .zip(other.stops.iter())
+                .all(|(stop_1, stop_2)| stop_1.rough_eq_by(*stop_2, 0.5))
+    }
+}
+
+pub fn calculate_prototypes(
+    history: &PlanHistory<CBGestureIntent>,
+    _current_result: &PlanResult<CBPrototypeKind>,
+) -> Result<Vec<Prototype<CBPrototypeKind>>, AreaError> {
+    Ok(history
+        .gestures
+        .pairs()
+        .filter_map(
+            |(gesture_id, VersionedGesture(gesture, step_id))| match gesture.intent {
+                CBGestureIntent::TransitLine(line_intent) if gesture.points.len() >= 2 => {
+                    Some(Prototype::new_with_influences(
+                        (gesture_id, step_id),
+                        CBPrototypeKind::TransitLine(TransitLinePrototype {
+                            stops: gesture.points.clone(),
+                            headway_minutes: line_intent.headway_minutes,
+                        }),
+                        gesture.points[0],
+                    ))
+                }
+                _ => None,
+            },
+        )
+        .collect())
+}
@@ -1,13 +1,19 @@
 use compact::CVec;
-use descartes::{ArcLinePath, Intersect, WithUniqueOrthogonal,
+use descartes::{N, ArcLinePath, Intersect, WithUniqueOrthogonal,
 RoughEq};
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 
 use super::{IntersectionPrototype, IntersectionConnector, ConnectionRole, LANE_DISTANCE,
-LanePrototype, GestureSideID};
+LanePrototype, GestureSideID, RoundaboutRing, RightOfWay, LaneArrows};
+use super::intersection_control::{IntersectionControl, SignalAspect};
 
 pub fn create_connecting_lanes(intersection: &mut IntersectionPrototype) {
+    if let Some(ring) = intersection.roundabout {
+        create_roundabout_lanes(intersection, ring);
+        return;
+    }
+
     // sort intersection connectors from inner to outer lanes
     for incoming_group in intersection.incoming.values_mut() {
         let base_position = incoming_group[0].position;
@@ -120,6 +126,44 @@ pub fn create_connecting_lanes(intersection: &mut IntersectionPrototype) {
                     incoming_lane.role.outer_turn = true;
                 }
             }
+
+            // painted lane-use arrows replace the automatic assignment, ignoring arrows
+            // for movements this intersection doesn't have, so that no lane is left unconnected
+            let roles_from_arrows = incoming_group
+                .iter()
+                .enumerate()
+                .map(|(l, incoming_lane)| {
+                    let arrows = incoming_lane.arrows;
+                    let possible_arrows = LaneArrows {
+                        inner_turn: arrows.inner_turn && has_inner_turn,
+                        straight: arrows.straight && has_straight,
+                        outer_turn: arrows.outer_turn && has_outer_turn,
+                    };
+
+                    if possible_arrows.is_automatic() {
+                        incoming_lane.role
+                    } else {
+                        ConnectionRole {
+                            straight: possible_arrows.straight,
+                            u_turn: l == 0 && possible_arrows.inner_turn,
+                            inner_turn: possible_arrows.inner_turn,
+                            outer_turn: possible_arrows.outer_turn,
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // if the arrows leave a movement without any lane, all of them are ignored
+            let serves_all_movements = (!has_inner_turn
+                || roles_from_arrows.iter().any(|role| role.inner_turn))
+                && (!has_straight || roles_from_arrows.iter().any(|role| role.straight))
+                && (!has_outer_turn || roles_from_arrows.iter().any(|role| role.outer_turn));
+
+            if serves_all_movements {
+                for (incoming_lane, role) in incoming_group.iter_mut().zip(roles_from_arrows) {
+                    incoming_lane.role = role;
+                }
+            }
         }
 
         for outgoing_group in intersection.outgoing.values_mut() {
@@ -211,7 +255,13 @@ pub fn create_connecting_lanes(intersection: &mut IntersectionPrototype) {
                                     )?
                                     .to_line_path_with_max_angle(0.6);
 
-                                    Some(LanePrototype(path, CVec::new()))
+                                    Some(LanePrototype(
+                                        path,
+                                        CVec::new(),
+                                        start.road_class.more_restrictive(end.road_class),
+                                        RightOfWay::Priority,
+                                        0,
+                                    ))
                                 })
                                 .collect::<Vec<_>>()
                         } else {
@@ -241,7 +291,7 @@ pub fn create_connecting_lanes(intersection: &mut IntersectionPrototype) {
 
         fn compatible(lanes_a: &[LanePrototype], lanes_b: &[LanePrototype]) -> bool {
             lanes_a.iter().cartesian_product(lanes_b).all(
-                |(&LanePrototype(ref path_a, _), &LanePrototype(ref path_b, _))| {
+                |(&LanePrototype(ref path_a, ..), &LanePrototype(ref path_b, ..))| {
                     path_a.start().rough_eq_by(path_b.start(), 0.1)
                         || (!path_a.end().rough_eq_by(path_b.end(), 0.1)
                             && (path_a, path_b).intersect().is_empty())
@@ -354,19 +404,163 @@ pub fn create_connecting_lanes(intersection: &mut IntersectionPrototype) {
             iteration += 1;
         }
 
+        let n_lanes_in_phase = phases
+            .iter()
+            .map(|&(_, n_lanes)| n_lanes)
+            .collect::<Vec<_>>();
+
+        intersection.phases = if intersection.control.is_adaptive() {
+            phases
+                .iter()
+                .map(|&(ref connections_in_phase, _)| connections_in_phase.clone().into())
+                .collect()
+        } else {
+            CVec::new()
+        };
+
         for ((incoming_id, outgoing_id), ref mut lanes) in intersection.connecting_lanes.pairs_mut()
         {
-            let timings: CVec<bool> = phases
+            let in_phase = phases
                 .iter()
-                .flat_map(|&(ref connections_in_phase, duration)| {
-                    let in_phase = connections_in_phase.contains(&(incoming_id, outgoing_id));
-                    vec![in_phase; duration]
+                .map(|&(ref connections_in_phase, _)| {
+                    connections_in_phase.contains(&(incoming_id, outgoing_id))
                 })
-                .collect();
-
-            for &mut LanePrototype(_, ref mut lane_timings) in lanes.iter_mut() {
-                *lane_timings = timings.clone()
+                .collect::<Vec<_>>();
+            let (timings, right_of_way) = match intersection.control {
+                IntersectionControl::Signalled(ref program) => (
+                    program.aspects(&in_phase, &n_lanes_in_phase),
+                    RightOfWay::Priority,
+                ),
+                // the signal controller sets the actual aspects once it's running,
+                // lanes that aren't part of any phase are never signalled
+                IntersectionControl::Adaptive(_) => (
+                    if in_phase.contains(&true) {
+                        vec![SignalAspect::Red].into()
+                    } else {
+                        CVec::new()
+                    },
+                    RightOfWay::Priority,
+                ),
+                IntersectionControl::Unsignalled => (CVec::new(), RightOfWay::Priority),
+                IntersectionControl::AllWayStop => (CVec::new(), RightOfWay::Stop),
+            };
+
+            for &mut LanePrototype(_, ref mut lane_timings, _, ref mut lane_right_of_way, _) in
+                lanes.iter_mut()
+            {
+                *lane_timings = timings.clone();
+                *lane_right_of_way = right_of_way;
             }
         }
     }
 }
+
+/// Distance along the ring over which entering and leaving lanes merge with it
+const ROUNDABOUT_MERGE_LENGTH: N = 6.0;
+
+/// Entering lanes merge into the ring and have to yield to the traffic on it,
+/// so unlike normal intersections, roundabouts don't need any signal timings.
+/// The ring is split into lanes wherever lanes enter or leave it
+fn create_roundabout_lanes(intersection: &mut IntersectionPrototype, ring: RoundaboutRing) {
+    const FULL_CIRCLE: N = 2.0 * ::std::f32::consts::PI;
+    let ring_id = GestureSideID::roundabout_ring();
+    let merge_angle = ROUNDABOUT_MERGE_LENGTH / ring.radius;
+    let normalize = |angle: N| {
+        if angle >= FULL_CIRCLE {
+            angle - FULL_CIRCLE
+        } else if angle < 0.0 {
+            angle + FULL_CIRCLE
+        } else {
+            angle
+        }
+    };
+
+    let mut break_angles = Vec::new();
+
+    for (incoming_id, incoming_group) in intersection.incoming.pairs() {
+        let entering_lanes = incoming_group
+            .iter()
+            .filter_map(|connector| {
+                let angle = normalize(ring.angle_of(connector.position) + merge_angle);
+                let path = ArcLinePath::biarc(
+                    connector.position,
+                    connector.direction,
+                    ring.point_at(angle),
+                    ring.direction_at(angle),
+                )?
+                .to_line_path_with_max_angle(0.6);
+                break_angles.push(angle);
+
+                Some(LanePrototype(
+                    path,
+                    CVec::new(),
+                    connector.road_class.more_restrictive(ring.road_class),
+                    RightOfWay::Yield,
+                    0,
+                ))
+            })
+            .collect::<CVec<_>>();
+        intersection
+            .connecting_lanes
+            .insert((*incoming_id, ring_id), entering_lanes);
+    }
+
+    for (outgoing_id, outgoing_group) in intersection.outgoing.pairs() {
+        let leaving_lanes = outgoing_group
+            .iter()
+            .filter_map(|connector| {
+                let angle = normalize(ring.angle_of(connector.position) - merge_angle);
+                let path = ArcLinePath::biarc(
+                    ring.point_at(angle),
+                    ring.direction_at(angle),
+                    connector.position,
+                    connector.direction,
+                )?
+                .to_line_path_with_max_angle(0.6);
+                break_angles.push(angle);
+
+                Some(LanePrototype(
+                    path,
+                    CVec::new(),
+                    connector.road_class.more_restrictive(ring.road_class),
+                    RightOfWay::Priority,
+                    0,
+                ))
+            })
+            .collect::<CVec<_>>();
+        intersection
+            .connecting_lanes
+            .insert((ring_id, *outgoing_id), leaving_lanes);
+    }
+
+    break_angles.sort_by_key(|angle| OrderedFloat(*angle));
+    break_angles.dedup_by(|a, b| (*a - *b).abs() * ring.radius < 0.05);
+
+    // a ring lane can't connect to itself, so always split the ring at least once
+    match break_angles.len() {
+        0 => break_angles.extend_from_slice(&[0.0, FULL_CIRCLE / 2.0]),
+        1 => {
+            let opposite = normalize(break_angles[0] + FULL_CIRCLE / 2.0);
+            break_angles.push(opposite);
+            break_angles.sort_by_key(|angle| OrderedFloat(*angle));
+        }
+        _ => {}
+    }
+
+    let ring_lanes = break_angles
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &start_angle)| {
+            let end_angle = break_angles
+                .get(i + 1)
+                .cloned()
+                .unwrap_or(break_angles[0] + FULL_CIRCLE);
+            ring.ring_path(start_angle, end_angle).map(|path| {
+                LanePrototype(path, CVec::new(), ring.road_class, RightOfWay::Priority, 0)
+            })
+        })
+        .collect::<CVec<_>>();
+    intersection
+        .connecting_lanes
+        .insert((ring_id, ring_id), ring_lanes);
+}
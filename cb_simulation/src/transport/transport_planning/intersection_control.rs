@@ -0,0 +1,125 @@
+//! How the right of way is regulated on intersections: either by a signal
+//! program that cycles through the phases found for an intersection, or without signals.
+
+use compact::CVec;
+use std::iter::repeat;
+
+/// What a signal shows to a lane, given for each second of the signal cycle.
+/// Cars may still enter an intersection on yellow
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SignalAspect {
+    Green,
+    Yellow,
+    Red,
+}
+
+#[derive(Compact, Clone, Debug, Serialize, Deserialize)]
+pub enum IntersectionControl {
+    Signalled(SignalProgram),
+    /// Like `Signalled`, but phases are extended while cars keep arriving, cut short
+    /// when nobody is left to serve and skipped when nobody waits for them.
+    /// The phase durations of the program are the maximum green times
+    Adaptive(SignalProgram),
+    /// Conflicting lanes give way to each other
+    Unsignalled,
+    /// Every car stops before entering, then conflicting lanes give way to each other
+    AllWayStop,
+}
+
+impl Default for IntersectionControl {
+    fn default() -> Self {
+        IntersectionControl::Signalled(SignalProgram::default())
+    }
+}
+
+impl IntersectionControl {
+    /// Adaptive intersections need a signal controller next to their lanes
+    pub fn is_adaptive(&self) -> bool {
+        match *self {
+            IntersectionControl::Adaptive(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// In seconds
+const DEFAULT_GREEN_PER_LANE: u16 = 10;
+
+#[derive(Compact, Clone, Debug, Serialize, Deserialize)]
+pub struct SignalProgram {
+    /// Indices of the automatically found phases, in the order they are run.
+    /// If empty, all phases are run in the order they were found
+    #[serde(default)]
+    pub phase_order: CVec<u8>,
+    /// Green time in seconds of each phase in the order above. Missing
+    /// durations are derived from the number of lanes in a phase
+    #[serde(default)]
+    pub phase_durations: CVec<u16>,
+    /// In seconds
+    pub yellow_duration: u16,
+    /// Time in seconds after each phase in which no new phase starts yet
+    pub all_red_duration: u16,
+}
+
+impl Default for SignalProgram {
+    fn default() -> Self {
+        SignalProgram {
+            phase_order: CVec::new(),
+            phase_durations: CVec::new(),
+            yellow_duration: 10,
+            all_red_duration: 3,
+        }
+    }
+}
+
+impl SignalProgram {
+    /// Indices of the found phases in the order they are run
+    pub fn order(&self, n_phases: usize) -> Vec<usize> {
+        if self.phase_order.is_empty() {
+            (0..n_phases).collect()
+        } else {
+            self.phase_order
+                .iter()
+                .map(|&phase_idx| phase_idx as usize)
+                .filter(|&phase_idx| phase_idx < n_phases)
+                .collect()
+        }
+    }
+
+    /// Green time in seconds of the phase at the given position in the order
+    pub fn green_duration(&self, position: usize, n_lanes_in_phase: usize) -> u16 {
+        self.phase_durations
+            .get(position)
+            .cloned()
+            .unwrap_or(DEFAULT_GREEN_PER_LANE * n_lanes_in_phase as u16)
+    }
+
+    /// Signal aspects of a lane for the whole cycle, given whether it is part
+    /// of each found phase and the number of lanes in each found phase.
+    /// Lanes that are part of two consecutive phases stay green in between
+    pub fn aspects(&self, in_phase: &[bool], n_lanes_in_phase: &[usize]) -> CVec<SignalAspect> {
+        let order = self.order(in_phase.len());
+
+        let mut aspects = Vec::new();
+
+        for (position, &phase_idx) in order.iter().enumerate() {
+            let green_duration = self.green_duration(position, n_lanes_in_phase[phase_idx]);
+            let is_green = in_phase[phase_idx];
+            let stays_green = is_green && in_phase[order[(position + 1) % order.len()]];
+
+            let (green_aspect, yellow_aspect, all_red_aspect) = if stays_green {
+                (SignalAspect::Green, SignalAspect::Green, SignalAspect::Green)
+            } else if is_green {
+                (SignalAspect::Green, SignalAspect::Yellow, SignalAspect::Red)
+            } else {
+                (SignalAspect::Red, SignalAspect::Red, SignalAspect::Red)
+            };
+
+            aspects.extend(repeat(green_aspect).take(green_duration as usize));
+            aspects.extend(repeat(yellow_aspect).take(self.yellow_duration as usize));
+            aspects.extend(repeat(all_red_aspect).take(self.all_red_duration as usize));
+        }
+
+        aspects.into()
+    }
+}
@@ -4,27 +4,258 @@ RoughEq, PointContainer, AreaError, ArcOrLineSegment, Segment, AreaEmbedding, Ar
 use ordered_float::OrderedFloat;
 
 use cb_planning::{VersionedGesture, StepID, PrototypeID, PlanHistory, PlanResult,
-Prototype, GestureID};
+Prototype, GestureID, PlanIssue, PlanIssueKind};
 use planning::{CBPrototypeKind, CBGestureIntent};
 
 mod intersection_connections;
+pub mod intersection_control;
 pub mod smooth_path;
+use self::intersection_control::{IntersectionControl, SignalAspect};
 use dimensions::{LANE_DISTANCE, CENTER_LANE_DISTANCE, MIN_SWITCHING_LANE_LENGTH,
-SWITCHING_LANE_OVERLAP_TOLERANCE};
+SWITCHING_LANE_OVERLAP_TOLERANCE, SIDEWALK_WIDTH, MIN_ROUNDABOUT_RADIUS,
+ROUNDABOUT_APPROACH_LENGTH, PARKING_LANE_WIDTH, PARKING_SPOT_LENGTH};
+
+/// Functional classification of a road, determining the properties of all its lanes
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum RoadClass {
+    ResidentialStreet,
+    Arterial,
+    Highway,
+    /// Narrow access road, usually planned with lanes in only one direction
+    Alley,
+}
+
+impl Default for RoadClass {
+    fn default() -> Self {
+        RoadClass::ResidentialStreet
+    }
+}
+
+impl RoadClass {
+    /// In m/s
+    pub fn speed_limit(self) -> N {
+        match self {
+            RoadClass::ResidentialStreet => 8.0,
+            RoadClass::Arterial => 14.0,
+            RoadClass::Highway => 25.0,
+            RoadClass::Alley => 5.0,
+        }
+    }
+
+    /// Distance between the center lines of neighboring lanes
+    pub fn lane_distance(self) -> N {
+        match self {
+            RoadClass::ResidentialStreet | RoadClass::Arterial => LANE_DISTANCE,
+            RoadClass::Highway => 1.1 * LANE_DISTANCE,
+            RoadClass::Alley => 0.85 * LANE_DISTANCE,
+        }
+    }
+
+    pub fn has_sidewalks(self) -> bool {
+        match self {
+            RoadClass::ResidentialStreet | RoadClass::Arterial => true,
+            RoadClass::Highway | RoadClass::Alley => false,
+        }
+    }
+
+    /// Pedestrians may not cross these roads at grade
+    pub fn is_limited_access(self) -> bool {
+        match self {
+            RoadClass::Highway => true,
+            RoadClass::ResidentialStreet | RoadClass::Arterial | RoadClass::Alley => false,
+        }
+    }
+
+    pub fn allows_building_access(self) -> bool {
+        match self {
+            RoadClass::ResidentialStreet | RoadClass::Arterial | RoadClass::Alley => true,
+            RoadClass::Highway => false,
+        }
+    }
+
+    /// The class whose rules apply where two classes meet (i.e. on intersections)
+    pub fn more_restrictive(self, other: RoadClass) -> RoadClass {
+        if other.speed_limit() < self.speed_limit() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl ::std::fmt::Display for RoadClass {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Vertical position of a road: roads only form intersections with
+/// roads on the same level, which allows for bridges and tunnels
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum RoadLevel {
+    /// 0 is the ground, positive levels are bridges, negative levels tunnels
+    Flat(i8),
+    /// Connects roads on the level at its start to roads on the level at its end
+    Ramp(i8, i8),
+}
+
+impl Default for RoadLevel {
+    fn default() -> Self {
+        RoadLevel::Flat(0)
+    }
+}
+
+impl RoadLevel {
+    pub fn start_level(self) -> i8 {
+        match self {
+            RoadLevel::Flat(level) | RoadLevel::Ramp(level, _) => level,
+        }
+    }
+
+    pub fn end_level(self) -> i8 {
+        match self {
+            RoadLevel::Flat(level) | RoadLevel::Ramp(_, level) => level,
+        }
+    }
+
+    pub fn reversed(self) -> Self {
+        match self {
+            RoadLevel::Flat(level) => RoadLevel::Flat(level),
+            RoadLevel::Ramp(start_level, end_level) => RoadLevel::Ramp(end_level, start_level),
+        }
+    }
+
+    /// The level along the whole road, `None` for ramps that change levels
+    pub fn body_level(self) -> Option<i8> {
+        if self.start_level() == self.end_level() {
+            Some(self.start_level())
+        } else {
+            None
+        }
+    }
+}
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct RoadIntent {
     pub n_lanes_forward: u8,
     pub n_lanes_backward: u8,
+    #[serde(default)]
+    pub road_class: RoadClass,
+    #[serde(default)]
+    pub level: RoadLevel,
+    /// Applies only where the forward lanes enter the intersection at the end of the gesture,
+    /// so each approach of an intersection gets its own arrows. Intersections that the
+    /// gesture passes through assign lanes automatically
+    #[serde(default)]
+    pub lane_use_forward: LaneUse,
+    /// Applies only where the backward lanes enter the intersection at the start of the gesture
+    #[serde(default)]
+    pub lane_use_backward: LaneUse,
+    /// On-street parking along the outermost lane of each direction
+    #[serde(default)]
+    pub parking: bool,
 }
 
 impl RoadIntent {
-    pub fn new(n_lanes_forward: u8, n_lanes_backward: u8) -> Self {
+    pub fn new(n_lanes_forward: u8, n_lanes_backward: u8, road_class: RoadClass) -> Self {
         RoadIntent {
             n_lanes_forward,
             n_lanes_backward,
+            road_class,
+            level: RoadLevel::default(),
+            lane_use_forward: LaneUse::default(),
+            lane_use_backward: LaneUse::default(),
+            parking: false,
         }
     }
+
+    pub fn with_level(self, level: RoadLevel) -> Self {
+        RoadIntent { level, ..self }
+    }
+
+    fn side_width(&self, n_lanes: u8) -> N {
+        let lane_distance = self.road_class.lane_distance();
+        let sidewalk_width = if self.road_class.has_sidewalks() {
+            SIDEWALK_WIDTH
+        } else {
+            0.0
+        };
+        let parking_width = if self.parking { PARKING_LANE_WIDTH } else { 0.0 };
+        f32::from(n_lanes) * lane_distance
+            + if n_lanes > 0 {
+                1.2 * lane_distance + sidewalk_width + parking_width
+            } else {
+                0.4 * lane_distance
+            }
+    }
+
+    pub fn forward_width(&self) -> N {
+        self.side_width(self.n_lanes_forward)
+    }
+
+    pub fn backward_width(&self) -> N {
+        self.side_width(self.n_lanes_backward)
+    }
+
+    /// Width of the driven part of the road only, used for intersection caps
+    pub fn forward_lanes_width(&self) -> N {
+        (f32::from(self.n_lanes_forward) + 0.4) * self.road_class.lane_distance()
+    }
+
+    pub fn backward_lanes_width(&self) -> N {
+        (f32::from(self.n_lanes_backward) + 0.4) * self.road_class.lane_distance()
+    }
+}
+
+/// The movements a lane may be used for when entering an intersection,
+/// as shown by the arrows painted on it. Inner turns include u-turns
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct LaneArrows {
+    pub inner_turn: bool,
+    pub straight: bool,
+    pub outer_turn: bool,
+}
+
+impl LaneArrows {
+    /// Lanes without any arrows get their movements assigned automatically
+    pub fn is_automatic(self) -> bool {
+        !self.inner_turn && !self.straight && !self.outer_turn
+    }
+}
+
+pub const MAX_LANE_USE_LANES: usize = 8;
+
+/// Lane-use arrows of the lanes of one direction of a road, from the inner to the outer lane
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct LaneUse(pub [LaneArrows; MAX_LANE_USE_LANES]);
+
+impl LaneUse {
+    pub fn arrows(self, lane_i: u8) -> LaneArrows {
+        self.0.get(lane_i as usize).cloned().unwrap_or_default()
+    }
+}
+
+/// A one-way ring around a central island, entered by yielding to the traffic on the ring.
+/// The first gesture point is the center, the second one lies on the ring
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoundaboutIntent {
+    #[serde(default)]
+    pub road_class: RoadClass,
+}
+
+/// Whether a lane has to give way to the lanes it conflicts with
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum RightOfWay {
+    Priority,
+    Yield,
+    /// Cars have to stop before entering the lane, then give way like on `Priority` lanes
+    Stop,
+}
+
+impl Default for RightOfWay {
+    fn default() -> Self {
+        RightOfWay::Priority
+    }
 }
 
 #[derive(Compact, Clone, Serialize, Deserialize, Debug)]
@@ -32,6 +263,7 @@ pub enum RoadPrototype {
     Lane(LanePrototype),
     SwitchLane(SwitchLanePrototype),
     Intersection(IntersectionPrototype),
+    Sidewalk(SidewalkPrototype),
     PavedArea(Area),
 }
 
@@ -48,34 +280,87 @@ impl RoadPrototype {
                 &RoadPrototype::Intersection(ref intersection_1),
                 &RoadPrototype::Intersection(ref intersection_2),
             ) => intersection_1.morphable_from(intersection_2),
+            (
+                &RoadPrototype::Sidewalk(ref sidewalk_1),
+                &RoadPrototype::Sidewalk(ref sidewalk_2),
+            ) => sidewalk_1.morphable_from(sidewalk_2),
             _ => false,
         }
     }
 }
 
 #[derive(Compact, Clone, Serialize, Deserialize, Debug)]
-pub struct LanePrototype(pub LinePath, pub CVec<bool>);
+pub struct LanePrototype(
+    pub LinePath,
+    pub CVec<SignalAspect>,
+    pub RoadClass,
+    pub RightOfWay,
+    /// Number of on-street parking spots along the lane
+    pub u16,
+);
 
 impl LanePrototype {
     pub fn morphable_from(&self, other: &LanePrototype) -> bool {
         match (self, other) {
             (
-                &LanePrototype(ref path_1, ref timings_1),
-                &LanePrototype(ref path_2, ref timings_2),
-            ) => path_1.rough_eq_by(path_2, 0.05) && timings_1[..] == timings_2[..],
+                &LanePrototype(
+                    ref path_1,
+                    ref timings_1,
+                    road_class_1,
+                    right_of_way_1,
+                    parking_spots_1,
+                ),
+                &LanePrototype(
+                    ref path_2,
+                    ref timings_2,
+                    road_class_2,
+                    right_of_way_2,
+                    parking_spots_2,
+                ),
+            ) => {
+                path_1.rough_eq_by(path_2, 0.05)
+                    && timings_1[..] == timings_2[..]
+                    && road_class_1 == road_class_2
+                    && right_of_way_1 == right_of_way_2
+                    && parking_spots_1 == parking_spots_2
+            }
         }
     }
 }
 
 #[derive(Compact, Clone, Serialize, Deserialize, Debug)]
-pub struct SwitchLanePrototype(pub LinePath);
+pub struct SwitchLanePrototype(pub LinePath, pub RoadClass);
 
 impl SwitchLanePrototype {
     pub fn morphable_from(&self, other: &SwitchLanePrototype) -> bool {
         match (self, other) {
-            (&SwitchLanePrototype(ref path_1), &SwitchLanePrototype(ref path_2)) => {
-                path_1.rough_eq_by(path_2, 0.05)
-            }
+            (
+                &SwitchLanePrototype(ref path_1, road_class_1),
+                &SwitchLanePrototype(ref path_2, road_class_2),
+            ) => path_1.rough_eq_by(path_2, 0.05) && road_class_1 == road_class_2,
+        }
+    }
+}
+
+/// Walkable path along the outer edge of a road, between two intersections
+#[derive(Compact, Clone, Serialize, Deserialize, Debug)]
+pub struct SidewalkPrototype(pub LinePath, pub SidewalkEnd, pub SidewalkEnd);
+
+/// Where pedestrians can go from the start or end of a sidewalk
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct SidewalkEnd {
+    pub level: i8,
+    /// Crossings may start here, unless the sidewalk ends next to a limited-access road
+    pub crossable: bool,
+}
+
+impl SidewalkPrototype {
+    pub fn morphable_from(&self, other: &SidewalkPrototype) -> bool {
+        match (self, other) {
+            (
+                &SidewalkPrototype(ref path_1, start_1, end_1),
+                &SidewalkPrototype(ref path_2, start_2, end_2),
+            ) => path_1.rough_eq_by(path_2, 0.05) && start_1 == start_2 && end_1 == end_2,
         }
     }
 }
@@ -92,14 +377,18 @@ pub struct ConnectionRole {
 pub struct IntersectionConnector {
     position: P2,
     direction: V2,
+    road_class: RoadClass,
+    arrows: LaneArrows,
     role: ConnectionRole,
 }
 
 impl IntersectionConnector {
-    fn new(position: P2, direction: V2) -> Self {
+    fn new(position: P2, direction: V2, road_class: RoadClass, arrows: LaneArrows) -> Self {
         IntersectionConnector {
             position,
             direction,
+            road_class,
+            arrows,
             role: ConnectionRole {
                 straight: false,
                 u_turn: false,
@@ -121,20 +410,145 @@ impl GestureSideID {
     pub fn new_backward(gesture_idx: usize) -> Self {
         GestureSideID(-((gesture_idx + 1) as i16))
     }
+
+    /// Used for the lanes of a roundabout that don't belong to any approaching road
+    pub fn roundabout_ring() -> Self {
+        GestureSideID(0)
+    }
+
+    /// `None` for the roundabout ring
+    pub fn gesture_idx(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some((self.0.abs() - 1) as usize)
+        }
+    }
+
+    pub fn is_forward(self) -> bool {
+        self.0 > 0
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
+pub struct RoundaboutRing {
+    center: P2,
+    radius: N,
+    road_class: RoadClass,
+}
+
+const ROUNDABOUT_MAX_SEGMENT_ANGLE: N = 0.15;
+
+impl RoundaboutRing {
+    /// Angle around the center, in `[0, 2π)`
+    fn angle_of(&self, point: P2) -> N {
+        let delta = point - self.center;
+        let angle = delta.y.atan2(delta.x);
+        if angle < 0.0 {
+            angle + 2.0 * ::std::f32::consts::PI
+        } else {
+            angle
+        }
+    }
+
+    fn point_at(&self, angle: N) -> P2 {
+        self.center + V2::new(angle.cos(), angle.sin()) * self.radius
+    }
+
+    /// Traffic on the ring drives counterclockwise
+    fn direction_at(&self, angle: N) -> V2 {
+        V2::new(-angle.sin(), angle.cos())
+    }
+
+    fn ring_path(&self, start_angle: N, end_angle: N) -> Option<LinePath> {
+        let n_segments = ((end_angle - start_angle) / ROUNDABOUT_MAX_SEGMENT_ANGLE)
+            .ceil()
+            .max(1.0) as usize;
+        LinePath::new(
+            (0..=n_segments)
+                .map(|i| {
+                    self.point_at(
+                        start_angle + (end_angle - start_angle) * i as N / n_segments as N,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn disk(&self, radius: N) -> Area {
+        let n_segments =
+            (2.0 * ::std::f32::consts::PI / ROUNDABOUT_MAX_SEGMENT_ANGLE).ceil() as usize;
+        // clockwise, like all other road areas
+        let points = (0..=n_segments)
+            .map(|i| {
+                let angle = -2.0 * ::std::f32::consts::PI * (i % n_segments) as N / n_segments as N;
+                self.center + V2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect::<CVec<_>>();
+        Area::new_simple(
+            ClosedLinePath::new(LinePath::new(points).expect("Roundabout disk should be valid"))
+                .expect("Roundabout disk should be closed"),
+        )
+    }
+
+    /// The area in which approaching roads are cut off and connected to the ring
+    fn approach_area(&self) -> Area {
+        self.disk(self.radius + 0.5 * self.road_class.lane_distance() + ROUNDABOUT_APPROACH_LENGTH)
+    }
+
+    fn paved_area(&self) -> Area {
+        self.disk(self.radius + 0.6 * self.road_class.lane_distance())
+    }
 }
 
 #[derive(Compact, Clone, Serialize, Deserialize, Debug)]
 pub struct IntersectionPrototype {
+    pub level: i8,
     area: Area,
+    roundabout: Option<RoundaboutRing>,
+    pub control: IntersectionControl,
     incoming: CHashMap<GestureSideID, CVec<IntersectionConnector>>,
     outgoing: CHashMap<GestureSideID, CVec<IntersectionConnector>>,
     pub connecting_lanes: CHashMap<(GestureSideID, GestureSideID), CVec<LanePrototype>>,
+    /// Connecting lane groups that are green together, only used by adaptive signals
+    pub phases: CVec<CVec<(GestureSideID, GestureSideID)>>,
 }
 
 impl IntersectionPrototype {
     pub fn morphable_from(&self, other: &IntersectionPrototype) -> bool {
         // TODO: make this better!!
         (&self.area).rough_eq_by(&other.area, 0.1)
+            && self.control.is_adaptive() == other.control.is_adaptive()
+            // a signal controller knows the lanes of each phase only from when it was built
+            && self.phases.len() == other.phases.len()
+            && self
+                .phases
+                .iter()
+                .zip(other.phases.iter())
+                .all(|(phase_1, phase_2)| phase_1[..] == phase_2[..])
+            // morphing keeps the built connecting lanes, so turn connections that
+            // were added or removed, for example by lane arrows, need a rebuild
+            && self.same_connecting_lane_paths(other)
+    }
+
+    fn connecting_lane_paths(&self) -> Vec<&LinePath> {
+        self.connecting_lanes
+            .values()
+            .flat_map(|group| group.iter().map(|&LanePrototype(ref path, ..)| path))
+            .collect()
+    }
+
+    /// Ignores timings, which morphing lanes can take over
+    fn same_connecting_lane_paths(&self, other: &IntersectionPrototype) -> bool {
+        let own_paths = self.connecting_lane_paths();
+        let other_paths = other.connecting_lane_paths();
+
+        own_paths.len() == other_paths.len()
+            && own_paths.iter().all(|own_path| {
+                other_paths
+                    .iter()
+                    .any(|other_path| own_path.rough_eq_by(*other_path, 0.05))
+            })
     }
 }
 
@@ -176,12 +590,125 @@ pub fn gesture_intent_smooth_paths(
         .collect::<Vec<_>>()
 }
 
+pub fn gesture_roundabouts(
+    history: &PlanHistory<CBGestureIntent>,
+) -> Vec<(GestureID, StepID, RoundaboutRing)> {
+    history
+        .gestures
+        .pairs()
+        .filter_map(
+            |(gesture_id, VersionedGesture(gesture, step_id))| match gesture.intent {
+                CBGestureIntent::Roundabout(roundabout_intent) if gesture.points.len() >= 2 => {
+                    Some((
+                        *gesture_id,
+                        *step_id,
+                        RoundaboutRing {
+                            center: gesture.points[0],
+                            radius: (gesture.points[1] - gesture.points[0])
+                                .norm()
+                                .max(MIN_ROUNDABOUT_RADIUS),
+                            road_class: roundabout_intent.road_class,
+                        },
+                    ))
+                }
+                _ => None,
+            },
+        )
+        .collect::<Vec<_>>()
+}
+
+pub fn gesture_intersection_controls(
+    history: &PlanHistory<CBGestureIntent>,
+) -> Vec<(GestureID, StepID, P2, IntersectionControl)> {
+    history
+        .gestures
+        .pairs()
+        .filter_map(
+            |(gesture_id, VersionedGesture(gesture, step_id))| match gesture.intent {
+                CBGestureIntent::IntersectionControl(ref control) if !gesture.points.is_empty() => {
+                    Some((*gesture_id, *step_id, gesture.points[0], control.clone()))
+                }
+                _ => None,
+            },
+        )
+        .collect::<Vec<_>>()
+}
+
+const ROAD_CAP_DEPTH: N = 15.0;
+const MAX_INTERSECTION_APPROACHES: usize = 5;
+
+pub fn validate(
+    history: &PlanHistory<CBGestureIntent>,
+    maybe_result: Option<&PlanResult<CBPrototypeKind>>,
+) -> Vec<PlanIssue> {
+    let gesture_intent_smooth_paths = gesture_intent_smooth_paths(history);
+
+    let too_short = gesture_intent_smooth_paths
+        .iter()
+        .filter(|&&(_, _, _, ref path)| path.length() < 2.0 * ROAD_CAP_DEPTH)
+        .map(|&(gesture_id, ..)| PlanIssue::new(Some(gesture_id), PlanIssueKind::TooShort));
+
+    let too_many_connections = maybe_result.into_iter().flat_map(|result| {
+        result.prototypes.values().filter_map(|prototype| {
+            if let CBPrototypeKind::Road(RoadPrototype::Intersection(ref intersection)) =
+                prototype.kind
+            {
+                if intersection.roundabout.is_some() {
+                    return None;
+                }
+
+                // each approach brings the lanes of one side in and takes those of the other
+                // side out, so it is identified by its gesture and on which side of the
+                // intersection it lies along the gesture
+                let mut approaches = intersection
+                    .incoming
+                    .keys()
+                    .map(|side_id| (*side_id, side_id.is_forward()))
+                    .chain(
+                        intersection
+                            .outgoing
+                            .keys()
+                            .map(|side_id| (*side_id, !side_id.is_forward())),
+                    )
+                    .filter_map(|(side_id, on_start_side)| {
+                        side_id.gesture_idx().map(|idx| (idx, on_start_side))
+                    })
+                    .collect::<Vec<_>>();
+                approaches.sort();
+                approaches.dedup();
+
+                let mut approaching_gestures = approaches
+                    .iter()
+                    .filter_map(|&(idx, _)| gesture_intent_smooth_paths.get(idx))
+                    .map(|&(gesture_id, ..)| gesture_id)
+                    .collect::<Vec<_>>();
+                approaching_gestures.sort_by_key(|gesture_id| gesture_id.0);
+                approaching_gestures.dedup();
+
+                if approaches.len() > MAX_INTERSECTION_APPROACHES {
+                    Some(PlanIssue::new(
+                        approaching_gestures,
+                        PlanIssueKind::TooManyConnections,
+                    ))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+    });
+
+    too_short.chain(too_many_connections).collect()
+}
+
 #[allow(clippy::cognitive_complexity)]
 pub fn calculate_prototypes(
     history: &PlanHistory<CBGestureIntent>,
     _current_result: &PlanResult<CBPrototypeKind>,
 ) -> Result<Vec<Prototype<CBPrototypeKind>>, AreaError> {
     let gesture_intent_smooth_paths = gesture_intent_smooth_paths(history);
+    let roundabouts = gesture_roundabouts(history);
 
     let gesture_areas_for_intersection = gesture_intent_smooth_paths
         .iter()
@@ -189,18 +716,8 @@ pub fn calculate_prototypes(
             (
                 Band::new_asymmetric(
                     path.clone(),
-                    f32::from(road_intent.n_lanes_backward) * LANE_DISTANCE
-                        + if road_intent.n_lanes_backward > 0 {
-                            1.2 * LANE_DISTANCE
-                        } else {
-                            0.4 * LANE_DISTANCE
-                        },
-                    f32::from(road_intent.n_lanes_forward) * LANE_DISTANCE
-                        + if road_intent.n_lanes_forward > 0 {
-                            1.2 * LANE_DISTANCE
-                        } else {
-                            0.4 * LANE_DISTANCE
-                        },
+                    road_intent.backward_width(),
+                    road_intent.forward_width(),
                 )
                 .as_area(),
                 gesture_id,
@@ -209,41 +726,38 @@ pub fn calculate_prototypes(
         })
         .collect::<Vec<_>>();
 
-    let mut road_intersection_embedding = AreaEmbedding::new(15.0);
-
     #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
     enum RoadPart {
         StartCap,
         Road,
         EndCap,
-    }
-
-    for (gesture_area, gesture_id, step_id) in &gesture_areas_for_intersection {
-        road_intersection_embedding.insert(
-            gesture_area.clone(),
-            (*gesture_id, *step_id, RoadPart::Road),
-        );
+        Roundabout,
     }
 
     // add intersections at the starts and ends of gestures
-    const ROAD_CAP_DEPTH: N = 15.0;
-
-    let road_caps = gesture_intent_smooth_paths.iter().flat_map(
-        |&(gesture_id, step_id, road_intent, ref path)| {
+    let road_caps = gesture_intent_smooth_paths
+        .iter()
+        .flat_map(|&(gesture_id, step_id, road_intent, ref path)| {
             [
-                (path.start(), path.start_direction(), RoadPart::StartCap),
-                (path.end(), path.end_direction(), RoadPart::EndCap),
+                (
+                    path.start(),
+                    path.start_direction(),
+                    RoadPart::StartCap,
+                    road_intent.level.start_level(),
+                ),
+                (
+                    path.end(),
+                    path.end_direction(),
+                    RoadPart::EndCap,
+                    road_intent.level.end_level(),
+                ),
             ]
             .iter()
-            .map(|&(point, direction, role)| {
+            .map(|&(point, direction, role, level)| {
                 let orthogonal = direction.orthogonal_right();
                 let half_depth = direction * ROAD_CAP_DEPTH / 2.0;
-                let width_backward = orthogonal
-                    * (f32::from(road_intent.n_lanes_backward) * LANE_DISTANCE
-                        + 0.4 * LANE_DISTANCE);
-                let width_forward = orthogonal
-                    * (f32::from(road_intent.n_lanes_forward) * LANE_DISTANCE
-                        + 0.4 * LANE_DISTANCE);
+                let width_backward = orthogonal * road_intent.backward_lanes_width();
+                let width_forward = orthogonal * road_intent.forward_lanes_width();
                 (
                     Area::new_simple(
                         ClosedLinePath::new(
@@ -262,43 +776,117 @@ pub fn calculate_prototypes(
                         .expect("End intersection path should be closed"),
                     ),
                     (gesture_id, step_id, role),
+                    level,
                 )
             })
             .collect::<Vec<_>>()
-        },
-    );
+        })
+        .collect::<Vec<_>>();
 
-    for (road_cap_area, road_cap_label) in road_caps {
-        road_intersection_embedding.insert(road_cap_area, road_cap_label);
+    let mut levels = gesture_intent_smooth_paths
+        .iter()
+        .flat_map(|&(_, _, road_intent, _)| {
+            vec![road_intent.level.start_level(), road_intent.level.end_level()]
+        })
+        .collect::<::std::collections::BTreeSet<_>>();
+
+    // roundabouts are always on the ground
+    if !roundabouts.is_empty() {
+        levels.insert(0);
     }
 
-    let mut intersection_prototypes: Vec<_> = road_intersection_embedding
-        .view(AreaFilter::Function(Box::new(|labels| labels.len() >= 2)))
-        .get_areas_with_pieces()?
-        .into_iter()
-        .map(|(area, pieces)| {
-            let mut influenced_id = PrototypeID::from_influences(
-                pieces
-                    .iter()
-                    .map(|(_piece, label)| label.own_right_label)
-                    .collect::<Vec<_>>(),
-            );
-            influenced_id = influenced_id.add_influences(vec![
-                pieces[0].0.start().x.to_bits(),
-                pieces[0].0.start().y.to_bits(),
-            ]);
-            Prototype {
-                representative_position: area.primitives[0].boundary.path().points[0],
-                kind: CBPrototypeKind::Road(RoadPrototype::Intersection(IntersectionPrototype {
-                    area,
-                    incoming: CHashMap::new(),
-                    outgoing: CHashMap::new(),
-                    connecting_lanes: CHashMap::new(),
-                })),
-                id: influenced_id,
+    let mut intersection_prototypes = Vec::new();
+
+    // roads only intersect with roads on the same level,
+    // ramps only with the roads at their start and end
+    for level in levels {
+        let mut road_intersection_embedding = AreaEmbedding::new(15.0);
+
+        for ((gesture_area, gesture_id, step_id), &(_, _, road_intent, _)) in
+            gesture_areas_for_intersection
+                .iter()
+                .zip(&gesture_intent_smooth_paths)
+        {
+            if road_intent.level.body_level() == Some(level) {
+                road_intersection_embedding.insert(
+                    gesture_area.clone(),
+                    (*gesture_id, *step_id, RoadPart::Road),
+                );
             }
-        })
-        .collect();
+        }
+
+        for &(ref road_cap_area, road_cap_label, road_cap_level) in &road_caps {
+            if road_cap_level == level {
+                road_intersection_embedding.insert(road_cap_area.clone(), road_cap_label);
+            }
+        }
+
+        // roads meeting within a roundabout are connected by the roundabout instead
+        if level == 0 {
+            for &(gesture_id, step_id, ring) in &roundabouts {
+                road_intersection_embedding.insert(
+                    ring.approach_area(),
+                    (gesture_id, step_id, RoadPart::Roundabout),
+                );
+            }
+        }
+
+        intersection_prototypes.extend(
+            road_intersection_embedding
+                .view(AreaFilter::Function(Box::new(|labels| {
+                    labels.len() >= 2
+                        && !labels.iter().any(|label| label.2 == RoadPart::Roundabout)
+                })))
+                .get_areas_with_pieces()?
+                .into_iter()
+                .map(|(area, pieces)| {
+                    let mut influenced_id = PrototypeID::from_influences(
+                        pieces
+                            .iter()
+                            .map(|(_piece, label)| label.own_right_label)
+                            .collect::<Vec<_>>(),
+                    );
+                    influenced_id = influenced_id.add_influences(vec![
+                        pieces[0].0.start().x.to_bits(),
+                        pieces[0].0.start().y.to_bits(),
+                    ]);
+                    influenced_id = influenced_id.add_influences(level);
+                    Prototype {
+                        representative_position: area.primitives[0].boundary.path().points[0],
+                        kind: CBPrototypeKind::Road(RoadPrototype::Intersection(
+                            IntersectionPrototype {
+                                level,
+                                area,
+                                roundabout: None,
+                                control: IntersectionControl::default(),
+                                incoming: CHashMap::new(),
+                                outgoing: CHashMap::new(),
+                                connecting_lanes: CHashMap::new(),
+                                phases: CVec::new(),
+                            },
+                        )),
+                        id: influenced_id,
+                    }
+                }),
+        );
+    }
+
+    intersection_prototypes.extend(roundabouts.iter().map(|&(gesture_id, step_id, ring)| {
+        Prototype {
+            representative_position: ring.center,
+            kind: CBPrototypeKind::Road(RoadPrototype::Intersection(IntersectionPrototype {
+                level: 0,
+                area: ring.approach_area(),
+                roundabout: Some(ring),
+                control: IntersectionControl::Unsignalled,
+                incoming: CHashMap::new(),
+                outgoing: CHashMap::new(),
+                connecting_lanes: CHashMap::new(),
+                phases: CVec::new(),
+            })),
+            id: PrototypeID::from_influences((gesture_id, step_id)).add_influences("ring"),
+        }
+    }));
 
     let intersected_lane_paths = {
         let raw_lane_paths = gesture_intent_smooth_paths
@@ -306,20 +894,25 @@ pub fn calculate_prototypes(
             .enumerate()
             .flat_map(
                 |(gesture_i, &(gesture_id, step_id, road_intent, ref path))| {
+                    let lane_distance = road_intent.road_class.lane_distance();
                     (0..road_intent.n_lanes_forward)
                         .map(|lane_i| {
                             (
-                                CENTER_LANE_DISTANCE / 2.0 + f32::from(lane_i) * LANE_DISTANCE,
+                                CENTER_LANE_DISTANCE / 2.0 + f32::from(lane_i) * lane_distance,
                                 lane_i as i8 + 1,
+                                road_intent.lane_use_forward.arrows(lane_i),
+                                road_intent.parking && lane_i + 1 == road_intent.n_lanes_forward,
                             )
                         })
                         .chain((0..road_intent.n_lanes_backward).map(|lane_i| {
                             (
-                                -(CENTER_LANE_DISTANCE / 2.0 + f32::from(lane_i) * LANE_DISTANCE),
+                                -(CENTER_LANE_DISTANCE / 2.0 + f32::from(lane_i) * lane_distance),
                                 -(lane_i as i8) - 1,
+                                road_intent.lane_use_backward.arrows(lane_i),
+                                road_intent.parking && lane_i + 1 == road_intent.n_lanes_backward,
                             )
                         }))
-                        .filter_map(|(offset, offset_i)| {
+                        .filter_map(|(offset, offset_i, arrows, parking)| {
                             path.shift_orthogonally(offset).map(|path| {
                                 (
                                     if offset < 0.0 {
@@ -329,6 +922,14 @@ pub fn calculate_prototypes(
                                     },
                                     PrototypeID::from_influences((gesture_id, step_id, offset_i)),
                                     if offset < 0.0 { path.reverse() } else { path },
+                                    road_intent.road_class,
+                                    if offset < 0.0 {
+                                        road_intent.level.reversed()
+                                    } else {
+                                        road_intent.level
+                                    },
+                                    arrows,
+                                    parking,
                                 )
                             })
                         })
@@ -339,110 +940,248 @@ pub fn calculate_prototypes(
 
         raw_lane_paths
             .into_iter()
-            .flat_map(|(gesture_side_id, lane_influence_id, raw_lane_path)| {
-                let mut start_trim = 0.0f32;
-                let mut start_influence = lane_influence_id;
-                let mut end_trim = raw_lane_path.length();
-                let mut end_influence = lane_influence_id;
-                let mut cuts = Vec::new();
-
-                use ::planning::CBPrototypeKind::Road;
-
-                for prototype in &mut intersection_prototypes {
-                    if let Prototype {
-                        id: intersection_id,
-                        kind: Road(RoadPrototype::Intersection(ref mut intersection)),
-                        ..
-                    } = prototype
-                    {
-                        let points = (
-                            &raw_lane_path,
-                            intersection.area.primitives[0].boundary.path(),
-                        )
-                            .intersect();
+            .flat_map(
+                |(
+                    gesture_side_id,
+                    lane_influence_id,
+                    raw_lane_path,
+                    road_class,
+                    road_level,
+                    arrows,
+                    parking,
+                )| {
+                    let mut start_trim = 0.0f32;
+                    let mut start_influence = lane_influence_id;
+                    let mut end_trim = raw_lane_path.length();
+                    let mut end_influence = lane_influence_id;
+                    let mut cuts = Vec::new();
 
-                        if points.len() >= 2 {
-                            let entry_distance = points
-                                .iter()
-                                .map(|p| OrderedFloat(p.along_a))
-                                .min()
-                                .unwrap();
-                            let exit_distance = points
-                                .iter()
-                                .map(|p| OrderedFloat(p.along_a))
-                                .max()
-                                .unwrap();
-                            intersection.incoming.push_at(
-                                gesture_side_id,
-                                IntersectionConnector::new(
-                                    raw_lane_path.along(*entry_distance),
-                                    raw_lane_path.direction_along(*entry_distance),
-                                ),
-                            );
-                            intersection.outgoing.push_at(
-                                gesture_side_id,
-                                IntersectionConnector::new(
-                                    raw_lane_path.along(*exit_distance),
-                                    raw_lane_path.direction_along(*exit_distance),
-                                ),
-                            );
-                            cuts.push((*entry_distance, *exit_distance, *intersection_id));
-                        } else if points.len() == 1 {
-                            if intersection.area.contains(raw_lane_path.start()) {
-                                let exit_distance = points[0].along_a;
-                                intersection.outgoing.push_at(
+                    use ::planning::CBPrototypeKind::Road;
+
+                    for prototype in &mut intersection_prototypes {
+                        if let Prototype {
+                            id: intersection_id,
+                            kind: Road(RoadPrototype::Intersection(ref mut intersection)),
+                            ..
+                        } = prototype
+                        {
+                            let on_same_level = match road_level.body_level() {
+                                Some(level) => intersection.level == level,
+                                None => {
+                                    (intersection.level == road_level.start_level()
+                                        && intersection.area.contains(raw_lane_path.start()))
+                                        || (intersection.level == road_level.end_level()
+                                            && intersection.area.contains(raw_lane_path.end()))
+                                }
+                            };
+
+                            if !on_same_level {
+                                continue;
+                            }
+
+                            let points = (
+                                &raw_lane_path,
+                                intersection.area.primitives[0].boundary.path(),
+                            )
+                                .intersect();
+
+                            if points.len() >= 2 {
+                                let entry_distance = points
+                                    .iter()
+                                    .map(|p| OrderedFloat(p.along_a))
+                                    .min()
+                                    .unwrap();
+                                let exit_distance = points
+                                    .iter()
+                                    .map(|p| OrderedFloat(p.along_a))
+                                    .max()
+                                    .unwrap();
+                                // the road only passes through, its arrows are for where it ends
+                                intersection.incoming.push_at(
                                     gesture_side_id,
                                     IntersectionConnector::new(
-                                        raw_lane_path.along(exit_distance),
-                                        raw_lane_path.direction_along(exit_distance),
+                                        raw_lane_path.along(*entry_distance),
+                                        raw_lane_path.direction_along(*entry_distance),
+                                        road_class,
+                                        LaneArrows::default(),
                                     ),
                                 );
-                                if exit_distance > start_trim {
-                                    start_trim = exit_distance;
-                                    start_influence = *intersection_id;
-                                }
-                            } else if intersection.area.contains(raw_lane_path.end()) {
-                                let entry_distance = points[0].along_a;
-                                intersection.incoming.push_at(
+                                intersection.outgoing.push_at(
                                     gesture_side_id,
                                     IntersectionConnector::new(
-                                        raw_lane_path.along(entry_distance),
-                                        raw_lane_path.direction_along(entry_distance),
+                                        raw_lane_path.along(*exit_distance),
+                                        raw_lane_path.direction_along(*exit_distance),
+                                        road_class,
+                                        LaneArrows::default(),
                                     ),
                                 );
-                                if entry_distance < end_trim {
-                                    end_trim = entry_distance;
-                                    end_influence = *intersection_id;
+                                cuts.push((*entry_distance, *exit_distance, *intersection_id));
+                            } else if points.len() == 1 {
+                                if intersection.area.contains(raw_lane_path.start()) {
+                                    let exit_distance = points[0].along_a;
+                                    intersection.outgoing.push_at(
+                                        gesture_side_id,
+                                        IntersectionConnector::new(
+                                            raw_lane_path.along(exit_distance),
+                                            raw_lane_path.direction_along(exit_distance),
+                                            road_class,
+                                            LaneArrows::default(),
+                                        ),
+                                    );
+                                    if exit_distance > start_trim {
+                                        start_trim = exit_distance;
+                                        start_influence = *intersection_id;
+                                    }
+                                } else if intersection.area.contains(raw_lane_path.end()) {
+                                    let entry_distance = points[0].along_a;
+                                    intersection.incoming.push_at(
+                                        gesture_side_id,
+                                        IntersectionConnector::new(
+                                            raw_lane_path.along(entry_distance),
+                                            raw_lane_path.direction_along(entry_distance),
+                                            road_class,
+                                            arrows,
+                                        ),
+                                    );
+                                    if entry_distance < end_trim {
+                                        end_trim = entry_distance;
+                                        end_influence = *intersection_id;
+                                    }
                                 }
                             }
+                        } else {
+                            unreachable!()
                         }
-                    } else {
-                        unreachable!()
                     }
-                }
 
-                cuts.sort_by(|a, b| OrderedFloat(a.0).cmp(&OrderedFloat(b.0)));
-
-                cuts.insert(0, (-1.0, start_trim, start_influence));
-                cuts.push((end_trim, raw_lane_path.length() + 1.0, end_influence));
-
-                cuts.windows(2)
-                    .filter_map(|two_cuts| {
-                        let (
-                            (_, exit_distance, exit_influence),
-                            (entry_distance, _, entry_influence),
-                        ) = (two_cuts[0], two_cuts[1]);
-                        let subsection_id =
-                            lane_influence_id.add_influences((exit_influence, entry_influence));
-                        raw_lane_path
-                            .subsection(exit_distance, entry_distance)
-                            .map(|subsection| (subsection, subsection_id))
-                    })
-                    .collect::<Vec<_>>()
-            })
+                    cuts.sort_by(|a, b| OrderedFloat(a.0).cmp(&OrderedFloat(b.0)));
+
+                    cuts.insert(0, (-1.0, start_trim, start_influence));
+                    cuts.push((end_trim, raw_lane_path.length() + 1.0, end_influence));
+
+                    cuts.windows(2)
+                        .filter_map(|two_cuts| {
+                            let (
+                                (_, exit_distance, exit_influence),
+                                (entry_distance, _, entry_influence),
+                            ) = (two_cuts[0], two_cuts[1]);
+                            let subsection_id =
+                                lane_influence_id.add_influences((exit_influence, entry_influence));
+                            raw_lane_path
+                                .subsection(exit_distance, entry_distance)
+                                .map(|subsection| {
+                                    (subsection, subsection_id, road_class, parking)
+                                })
+                        })
+                        .collect::<Vec<_>>()
+                },
+            )
             .collect::<Vec<_>>()
     };
 
+    // sidewalks run along the outer edge of each side that has lanes
+    // and are interrupted wherever the road meets an intersection
+    let sidewalks = gesture_intent_smooth_paths
+        .iter()
+        .filter(|&&(_, _, road_intent, _)| road_intent.road_class.has_sidewalks())
+        .flat_map(|&(gesture_id, step_id, road_intent, ref path)| {
+            [
+                (road_intent.n_lanes_forward, road_intent.forward_width(), 1i8),
+                (road_intent.n_lanes_backward, -road_intent.backward_width(), -1i8),
+            ]
+            .iter()
+            .filter(|&&(n_lanes, _, _)| n_lanes > 0)
+            .filter_map(|&(_, width, side)| {
+                let offset = width - width.signum() * SIDEWALK_WIDTH / 2.0;
+                path.shift_orthogonally(offset).map(|raw_path| {
+                    (
+                        raw_path,
+                        PrototypeID::from_influences((gesture_id, step_id, "sidewalk", side)),
+                        road_intent.level,
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+        })
+        .flat_map(|(raw_path, sidewalk_influence_id, road_level)| {
+            // with the level of each intersection and whether it joins a limited-access road
+            let intersection_areas = intersection_prototypes
+                .iter()
+                .filter_map(|prototype| {
+                    if let CBPrototypeKind::Road(RoadPrototype::Intersection(ref intersection)) =
+                        prototype.kind
+                    {
+                        if intersection.level == road_level.start_level()
+                            || intersection.level == road_level.end_level()
+                        {
+                            let joins_limited_access = intersection
+                                .incoming
+                                .values()
+                                .chain(intersection.outgoing.values())
+                                .flat_map(|group| group.iter())
+                                .any(|connector| connector.road_class.is_limited_access());
+                            return Some((
+                                &intersection.area,
+                                intersection.level,
+                                joins_limited_access,
+                            ));
+                        }
+                    }
+                    None
+                })
+                .collect::<Vec<_>>();
+
+            let raw_end = |level| SidewalkEnd {
+                level,
+                crossable: true,
+            };
+            let mut cuts = vec![
+                (0.0, raw_end(road_level.start_level())),
+                (raw_path.length(), raw_end(road_level.end_level())),
+            ];
+            for &(area, level, joins_limited_access) in &intersection_areas {
+                cuts.extend(
+                    (&raw_path, area.primitives[0].boundary.path())
+                        .intersect()
+                        .iter()
+                        .map(|point| {
+                            (
+                                point.along_a,
+                                SidewalkEnd {
+                                    level,
+                                    crossable: !joins_limited_access,
+                                },
+                            )
+                        }),
+                );
+            }
+            cuts.sort_by_key(|&(distance, _)| OrderedFloat(distance));
+
+            cuts.windows(2)
+                .enumerate()
+                .filter_map(|(piece_i, two_cuts)| {
+                    let ((start_distance, start), (end_distance, end)) = (two_cuts[0], two_cuts[1]);
+                    raw_path
+                        .subsection(start_distance, end_distance)
+                        .and_then(|piece| {
+                            let midpoint = piece.along(piece.length() / 2.0);
+                            if intersection_areas
+                                .iter()
+                                .any(|&(area, ..)| area.contains(midpoint))
+                            {
+                                None
+                            } else {
+                                Some((
+                                    SidewalkPrototype(piece, start, end),
+                                    sidewalk_influence_id.add_influences(piece_i),
+                                ))
+                            }
+                        })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
     let switch_lane_paths = {
         #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
         enum SwitchLaneLabel {
@@ -452,26 +1191,39 @@ pub fn calculate_prototypes(
 
         let mut switch_lane_embedding = AreaEmbedding::new(30.0);
 
-        let right_lane_bands = intersected_lane_paths.iter().filter_map(|(path, id)| {
-            path.shift_orthogonally(0.5 * LANE_DISTANCE + 0.5 * SWITCHING_LANE_OVERLAP_TOLERANCE)
+        let lane_road_classes = intersected_lane_paths
+            .iter()
+            .map(|&(_, id, road_class, _)| (id, road_class))
+            .collect::<::std::collections::HashMap<_, _>>();
+
+        let right_lane_bands = intersected_lane_paths
+            .iter()
+            .filter_map(|(path, id, road_class, _)| {
+                path.shift_orthogonally(
+                    0.5 * road_class.lane_distance() + 0.5 * SWITCHING_LANE_OVERLAP_TOLERANCE,
+                )
                 .map(|right_path| {
                     let band =
                         Band::new(right_path.clone(), SWITCHING_LANE_OVERLAP_TOLERANCE * 2.0);
                     (band.as_area(), *id)
                 })
-        });
+            });
 
         for (band_area, id) in right_lane_bands {
             switch_lane_embedding.insert(band_area, SwitchLaneLabel::Right(id))
         }
 
-        let left_lane_bands = intersected_lane_paths.iter().filter_map(|(path, id)| {
-            path.shift_orthogonally(-0.5 * LANE_DISTANCE - 0.5 * SWITCHING_LANE_OVERLAP_TOLERANCE)
+        let left_lane_bands = intersected_lane_paths
+            .iter()
+            .filter_map(|(path, id, road_class, _)| {
+                path.shift_orthogonally(
+                    -0.5 * road_class.lane_distance() - 0.5 * SWITCHING_LANE_OVERLAP_TOLERANCE,
+                )
                 .map(|left_path| {
                     let band = Band::new(left_path.clone(), SWITCHING_LANE_OVERLAP_TOLERANCE * 2.0);
                     (band.as_area(), *id)
                 })
-        });
+            });
 
         for (band_area, id) in left_lane_bands {
             switch_lane_embedding.insert(band_area, SwitchLaneLabel::Left(id))
@@ -507,7 +1259,9 @@ pub fn calculate_prototypes(
                         );
                         influenced_id = influenced_id.add_influences(piece.points[0].x.to_bits());
                         influenced_id = influenced_id.add_influences(piece.points[0].y.to_bits());
-                        Some((piece, influenced_id))
+                        let road_class =
+                            lane_road_classes.get(&own_id).cloned().unwrap_or_default();
+                        Some((piece, influenced_id, road_class))
                     } else {
                         None
                     }
@@ -517,10 +1271,23 @@ pub fn calculate_prototypes(
             })
     };
 
+    let intersection_controls = gesture_intersection_controls(history);
+
     for prototype in &mut intersection_prototypes {
         if let CBPrototypeKind::Road(RoadPrototype::Intersection(ref mut intersection)) =
             prototype.kind
         {
+            // only changes the id of the intersection itself, so changing the control
+            // morphs its existing lanes instead of rebuilding it and its surroundings
+            if intersection.roundabout.is_none() {
+                for &(gesture_id, step_id, position, ref control) in &intersection_controls {
+                    if intersection.area.contains(position) {
+                        intersection.control = control.clone();
+                        prototype.id = prototype.id.add_influences((gesture_id, step_id));
+                    }
+                }
+            }
+
             intersection_connections::create_connecting_lanes(intersection);
         } else {
             unreachable!()
@@ -532,18 +1299,35 @@ pub fn calculate_prototypes(
         .chain(
             intersected_lane_paths
                 .into_iter()
-                .map(|(path, id)| Prototype {
-                    representative_position: path.points[0],
-                    kind: CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(
-                        path,
-                        CVec::new(),
-                    ))),
-                    id,
+                .map(|(path, id, road_class, parking)| {
+                    let parking_spots = if parking {
+                        (path.length() / PARKING_SPOT_LENGTH) as u16
+                    } else {
+                        0
+                    };
+                    Prototype {
+                        representative_position: path.points[0],
+                        kind: CBPrototypeKind::Road(RoadPrototype::Lane(LanePrototype(
+                            path,
+                            CVec::new(),
+                            road_class,
+                            RightOfWay::Priority,
+                            parking_spots,
+                        ))),
+                        id,
+                    }
                 }),
         )
-        .chain(switch_lane_paths.map(|(path, id)| Prototype {
+        .chain(switch_lane_paths.map(|(path, id, road_class)| Prototype {
             representative_position: path.points[0],
-            kind: CBPrototypeKind::Road(RoadPrototype::SwitchLane(SwitchLanePrototype(path))),
+            kind: CBPrototypeKind::Road(RoadPrototype::SwitchLane(SwitchLanePrototype(
+                path, road_class,
+            ))),
+            id,
+        }))
+        .chain(sidewalks.into_iter().map(|(sidewalk, id)| Prototype {
+            representative_position: sidewalk.0.points[0],
+            kind: CBPrototypeKind::Road(RoadPrototype::Sidewalk(sidewalk)),
             id,
         }))
         .chain(
@@ -555,5 +1339,14 @@ pub fn calculate_prototypes(
                     id: PrototypeID::from_influences((gesture_id, step_id)),
                 }),
         )
+        .chain(
+            roundabouts
+                .into_iter()
+                .map(|(gesture_id, step_id, ring)| Prototype {
+                    representative_position: ring.center,
+                    kind: CBPrototypeKind::Road(RoadPrototype::PavedArea(ring.paved_area())),
+                    id: PrototypeID::from_influences((gesture_id, step_id)),
+                }),
+        )
         .collect())
 }
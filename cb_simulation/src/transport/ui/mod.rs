@@ -3,6 +3,7 @@ use compact::CVec;
 use kay::{ActorSystem, World, TypedID, RawID};
 use michelangelo::Mesh;
 use super::lane::{Lane, LaneID, SwitchLane, SwitchLaneID};
+use super::microtraffic::VehicleType;
 use transport::pathfinding::trip::TripID;
 
 use dimensions::{LANE_DISTANCE, LANE_WIDTH, LANE_MARKER_WIDTH, LANE_MARKER_DASH_GAP,
@@ -12,9 +13,11 @@ use itertools::Itertools;
 
 #[derive(Compact, Clone)]
 pub struct CarRenderInfo {
+    /// Of the middle of the vehicle
     pub position: [f32; 2],
     pub direction: [f32; 2],
     pub trip: TripID,
+    pub vehicle_type: VehicleType,
 }
 
 pub trait TransportUI {
@@ -45,12 +48,15 @@ impl Lane {
             for car in
                 cars_iter.take_while_ref(|car| *car.position - distance_pair[0] < segment.length())
             {
-                let position2d = segment.along(*car.position - distance_pair[0]);
+                // car positions are at their rear end
+                let half_length = car.vehicle_type.parameters().length / 2.0;
+                let position2d = segment.along(*car.position + half_length - distance_pair[0]);
                 let direction = segment.direction();
                 car_infos.push(CarRenderInfo {
                     position: [position2d.x, position2d.y],
                     direction: [direction.x, direction.y],
                     trip: car.trip,
+                    vehicle_type: car.vehicle_type,
                 })
             }
         }
@@ -125,7 +131,8 @@ impl SwitchLane {
             for car in
                 cars_iter.take_while_ref(|car| *car.position - distance_pair[0] < segment.length())
             {
-                let position2d = segment.along(*car.position - distance_pair[0]);
+                let half_length = car.vehicle_type.parameters().length / 2.0;
+                let position2d = segment.along(*car.position + half_length - distance_pair[0]);
                 let direction = segment.direction();
                 let rotated_direction = (direction
                     + 0.3 * car.switch_velocity * direction.orthogonal_right())
@@ -136,6 +143,7 @@ impl SwitchLane {
                     position: [shifted_position2d.x, shifted_position2d.y],
                     direction: [rotated_direction.x, rotated_direction.y],
                     trip: car.trip,
+                    vehicle_type: car.vehicle_type,
                 })
             }
         }